@@ -1,7 +1,7 @@
 mod registered_modules;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use figment::Figment;
 use mimalloc::MiMalloc;
 use modkit_bootstrap::{AppConfig, AppConfigProvider, CliArgs, ConfigProvider};
@@ -71,6 +71,34 @@ enum Commands {
     Run,
     /// Validate configuration and exit
     Check,
+    /// Inspect the compiled-in module registry
+    Modules {
+        #[command(subcommand)]
+        command: ModulesCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ModulesCommand {
+    /// List modules with their dependencies, capabilities and phase order
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    /// Print the module dependency graph
+    Graph {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Dot)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+    Dot,
 }
 
 #[tokio::main]
@@ -79,6 +107,11 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Registry introspection needs neither config nor logging and must keep stdout clean.
+    if let Some(Commands::Modules { command }) = &cli.command {
+        return print_modules(command);
+    }
+
     if let Some(ref path) = cli.config {
         let path_str = path.to_string_lossy();
         if !Path::new(path).is_file() {
@@ -143,6 +176,7 @@ async fn main() -> Result<()> {
     match cli.command.unwrap_or(Commands::Run) {
         Commands::Run => run_server(config, args).await,
         Commands::Check => check_config(config).await,
+        Commands::Modules { command } => print_modules(&command),
     }
 }
 
@@ -205,6 +239,24 @@ async fn check_config(config: AppConfig) -> Result<()> {
     Ok(())
 }
 
+/// Build the module registry exactly as the runtime does and render it.
+///
+/// Missing dependencies and cycles surface as the same `RegistryError` the runtime reports.
+fn print_modules(command: &ModulesCommand) -> Result<()> {
+    let registry = modkit::registry::ModuleRegistry::discover_and_build()?;
+    let graph = modkit::registry::ModuleGraph::from_registry(&registry);
+
+    let format = match command {
+        ModulesCommand::List { format } | ModulesCommand::Graph { format } => *format,
+    };
+    match format {
+        OutputFormat::Table => print!("{}", graph.to_table()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&graph)?),
+        OutputFormat::Dot => print!("{}", graph.to_dot()),
+    }
+    Ok(())
+}
+
 /// Create a Figment from the loaded AppConfig for use with DbManager.
 fn create_figment_from_config(config: &AppConfig) -> Result<Figment> {
     use figment::providers::Serialized;
@@ -223,7 +275,8 @@ fn create_mock_figment(config: &AppConfig) -> Figment {
     // Create a mock configuration where all modules get in-memory SQLite
     let mut mock_config = config.clone();
 
-    // Override all module database configurations to use in-memory SQLite
+    // Override all module database configurations to use in-memory SQLite. Every connection
+    // to `sqlite::memory:` opens a database of its own, so the pool is held to one.
     for module_value in mock_config.modules.values_mut() {
         if let Some(obj) = module_value.as_object_mut() {
             obj.insert(
@@ -232,6 +285,9 @@ fn create_mock_figment(config: &AppConfig) -> Figment {
                     "dsn": "sqlite::memory:",
                     "params": {
                         "journal_mode": "WAL"
+                    },
+                    "pool": {
+                        "max_conns": 1
                     }
                 }),
             );
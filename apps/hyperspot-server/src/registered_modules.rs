@@ -7,5 +7,6 @@ use api_ingress as _;
 use directory_service as _;
 use file_parser as _;
 use grpc_hub as _;
+use scheduler as _;
 #[cfg(feature = "users-info-example")]
 use users_info as _;
@@ -404,6 +404,46 @@ fn test_cli_subcommand_help() {
     );
 }
 
+#[test]
+fn test_cli_modules_list_json() {
+    let output = run_hyperspot_server(&["modules", "list", "--format", "json"]);
+
+    assert!(
+        output.status.success(),
+        "modules list should succeed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let graph: serde_json::Value =
+        serde_json::from_str(&stdout).expect("modules list --format json should print JSON");
+
+    let modules = graph["modules"].as_array().expect("modules array");
+    let ingress = modules
+        .iter()
+        .find(|m| m["name"] == "api_ingress")
+        .expect("api_ingress should be registered");
+    let caps = ingress["capabilities"].as_array().unwrap();
+    assert!(caps.iter().any(|c| c == "rest_host"));
+
+    let phases = graph["phases"].as_array().expect("phases array");
+    assert!(phases.iter().any(|p| p["phase"] == "init"));
+}
+
+#[test]
+fn test_cli_modules_graph_dot() {
+    let output = run_hyperspot_server(&["modules", "graph"]);
+
+    assert!(output.status.success(), "modules graph should succeed");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("digraph modules {"),
+        "Default graph output should be DOT: {}",
+        stdout
+    );
+}
+
 #[test]
 fn test_cli_config_precedence() {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
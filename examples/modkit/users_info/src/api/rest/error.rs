@@ -36,6 +36,16 @@ pub fn domain_error_to_problem(e: DomainError, instance: &str) -> Problem {
                 trace_id,
             )
         }
+        DomainError::ConcurrentModification { id } => {
+            modkit::http_errors::ErrorCode::modkit_errors_precondition_failed_v1().with_context(
+                format!(
+                    "User with id {} was modified concurrently; fetch it and retry",
+                    id
+                ),
+                instance,
+                trace_id,
+            )
+        }
         DomainError::Database { .. } => {
             // Log the internal error details but don't expose them to the client
             tracing::error!(error = ?e, "Database error occurred");
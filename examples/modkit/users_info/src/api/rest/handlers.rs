@@ -4,8 +4,10 @@ use uuid::Uuid;
 
 use crate::api::rest::dto::{CreateUserReq, UpdateUserReq, UserDto, UserEvent};
 
+use axum::response::Response;
 use modkit::api::odata::OData;
 use modkit::api::prelude::*;
+use modkit::api::{conditional_json, with_etag, ETag, Preconditions};
 
 use crate::domain::service::Service;
 use modkit::SseBroadcaster;
@@ -57,7 +59,8 @@ pub async fn get_user(
     Authz(ctx): Authz,
     Extension(svc): Extension<std::sync::Arc<Service>>,
     Path(id): Path<Uuid>,
-) -> UsersResult<JsonBody<UserDto>> {
+    pre: Preconditions,
+) -> UsersResult<Response> {
     info!(
         user_id = %id,
         requester_id = %ctx.subject_id(),
@@ -68,7 +71,8 @@ pub async fn get_user(
         .get_user(&ctx, id)
         .await
         .map_err(UsersApiError::from_domain)?;
-    Ok(Json(UserDto::from(user)))
+    let dto = UserDto::from(user);
+    Ok(conditional_json(&pre, ETag::from_content(&dto), dto))
 }
 
 /// Create a new user
@@ -148,20 +152,33 @@ pub async fn update_user(
     Authz(ctx): Authz,
     Extension(svc): Extension<std::sync::Arc<Service>>,
     Path(id): Path<Uuid>,
+    pre: Preconditions,
     Json(req_body): Json<UpdateUserReq>,
-) -> UsersResult<JsonBody<UserDto>> {
+) -> UsersResult<Response> {
     info!(
         user_id = %id,
         updater_id = %ctx.subject_id(),
         "Updating user"
     );
 
+    // Optimistic concurrency: reject the update if the client edited a stale copy, and
+    // only write if the user is still the version the ETag was checked against
     let patch = req_body.into();
-    let user = svc
-        .update_user(&ctx, id, patch)
-        .await
-        .map_err(UsersApiError::from_domain)?;
-    Ok(Json(UserDto::from(user)))
+    let user = if pre.has_if_match() {
+        let current = svc
+            .get_user(&ctx, id)
+            .await
+            .map_err(UsersApiError::from_domain)?;
+        let expected_updated_at = current.updated_at;
+        pre.check_if_match(Some(&ETag::from_content(&UserDto::from(current))))?;
+        svc.update_user_if_unmodified(&ctx, id, patch, expected_updated_at)
+            .await
+    } else {
+        svc.update_user(&ctx, id, patch).await
+    }
+    .map_err(UsersApiError::from_domain)?;
+    let dto = UserDto::from(user);
+    Ok(with_etag(&ETag::from_content(&dto), Json(dto)))
 }
 
 /// Delete a user by ID
@@ -41,6 +41,7 @@ pub fn register_routes(
         .path_param("id", "User UUID")
         .handler(handlers::get_user)
         .json_response_with_schema::<dto::UserDto>(openapi, http::StatusCode::OK, "User found")
+        .conditional_get()
         .error_401(openapi)
         .error_403(openapi)
         .error_404(openapi)
@@ -54,6 +55,7 @@ pub fn register_routes(
         .summary("Create a new user")
         .description("Create a new user with the provided information")
         .tag("users")
+        .idempotent()
         .json_request::<dto::CreateUserReq>(openapi, "User creation data")
         .handler(handlers::create_user)
         .json_response_with_schema::<dto::UserDto>(
@@ -79,6 +81,7 @@ pub fn register_routes(
         .json_request::<dto::UpdateUserReq>(openapi, "User update data")
         .handler(handlers::update_user)
         .json_response_with_schema::<dto::UserDto>(openapi, http::StatusCode::OK, "Updated user")
+        .conditional_update(openapi, false)
         .error_400(openapi)
         .error_401(openapi)
         .error_403(openapi)
@@ -146,6 +149,7 @@ mod sse_tests {
     use chrono::Utc;
     use futures::StreamExt;
     use modkit::SseBroadcaster;
+    use modkit_security::SecurityCtx;
     use tokio::time::{timeout, Duration};
     use uuid::Uuid;
 
@@ -216,7 +220,7 @@ mod sse_tests {
         };
 
         // Publish domain event through adapter
-        adapter.publish(&domain_event);
+        adapter.publish(&SecurityCtx::root_ctx(), &domain_event);
 
         // Receive converted event
         let received = timeout(Duration::from_millis(100), stream.next())
@@ -239,10 +243,13 @@ mod sse_tests {
         let timestamp = Utc::now();
 
         // Test Created event
-        adapter.publish(&UserDomainEvent::Created {
-            id: user_id,
-            at: timestamp,
-        });
+        adapter.publish(
+            &SecurityCtx::root_ctx(),
+            &UserDomainEvent::Created {
+                id: user_id,
+                at: timestamp,
+            },
+        );
         let event = timeout(Duration::from_millis(100), stream.next())
             .await
             .expect("timeout")
@@ -250,10 +257,13 @@ mod sse_tests {
         assert_eq!(event.kind, "created");
 
         // Test Updated event
-        adapter.publish(&UserDomainEvent::Updated {
-            id: user_id,
-            at: timestamp,
-        });
+        adapter.publish(
+            &SecurityCtx::root_ctx(),
+            &UserDomainEvent::Updated {
+                id: user_id,
+                at: timestamp,
+            },
+        );
         let event = timeout(Duration::from_millis(100), stream.next())
             .await
             .expect("timeout")
@@ -261,10 +271,13 @@ mod sse_tests {
         assert_eq!(event.kind, "updated");
 
         // Test Deleted event
-        adapter.publish(&UserDomainEvent::Deleted {
-            id: user_id,
-            at: timestamp,
-        });
+        adapter.publish(
+            &SecurityCtx::root_ctx(),
+            &UserDomainEvent::Deleted {
+                id: user_id,
+                at: timestamp,
+            },
+        );
         let event = timeout(Duration::from_millis(100), stream.next())
             .await
             .expect("timeout")
@@ -1,4 +1,5 @@
 use modkit::SseBroadcaster;
+use modkit_security::SecurityCtx;
 
 use crate::domain::{events::UserDomainEvent, ports::EventPublisher};
 
@@ -16,7 +17,7 @@ impl SseUserEventPublisher {
 }
 
 impl EventPublisher<UserDomainEvent> for SseUserEventPublisher {
-    fn publish(&self, event: &UserDomainEvent) {
+    fn publish(&self, _ctx: &SecurityCtx, event: &UserDomainEvent) {
         self.out.send(UserEvent::from(event));
     }
 }
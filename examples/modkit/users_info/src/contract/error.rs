@@ -10,6 +10,9 @@ pub enum UsersInfoError {
     #[error("User with email '{email}' already exists")]
     Conflict { email: String },
 
+    #[error("User {id} was modified concurrently")]
+    ConcurrentModification { id: Uuid },
+
     #[error("Validation error: {message}")]
     Validation { message: String },
 
@@ -26,6 +29,10 @@ impl UsersInfoError {
         Self::Conflict { email }
     }
 
+    pub fn concurrent_modification(id: Uuid) -> Self {
+        Self::ConcurrentModification { id }
+    }
+
     pub fn validation(message: impl Into<String>) -> Self {
         Self::Validation {
             message: message.into(),
@@ -50,6 +57,7 @@ impl From<crate::domain::error::DomainError> for UsersInfoError {
                 len, max
             )),
             Validation { field, message } => Self::validation(format!("{}: {}", field, message)),
+            ConcurrentModification { id } => Self::concurrent_modification(id),
             Database { .. } => Self::internal(),
         }
     }
@@ -2,6 +2,11 @@ pub mod client;
 pub mod error;
 pub mod model;
 
+/// Domain events published on the runtime event bus under the `users_info.user` topic.
+pub mod events {
+    pub use crate::domain::events::UserDomainEvent;
+}
+
 pub use client::*;
 pub use error::*;
 pub use model::*;
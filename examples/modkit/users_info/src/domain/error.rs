@@ -19,6 +19,9 @@ pub enum DomainError {
     #[error("Display name too long: {len} characters (max: {max})")]
     DisplayNameTooLong { len: usize, max: usize },
 
+    #[error("User {id} was modified concurrently")]
+    ConcurrentModification { id: Uuid },
+
     #[error("Database error: {message}")]
     Database { message: String },
 
@@ -47,6 +50,10 @@ impl DomainError {
         Self::DisplayNameTooLong { len, max }
     }
 
+    pub fn concurrent_modification(id: Uuid) -> Self {
+        Self::ConcurrentModification { id }
+    }
+
     pub fn database(message: impl Into<String>) -> Self {
         Self::Database {
             message: message.into(),
@@ -2,7 +2,12 @@ pub mod audit;
 
 pub use audit::AuditPort;
 
+use modkit_security::SecurityCtx;
+
 /// Output port: publish domain events (no knowledge of transport).
+///
+/// The security context of the originating request travels with the event so that
+/// subscribers can scope deliveries by tenant.
 pub trait EventPublisher<E>: Send + Sync + 'static {
-    fn publish(&self, event: &E);
+    fn publish(&self, ctx: &SecurityCtx, event: &E);
 }
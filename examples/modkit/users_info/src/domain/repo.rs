@@ -1,5 +1,6 @@
 use crate::contract::model::User;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use modkit_db::secure::SecurityCtx;
 use modkit_odata::Error as ODataError;
 use modkit_odata::{ODataQuery, Page};
@@ -36,6 +37,16 @@ pub trait UsersRepository: Send + Sync {
     /// Only users within the security scope can be updated.
     async fn update(&self, ctx: &SecurityCtx, u: User) -> anyhow::Result<()>;
 
+    /// Update an existing user only if its stored `updated_at` still equals `expected_updated_at`.
+    ///
+    /// Returns false when no row matched, i.e. the user was changed or removed since it was read.
+    async fn update_if_unmodified(
+        &self,
+        ctx: &SecurityCtx,
+        u: User,
+        expected_updated_at: DateTime<Utc>,
+    ) -> anyhow::Result<bool>;
+
     /// Delete by id with security validation. Returns true if a row was deleted.
     ///
     /// Only users within the security scope can be deleted.
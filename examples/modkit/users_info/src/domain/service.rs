@@ -5,7 +5,7 @@ use crate::domain::error::DomainError;
 use crate::domain::events::UserDomainEvent;
 use crate::domain::ports::{AuditPort, EventPublisher};
 use crate::domain::repo::UsersRepository;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use modkit_db::secure::SecurityCtx;
 use modkit_odata::{ODataQuery, Page};
 use tracing::{debug, info, instrument};
@@ -149,10 +149,13 @@ impl Service {
             debug!("Notification service call failed (continuing): {}", e);
         }
 
-        self.events.publish(&UserDomainEvent::Created {
-            id: user.id,
-            at: user.created_at,
-        });
+        self.events.publish(
+            ctx,
+            &UserDomainEvent::Created {
+                id: user.id,
+                at: user.created_at,
+            },
+        );
 
         info!("Successfully created user with id={}", user.id);
         Ok(user)
@@ -167,6 +170,33 @@ impl Service {
         ctx: &SecurityCtx,
         id: Uuid,
         patch: UserPatch,
+    ) -> Result<User, DomainError> {
+        self.apply_update(ctx, id, patch, None).await
+    }
+
+    /// Like [`Service::update_user`], but the write only lands if the stored user still has
+    /// `expected_updated_at`, i.e. nobody changed it since the caller read it.
+    #[instrument(
+        skip(self, ctx),
+        fields(user_id = %id)
+    )]
+    pub async fn update_user_if_unmodified(
+        &self,
+        ctx: &SecurityCtx,
+        id: Uuid,
+        patch: UserPatch,
+        expected_updated_at: DateTime<Utc>,
+    ) -> Result<User, DomainError> {
+        self.apply_update(ctx, id, patch, Some(expected_updated_at))
+            .await
+    }
+
+    async fn apply_update(
+        &self,
+        ctx: &SecurityCtx,
+        id: Uuid,
+        patch: UserPatch,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<User, DomainError> {
         info!("Updating user");
 
@@ -199,15 +229,31 @@ impl Service {
         }
         current.updated_at = Utc::now();
 
-        self.repo
-            .update(ctx, current.clone())
-            .await
-            .map_err(|e| DomainError::database(e.to_string()))?;
+        match expected_updated_at {
+            Some(expected) => {
+                let applied = self
+                    .repo
+                    .update_if_unmodified(ctx, current.clone(), expected)
+                    .await
+                    .map_err(|e| DomainError::database(e.to_string()))?;
+                if !applied {
+                    return Err(DomainError::concurrent_modification(id));
+                }
+            }
+            None => self
+                .repo
+                .update(ctx, current.clone())
+                .await
+                .map_err(|e| DomainError::database(e.to_string()))?,
+        }
 
-        self.events.publish(&UserDomainEvent::Updated {
-            id: current.id,
-            at: current.updated_at,
-        });
+        self.events.publish(
+            ctx,
+            &UserDomainEvent::Updated {
+                id: current.id,
+                at: current.updated_at,
+            },
+        );
 
         info!("Successfully updated user");
         Ok(current)
@@ -231,7 +277,7 @@ impl Service {
         }
 
         self.events
-            .publish(&UserDomainEvent::Deleted { id, at: Utc::now() });
+            .publish(ctx, &UserDomainEvent::Deleted { id, at: Utc::now() });
 
         info!("Successfully deleted user");
         Ok(())
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use modkit::EventBus;
+use modkit_security::SecurityCtx;
+use tracing::warn;
+
+use crate::domain::{events::UserDomainEvent, ports::EventPublisher};
+
+/// Topic name under which user domain events are published on the in-process bus.
+pub const USER_EVENTS_TOPIC: &str = "users_info.user";
+
+/// Adapter: publishes domain events on the runtime event bus, then forwards them to `next`.
+pub struct EventBusPublisher {
+    bus: Arc<EventBus>,
+    next: Arc<dyn EventPublisher<UserDomainEvent>>,
+}
+
+impl EventBusPublisher {
+    /// Registers the user events topic and wraps the given publisher.
+    pub fn new(
+        bus: Arc<EventBus>,
+        next: Arc<dyn EventPublisher<UserDomainEvent>>,
+    ) -> anyhow::Result<Self> {
+        bus.register_topic::<UserDomainEvent>(USER_EVENTS_TOPIC)?;
+        Ok(Self { bus, next })
+    }
+}
+
+impl EventPublisher<UserDomainEvent> for EventBusPublisher {
+    fn publish(&self, ctx: &SecurityCtx, event: &UserDomainEvent) {
+        if let Err(e) = self.bus.publish(ctx, event.clone()) {
+            warn!(error = %e, "Failed to publish user event on the event bus");
+        }
+        self.next.publish(ctx, event);
+    }
+}
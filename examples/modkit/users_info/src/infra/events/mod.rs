@@ -0,0 +1,3 @@
+pub mod bus_publisher;
+
+pub use bus_publisher::EventBusPublisher;
@@ -12,7 +12,9 @@
 //! - All filtering, ordering, and cursor extraction is type-safe
 
 use anyhow::Context;
-use sea_orm::{PaginatorTrait, Set};
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
 use tracing::{debug, instrument};
 use uuid::Uuid;
 
@@ -22,7 +24,7 @@ use crate::domain::repo::UsersRepository;
 use crate::infra::storage::entity::{ActiveModel as UserAM, Column, Entity as UserEntity};
 use crate::infra::storage::odata_mapper::UserODataMapper;
 use modkit_db::odata::{paginate_odata, LimitCfg};
-use modkit_db::secure::{SecureConn, SecurityCtx};
+use modkit_db::secure::{SecureConn, SecureUpdateExt, SecurityCtx};
 use modkit_odata::{ODataQuery, Page, SortDir};
 
 /// SeaORM repository implementation with automatic security scoping.
@@ -159,6 +161,40 @@ impl UsersRepository for SeaOrmUsersRepository {
         Ok(())
     }
 
+    #[instrument(
+        skip(self, ctx, u),
+        fields(
+            db.system = %self.sec.db_engine(),
+            db.operation = "UPDATE",
+            user.id = %u.id,
+            user.email = %u.email
+        )
+    )]
+    async fn update_if_unmodified(
+        &self,
+        ctx: &SecurityCtx,
+        u: User,
+        expected_updated_at: DateTime<Utc>,
+    ) -> anyhow::Result<bool> {
+        debug!("Conditionally updating user with security validation");
+
+        // Compare-and-set on updated_at so a concurrent write between read and update is detected
+        let result = UserEntity::update_many()
+            .col_expr(Column::Email, Expr::value(u.email))
+            .col_expr(Column::DisplayName, Expr::value(u.display_name))
+            .col_expr(Column::UpdatedAt, Expr::value(u.updated_at))
+            .filter(Column::Id.eq(u.id))
+            .filter(Column::UpdatedAt.eq(expected_updated_at))
+            .secure()
+            .scope_with(ctx.scope())
+            .context("Failed to create secure update")?
+            .exec(self.sec.conn())
+            .await
+            .context("Secure conditional update failed")?;
+
+        Ok(result.rows_affected > 0)
+    }
+
     #[instrument(
         skip(self, ctx),
         fields(
@@ -17,6 +17,7 @@ use crate::domain::ports::{AuditPort, EventPublisher};
 use crate::domain::service::{Service, ServiceConfig};
 use crate::gateways::local::UsersInfoLocalClient;
 use crate::infra::audit::HttpAuditClient;
+use crate::infra::events::EventBusPublisher;
 use crate::infra::storage::sea_orm_repo::SeaOrmUsersRepository;
 
 /// Main module struct with DDD-light layout and proper ClientHub integration
@@ -70,9 +71,12 @@ impl Module for UsersInfo {
         // Repository now uses SecureConn to automatically apply security filtering
         let repo = SeaOrmUsersRepository::new(sec_conn);
 
-        // Create event publisher adapter that bridges domain events to SSE
-        let publisher: Arc<dyn EventPublisher<UserDomainEvent>> =
+        // Create event publisher adapter that bridges domain events to SSE,
+        // and publish them on the runtime event bus for other modules
+        let sse_publisher: Arc<dyn EventPublisher<UserDomainEvent>> =
             Arc::new(SseUserEventPublisher::new(self.sse.clone()));
+        let publisher: Arc<dyn EventPublisher<UserDomainEvent>> =
+            Arc::new(EventBusPublisher::new(ctx.event_bus()?, sse_publisher)?);
 
         // Build traced HTTP client
         let traced_client = TracedClient::default();
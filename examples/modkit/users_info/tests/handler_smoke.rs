@@ -94,6 +94,97 @@ async fn get_user_handler_returns_json() {
     );
 }
 
+#[tokio::test]
+async fn get_user_honours_if_none_match() {
+    let db = inmem_db().await;
+    let fake_tenant = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+    let user_id = Uuid::new_v4();
+    let _user = seed_user(&db, user_id, fake_tenant, "etag@example.com", "ETag User").await;
+
+    let service = Arc::new(Service::new(
+        Arc::new(SeaOrmUsersRepository::new(SecureConn::new(db))),
+        Arc::new(MockEventPublisher),
+        Arc::new(MockAuditPort),
+        ServiceConfig::default(),
+    ));
+    let app = Router::new()
+        .route("/users/{id}", axum::routing::get(handlers::get_user))
+        .layer(Extension(service))
+        .layer(middleware::from_fn(inject_fake_security_ctx));
+
+    let get = |if_none_match: Option<String>| {
+        let mut req = Request::builder()
+            .method("GET")
+            .uri(format!("/users/{}", user_id));
+        if let Some(tag) = if_none_match {
+            req = req.header("if-none-match", tag);
+        }
+        app.clone().oneshot(req.body(Body::empty()).unwrap())
+    };
+
+    let response = get(None).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+
+    let response = get(Some(etag)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[tokio::test]
+async fn update_user_with_stale_if_match_returns_412() {
+    let db = inmem_db().await;
+    let fake_tenant = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+    let user_id = Uuid::new_v4();
+    let _user = seed_user(&db, user_id, fake_tenant, "etag@example.com", "ETag User").await;
+
+    let service = Arc::new(Service::new(
+        Arc::new(SeaOrmUsersRepository::new(SecureConn::new(db))),
+        Arc::new(MockEventPublisher),
+        Arc::new(MockAuditPort),
+        ServiceConfig::default(),
+    ));
+    let app = Router::new()
+        .route(
+            "/users/{id}",
+            axum::routing::get(handlers::get_user).put(handlers::update_user),
+        )
+        .layer(Extension(service))
+        .layer(middleware::from_fn(inject_fake_security_ctx));
+
+    let put = |if_match: String, display_name: &str| {
+        app.clone().oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/users/{}", user_id))
+                .header("content-type", "application/json")
+                .header("if-match", if_match)
+                .body(Body::from(
+                    serde_json::json!({ "display_name": display_name }).to_string(),
+                ))
+                .unwrap(),
+        )
+    };
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/users/{}", user_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+
+    let response = put(etag.clone(), "First Writer").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The second writer still holds the original ETag
+    let response = put(etag, "Second Writer").await.unwrap();
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+}
+
 #[tokio::test]
 async fn get_nonexistent_user_returns_404() {
     // Arrange: Create router (empty database)
@@ -99,3 +99,36 @@ async fn update_with_deny_all_fails() {
     // Assert: Should fail
     assert!(result.is_err(), "Deny-all context should prevent updates");
 }
+
+#[tokio::test]
+async fn conditional_update_skips_modified_rows() {
+    let db = inmem_db().await;
+    let tenant_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let user = seed_user(&db, user_id, tenant_id, "test@example.com", "Test User").await;
+
+    let repo = SeaOrmUsersRepository::new(SecureConn::new(db));
+    let ctx_ok = ctx_allow_tenants(&[tenant_id]);
+    let read_version = user.updated_at;
+
+    // A concurrent writer lands first
+    let mut first = user.clone();
+    first.display_name = "First Writer".to_string();
+    first.updated_at = read_version + chrono::Duration::seconds(1);
+    assert!(repo
+        .update_if_unmodified(&ctx_ok, first, read_version)
+        .await
+        .unwrap());
+
+    // The stale writer no longer matches and must not overwrite
+    let mut second = user.clone();
+    second.display_name = "Second Writer".to_string();
+    second.updated_at = read_version + chrono::Duration::seconds(2);
+    assert!(!repo
+        .update_if_unmodified(&ctx_ok, second, read_version)
+        .await
+        .unwrap());
+
+    let loaded = repo.find_by_id(&ctx_ok, user_id).await.unwrap().unwrap();
+    assert_eq!(loaded.display_name, "First Writer");
+}
@@ -164,7 +164,7 @@ impl AuditPort for MockAuditPort {
 pub struct MockEventPublisher;
 
 impl EventPublisher<UserDomainEvent> for MockEventPublisher {
-    fn publish(&self, _event: &UserDomainEvent) {
+    fn publish(&self, _ctx: &SecurityCtx, _event: &UserDomainEvent) {
         // Discard events in tests
     }
 }
@@ -0,0 +1,76 @@
+//! Boots `users_info` through `modkit::testing::TestRuntime` instead of hand-wiring the router.
+
+use axum::http::StatusCode;
+use modkit::testing::TestRuntime;
+use modkit_security::SecurityCtx;
+use serde_json::json;
+use users_info::contract::client::UsersInfoApi;
+use uuid::Uuid;
+
+async fn boot() -> TestRuntime {
+    TestRuntime::builder()
+        .module("users_info")
+        .module_config("users_info", json!({ "default_page_size": 5 }))
+        .build()
+        .await
+        .expect("users_info should boot")
+}
+
+#[tokio::test]
+async fn list_users_on_fresh_database() {
+    let rt = boot().await;
+
+    let resp = rt.get(&SecurityCtx::root_ctx(), "/users").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page: serde_json::Value = TestRuntime::read_json(resp).await.unwrap();
+    assert_eq!(page["items"], json!([]));
+
+    let resp = rt
+        .get(
+            &SecurityCtx::root_ctx(),
+            &format!("/users/{}", Uuid::new_v4()),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let report = rt.shutdown().await;
+    assert!(report.is_clean());
+}
+
+#[tokio::test]
+async fn client_is_exposed_per_runtime() {
+    let first = boot().await;
+    let second = boot().await;
+
+    let api = first
+        .client_hub()
+        .get::<dyn UsersInfoApi>()
+        .expect("users_info client should be registered");
+    let page = api
+        .list_users(&SecurityCtx::root_ctx(), Default::default())
+        .await
+        .unwrap();
+    assert!(page.items.is_empty());
+
+    assert!(second.client_hub().get::<dyn UsersInfoApi>().is_ok());
+}
+
+#[tokio::test]
+async fn test_build_leaves_the_crate_directory_untouched() {
+    let entries = || {
+        let mut names: Vec<_> = std::fs::read_dir(env!("CARGO_MANIFEST_DIR"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        names.sort();
+        names
+    };
+    let before = entries();
+
+    let rt = boot().await;
+    let resp = rt.get(&SecurityCtx::root_ctx(), "/users").await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    rt.shutdown().await;
+
+    assert_eq!(entries(), before);
+}
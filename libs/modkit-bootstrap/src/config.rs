@@ -19,6 +19,9 @@ pub struct ModuleEntry {
     pub database: Option<DbConnConfig>,
     #[serde(default)]
     pub config: serde_json::Value,
+    /// Stop deadline override in milliseconds (read by the runtime as `stop_timeout_ms`).
+    #[serde(default)]
+    pub stop_timeout_ms: Option<u64>,
 }
 
 /// Main application configuration with strongly-typed global sections
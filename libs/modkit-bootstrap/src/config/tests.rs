@@ -99,6 +99,37 @@ modules:
     assert!(config.modules.contains_key("test_module"));
 }
 
+#[test]
+fn test_module_entry_accepts_stop_timeout() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let config_path = temp_dir.path().join("test-config.yaml");
+
+    let yaml_content = r#"
+server:
+  home_dir: "/tmp/test"
+
+modules:
+  slow_module:
+    stop_timeout_ms: 45000
+    database:
+      file: "slow.db"
+    config:
+      batch_size: 10
+"#;
+
+    fs::write(&config_path, yaml_content).expect("Failed to write config file");
+
+    let config = AppConfig::load_layered(&config_path).expect("Failed to load config");
+
+    let entry: ModuleEntry = serde_json::from_value(config.modules["slow_module"].clone())
+        .expect("stop_timeout_ms should be a known module key");
+    assert_eq!(entry.stop_timeout_ms, Some(45000));
+    assert_eq!(entry.config["batch_size"], 10);
+
+    let db = get_module_db_config(&config, "slow_module").expect("database config");
+    assert_eq!(db.file.as_deref(), Some("slow.db"));
+}
+
 #[test]
 fn test_cli_overrides() {
     let mut config = AppConfig::default();
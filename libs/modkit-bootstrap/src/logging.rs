@@ -16,6 +16,10 @@ pub type OtelLayer = tracing_opentelemetry::OpenTelemetryLayer<
 #[cfg(not(feature = "otel"))]
 pub type OtelLayer = ();
 
+/// Target of access log events. The `access_log` logging section gives them a dedicated file,
+/// written one message per line without any decoration; they never reach the other sinks.
+pub const ACCESS_LOG_TARGET: &str = "access_log";
+
 // Keep a guard for non-blocking console to avoid being dropped.
 static CONSOLE_GUARD: std::sync::OnceLock<tracing_appender::non_blocking::WorkerGuard> =
     std::sync::OnceLock::new();
@@ -131,18 +135,20 @@ impl<'a> fmt::MakeWriter<'a> for MultiFileRouter {
 
 struct ConfigData<'a> {
     default_section: Option<&'a Section>,
+    access_section: Option<&'a Section>,
     crate_sections: Vec<(String, &'a Section)>,
 }
 
 fn extract_config_data(cfg: &LoggingConfig) -> ConfigData<'_> {
     let crate_sections = cfg
         .iter()
-        .filter(|(k, _)| k.as_str() != "default")
+        .filter(|(k, _)| k.as_str() != "default" && k.as_str() != ACCESS_LOG_TARGET)
         .map(|(k, v)| (k.clone(), v))
         .collect::<Vec<_>>();
 
     ConfigData {
         default_section: cfg.get("default"),
+        access_section: cfg.get(ACCESS_LOG_TARGET),
         crate_sections,
     }
 }
@@ -198,7 +204,10 @@ pub fn init_logging_unified(cfg: &LoggingConfig, base_dir: &Path, otel_layer: Op
 
     let data = extract_config_data(cfg);
 
-    if data.crate_sections.is_empty() && data.default_section.is_none() {
+    if data.crate_sections.is_empty()
+        && data.default_section.is_none()
+        && data.access_section.is_none()
+    {
         // Minimal fallback (INFO to console; honors RUST_LOG)
         init_minimal(otel_layer);
         return;
@@ -215,7 +224,17 @@ pub fn init_logging_unified(cfg: &LoggingConfig, base_dir: &Path, otel_layer: Op
         },
     );
 
-    install_subscriber(console_targets, file_targets, file_router, otel_layer);
+    let access_writer = data
+        .access_section
+        .and_then(|section| create_crate_file_writer(ACCESS_LOG_TARGET, section, base_dir));
+
+    install_subscriber(
+        console_targets,
+        file_targets,
+        file_router,
+        access_writer,
+        otel_layer,
+    );
 }
 
 // ================= generic targets builder =================
@@ -240,8 +259,10 @@ fn build_targets(config: &ConfigData, kind: SinkKind) -> Targets {
                 .map(LevelFilter::from_level)
                 .unwrap_or(LevelFilter::INFO);
 
-            // start with default
-            let mut targets = Targets::new().with_default(default_level);
+            // start with default; access lines only go to their own file
+            let mut targets = Targets::new()
+                .with_default(default_level)
+                .with_target(ACCESS_LOG_TARGET, LevelFilter::OFF);
 
             // per-crate rules (console sink is always "active")
             for (crate_name, section) in &config.crate_sections {
@@ -267,7 +288,9 @@ fn build_targets(config: &ConfigData, kind: SinkKind) -> Targets {
                     LevelFilter::OFF
                 });
 
-            let mut targets = Targets::new().with_default(default_level);
+            let mut targets = Targets::new()
+                .with_default(default_level)
+                .with_target(ACCESS_LOG_TARGET, LevelFilter::OFF);
 
             // per-crate rules: file sink is "active" only when path is present
             for (crate_name, section) in &config.crate_sections {
@@ -363,19 +386,60 @@ fn create_crate_file_writer(
     }
 }
 
+// ================= access log =================
+
+/// Writes the event's message as-is, one per line.
+struct MessageOnly;
+
+impl<S, N> fmt::FormatEvent<S, N> for MessageOnly
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    N: for<'a> fmt::FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        _ctx: &fmt::FmtContext<'_, S, N>,
+        mut writer: fmt::format::Writer<'_>,
+        event: &tracing::Event<'_>,
+    ) -> std::fmt::Result {
+        let mut visitor = MessageVisitor {
+            writer: &mut writer,
+            result: Ok(()),
+        };
+        event.record(&mut visitor);
+        visitor.result?;
+        writeln!(writer)
+    }
+}
+
+struct MessageVisitor<'a, 'w> {
+    writer: &'a mut fmt::format::Writer<'w>,
+    result: std::fmt::Result,
+}
+
+impl tracing::field::Visit for MessageVisitor<'_, '_> {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.result = write!(self.writer, "{value:?}");
+        }
+    }
+}
+
 // ================= registry & layers =================
 
 fn install_subscriber(
     console_targets: tracing_subscriber::filter::Targets,
     file_targets: tracing_subscriber::filter::Targets,
     file_router: MultiFileRouter,
+    access_writer: Option<RotWriter>,
     _otel_layer: Option<OtelLayer>,
 ) {
-    use tracing_subscriber::{fmt, layer::SubscriberExt, EnvFilter, Registry};
+    use tracing_subscriber::{filter::FilterExt, fmt, layer::SubscriberExt, EnvFilter, Registry};
 
-    // RUST_LOG acts as a global upper-bound for console/file if present.
+    // RUST_LOG acts as an upper bound for console/file (and OTEL) if present.
+    // It is attached per layer, so the access log is governed by its own target only.
     // If not set, we don't clamp here — YAML targets drive levels.
-    let env: Option<EnvFilter> = EnvFilter::try_from_default_env().ok();
+    let env = || EnvFilter::try_from_default_env().ok();
 
     // Console writer (non-blocking stderr)
     let (nb_stderr, guard) = tracing_appender::non_blocking(std::io::stderr());
@@ -388,7 +452,7 @@ fn install_subscriber(
         .with_target(true)
         .with_level(true)
         .with_timer(fmt::time::UtcTime::rfc_3339())
-        .with_filter(console_targets.clone());
+        .with_filter(console_targets.clone().and(env()));
 
     // File fmt layer (JSON) if router is not empty
     let file_layer_opt = if !file_router.is_empty() {
@@ -400,30 +464,41 @@ fn install_subscriber(
                 .with_level(true)
                 .with_timer(fmt::time::UtcTime::rfc_3339())
                 .with_writer(file_router)
-                .with_filter(file_targets),
+                .with_filter(file_targets.and(env())),
         )
     } else {
         None
     };
 
+    // Access log layer: raw lines of the access log target into their own rotated file
+    let access_layer_opt = access_writer.map(|writer| {
+        fmt::layer()
+            .event_format(MessageOnly)
+            .with_ansi(false)
+            .with_writer(writer)
+            .with_filter(Targets::new().with_target(ACCESS_LOG_TARGET, LevelFilter::INFO))
+    });
+
     // Build subscriber:
     // 1) OTEL first (because your OtelLayer is bound to `Registry`);
     //    also filter OTEL by the SAME console targets from YAML.
-    // 2) Then EnvFilter (caps console/file if RUST_LOG is set).
-    // 3) Then console + file fmt layers.
+    // 2) Then console + file fmt layers, each capped by RUST_LOG.
+    // 3) Then the access log layer.
     let subscriber = {
         let base = Registry::default();
 
         #[cfg(feature = "otel")]
         let base = {
-            let otel_opt = _otel_layer.map(|otel| otel.with_filter(console_targets.clone()));
+            let otel_opt =
+                _otel_layer.map(|otel| otel.with_filter(console_targets.clone().and(env())));
             base.with(otel_opt)
         };
         #[cfg(not(feature = "otel"))]
         let base = base;
 
-        let base = base.with(env);
-        base.with(console_layer).with(file_layer_opt)
+        base.with(console_layer)
+            .with(file_layer_opt)
+            .with(access_layer_opt)
     };
 
     let _ = subscriber.try_init();
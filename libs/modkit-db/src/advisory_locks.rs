@@ -109,6 +109,61 @@ impl DbLockGuard {
         }
         // drop self
     }
+
+    /// Check that the lock is still held.
+    ///
+    /// For PG/MySQL this probes the connection that owns the lock (the lock dies with the
+    /// session); for the file fallback it checks that the marker file is still in place.
+    pub async fn is_held(&mut self) -> bool {
+        match self.inner.as_mut() {
+            #[cfg(feature = "pg")]
+            Some(GuardInner::Postgres { conn, .. }) => {
+                use sqlx::Connection;
+                match conn.ping().await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::debug!(key = %self.namespaced_key, error = %e, "advisory lock connection lost");
+                        false
+                    }
+                }
+            }
+            #[cfg(feature = "mysql")]
+            Some(GuardInner::MySql { conn, lock_name }) => {
+                match sqlx::query_scalar::<_, Option<i64>>(
+                    "SELECT IS_USED_LOCK(?) = CONNECTION_ID()",
+                )
+                .bind(lock_name.as_str())
+                .fetch_one(&mut **conn)
+                .await
+                {
+                    Ok(owned) => owned == Some(1),
+                    Err(e) => {
+                        tracing::debug!(key = %self.namespaced_key, error = %e, "advisory lock connection lost");
+                        false
+                    }
+                }
+            }
+            Some(GuardInner::File { path, .. }) => {
+                tokio::fs::try_exists(&path).await.unwrap_or(false)
+            }
+            None => false,
+        }
+    }
+
+    /// Drop a lock that is known to be lost without trying to unlock it.
+    ///
+    /// The owning DB connection is closed instead of being returned to the pool, and the
+    /// marker file is left alone since it may already belong to another holder.
+    pub fn abandon(mut self) {
+        match self.inner.take() {
+            #[cfg(feature = "pg")]
+            Some(GuardInner::Postgres { conn, .. }) => drop(conn.detach()),
+            #[cfg(feature = "mysql")]
+            Some(GuardInner::MySql { conn, .. }) => drop(conn.detach()),
+            Some(GuardInner::File { file, .. }) => drop(file),
+            None => {}
+        }
+    }
 }
 
 impl Drop for DbLockGuard {
@@ -0,0 +1,114 @@
+//! Transactions shared by the items of an atomic `$batch` group.
+//!
+//! The ingress puts a [`BatchTransaction`] into the extensions of every sub-request of an
+//! atomic group. Handlers of operations that opted in run their writes on the transaction it
+//! hands out instead of their own connection; the ingress commits it when every item of the
+//! group succeeded and rolls it back otherwise.
+//!
+//! ```ignore
+//! async fn create_user(
+//!     Extension(ctx): Extension<SecurityCtx>,
+//!     batch: Option<Extension<BatchTransaction>>,
+//!     State(db): State<SecureConn>,
+//!     Json(body): Json<NewUser>,
+//! ) -> ApiResult<Json<User>> {
+//!     let user = match batch {
+//!         Some(Extension(batch)) => {
+//!             let txn = batch.on("users_info", db.conn()).await?;
+//!             secure_insert::<user::Entity>(body.into(), &ctx, txn.as_ref()).await?
+//!         }
+//!         None => secure_insert::<user::Entity>(body.into(), &ctx, db.conn()).await?,
+//!     };
+//!     Ok(Json(user.into()))
+//! }
+//! ```
+//!
+//! One transaction is begun per database key, so a group touching two modules' databases
+//! commits them one after the other: atomic per database, not across them.
+
+use std::sync::Arc;
+
+use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr, TransactionTrait};
+use tokio::sync::Mutex;
+
+/// Transactions begun so far, in order, with the key of their database.
+type Begun = Vec<(String, Arc<DatabaseTransaction>)>;
+
+/// Lazily begun transactions of one atomic batch group, keyed by database.
+#[derive(Clone, Default)]
+pub struct BatchTransaction {
+    txns: Arc<Mutex<Begun>>,
+}
+
+impl BatchTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The group's transaction on `conn`, begun on first use.
+    ///
+    /// `key` names the database, usually the module owning `conn`; items of the group using
+    /// the same key share one transaction. Drop the returned handle before the handler returns.
+    pub async fn on(
+        &self,
+        key: &str,
+        conn: &DatabaseConnection,
+    ) -> Result<Arc<DatabaseTransaction>, DbErr> {
+        let mut txns = self.txns.lock().await;
+        if let Some((_, txn)) = txns.iter().find(|(k, _)| k == key) {
+            return Ok(txn.clone());
+        }
+        let txn = Arc::new(conn.begin().await?);
+        txns.push((key.to_string(), txn.clone()));
+        Ok(txn)
+    }
+
+    /// Whether any item of the group used a transaction.
+    pub async fn is_empty(&self) -> bool {
+        self.txns.lock().await.is_empty()
+    }
+
+    /// Commit every transaction in the order they were begun.
+    pub async fn commit(&self) -> Result<(), DbErr> {
+        for (key, txn) in self.take().await? {
+            txn.commit()
+                .await
+                .map_err(|e| DbErr::Custom(format!("commit of '{key}' failed: {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// Roll every transaction back.
+    pub async fn rollback(&self) -> Result<(), DbErr> {
+        let mut result = Ok(());
+        for (_, txn) in self.take().await? {
+            if let Err(e) = txn.rollback().await {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    async fn take(&self) -> Result<Vec<(String, DatabaseTransaction)>, DbErr> {
+        let txns = std::mem::take(&mut *self.txns.lock().await);
+        let mut owned = Vec::with_capacity(txns.len());
+        for (key, txn) in txns {
+            match Arc::try_unwrap(txn) {
+                Ok(txn) => owned.push((key, txn)),
+                // Dropping a sea-orm transaction rolls it back
+                Err(_) => {
+                    return Err(DbErr::Custom(format!(
+                        "batch transaction '{key}' is still held by a handler"
+                    )))
+                }
+            }
+        }
+        Ok(owned)
+    }
+}
+
+impl std::fmt::Debug for BatchTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchTransaction").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,223 @@
+//! Leader election on top of advisory locks.
+//!
+//! A [`LeaderElector`] campaigns for a namespaced advisory lock and, once it wins, keeps
+//! checking that the lock is still held: the owning connection for PostgreSQL/MySQL, the
+//! marker file for the SQLite fallback. Leadership is surfaced as:
+//! - a [`watch`](tokio::sync::watch) channel of [`LeaderState`] for observers, and
+//! - a [`Leadership`] handle whose cancellation token fires when leadership is lost or
+//!   the elector is shut down.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let elector = LeaderElector::new(&db, "scheduler", "leader", LeaderConfig::default());
+//! elector
+//!     .run(cancel, |lost| async move {
+//!         // Singleton work; must return promptly once `lost` is cancelled.
+//!         run_jobs(lost).await;
+//!     })
+//!     .await?;
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, watch};
+use tokio_util::sync::CancellationToken;
+
+use crate::{DbHandle, LockConfig, Result};
+
+/// Leadership state as seen by this process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderState {
+    /// Campaigning or waiting for the current leader to go away.
+    Follower,
+    /// Holding the lock.
+    Leader,
+    /// The elector was shut down.
+    Stopped,
+}
+
+/// Timing of the campaign and of the held-lock checks.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LeaderConfig {
+    /// Delay between attempts to acquire the lock while another node leads.
+    #[serde(with = "humantime_serde")]
+    pub retry_interval: Duration,
+    /// How often the leader verifies that its lock is still held.
+    #[serde(with = "humantime_serde")]
+    pub check_interval: Duration,
+}
+
+impl Default for LeaderConfig {
+    fn default() -> Self {
+        Self {
+            retry_interval: Duration::from_secs(5),
+            check_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Campaigns for leadership of `{namespace}:{key}`.
+pub struct LeaderElector {
+    db: DbHandle,
+    namespace: String,
+    key: String,
+    config: LeaderConfig,
+    state: watch::Sender<LeaderState>,
+}
+
+impl LeaderElector {
+    pub fn new(
+        db: &DbHandle,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        config: LeaderConfig,
+    ) -> Self {
+        let (state, _) = watch::channel(LeaderState::Follower);
+        Self {
+            db: db.clone(),
+            namespace: namespace.into(),
+            key: key.into(),
+            config,
+            state,
+        }
+    }
+
+    /// Subscribe to leadership state changes.
+    pub fn watch(&self) -> watch::Receiver<LeaderState> {
+        self.state.subscribe()
+    }
+
+    pub fn is_leader(&self) -> bool {
+        *self.state.borrow() == LeaderState::Leader
+    }
+
+    /// Wait until this process becomes leader.
+    ///
+    /// Returns `None` if `cancel` fires first. The returned handle's token is a child of
+    /// `cancel`, so shutting down also steps down.
+    pub async fn campaign(&self, cancel: &CancellationToken) -> Result<Option<Leadership>> {
+        let lock_cfg = LockConfig {
+            max_wait: None,
+            max_attempts: Some(1),
+            ..Default::default()
+        };
+
+        loop {
+            if cancel.is_cancelled() {
+                self.state.send_replace(LeaderState::Stopped);
+                return Ok(None);
+            }
+
+            let res = tokio::select! {
+                _ = cancel.cancelled() => continue,
+                res = self.db.try_lock(&self.namespace, &self.key, lock_cfg.clone()) => res,
+            };
+            match res {
+                Ok(Some(guard)) => {
+                    tracing::info!(namespace = %self.namespace, key = %self.key, "Acquired leadership");
+                    self.state.send_replace(LeaderState::Leader);
+                    return Ok(Some(self.monitor(guard, cancel.child_token())));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(namespace = %self.namespace, key = %self.key, error = %e, "Leader campaign failed");
+                }
+            }
+
+            tokio::select! {
+                _ = cancel.cancelled() => {}
+                _ = tokio::time::sleep(self.config.retry_interval) => {}
+            }
+        }
+    }
+
+    /// Run `work` whenever this process is leader, until `cancel` fires.
+    ///
+    /// `work` receives a token that fires on leadership loss or shutdown and must return
+    /// promptly after that. If `work` returns on its own, leadership is released and the
+    /// elector campaigns again.
+    pub async fn run<F, Fut>(&self, cancel: CancellationToken, mut work: F) -> Result<()>
+    where
+        F: FnMut(CancellationToken) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        while let Some(leadership) = self.campaign(&cancel).await? {
+            work(leadership.token()).await;
+            leadership.step_down().await;
+        }
+        Ok(())
+    }
+
+    fn monitor(&self, mut guard: crate::DbLockGuard, token: CancellationToken) -> Leadership {
+        let (done_tx, done_rx) = oneshot::channel();
+        let state = self.state.clone();
+        let check_interval = self.config.check_interval;
+        let key = guard.key().to_string();
+        let lost = token.clone();
+
+        tokio::spawn(async move {
+            let held = loop {
+                tokio::select! {
+                    _ = lost.cancelled() => break true,
+                    _ = tokio::time::sleep(check_interval) => {
+                        if !guard.is_held().await {
+                            break false;
+                        }
+                    }
+                }
+            };
+
+            lost.cancel();
+            if held {
+                guard.release().await;
+                tracing::info!(key = %key, "Stepped down from leadership");
+            } else {
+                guard.abandon();
+                tracing::warn!(key = %key, "Leadership lost");
+            }
+            // Follower until the next campaign decides otherwise.
+            state.send_if_modified(|s| {
+                let changed = *s == LeaderState::Leader;
+                if changed {
+                    *s = LeaderState::Follower;
+                }
+                changed
+            });
+            let _ = done_tx.send(());
+        });
+
+        Leadership {
+            token,
+            done: done_rx,
+        }
+    }
+}
+
+/// Held leadership. Dropping it without [`step_down`](Self::step_down) keeps the lock
+/// until the parent cancellation token fires or the lock is lost.
+pub struct Leadership {
+    token: CancellationToken,
+    done: oneshot::Receiver<()>,
+}
+
+impl Leadership {
+    /// Token cancelled when leadership is lost or given up.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_lost(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Release the lock and wait until it is actually released.
+    pub async fn step_down(self) {
+        self.token.cancel();
+        let _ = self.done.await;
+    }
+}
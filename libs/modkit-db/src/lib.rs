@@ -18,7 +18,8 @@
 //!
 //! # Features
 //! - `pg`, `mysql`, `sqlite`: enable SQLx backends
-//! - `sea-orm`: add SeaORM integration for type-safe operations
+//! - `sea-orm`: add SeaORM integration for type-safe operations and the
+//!   transactional [`outbox`]
 //!
 //! # New Architecture
 //! The crate now supports:
@@ -66,10 +67,12 @@
 
 // Re-export key types for public API
 pub use advisory_locks::{DbLockGuard, LockConfig};
+pub use leader::{LeaderConfig, LeaderElector, LeaderState, Leadership};
 
 // Core modules
 pub mod advisory_locks;
 pub mod config;
+pub mod leader;
 pub mod manager;
 pub mod odata;
 pub mod options;
@@ -78,6 +81,14 @@ pub mod options;
 #[cfg(feature = "sea-orm")]
 pub mod secure;
 
+// Transactional outbox (requires sea-orm feature)
+#[cfg(feature = "sea-orm")]
+pub mod outbox;
+
+// Transactions shared by atomic `$batch` groups (requires sea-orm feature)
+#[cfg(feature = "sea-orm")]
+pub mod batch;
+
 // Internal modules
 mod pool_opts;
 #[cfg(feature = "sqlite")]
@@ -228,6 +239,15 @@ pub enum DbPool {
     Sqlite(SqlitePool),
 }
 
+/// Point-in-time connection counts of a pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Open connections, idle or in use
+    pub size: u32,
+    /// Open connections not currently in use
+    pub idle: u32,
+}
+
 /// Database transaction wrapper (lifetime-bound to the pool).
 pub enum DbTransaction<'a> {
     #[cfg(feature = "pg")]
@@ -400,10 +420,9 @@ impl DbHandle {
                             let timeout = pragmas
                                 .busy_timeout_ms
                                 .unwrap_or(DEFAULT_SQLITE_BUSY_TIMEOUT.into());
-                            sqlx::query("PRAGMA busy_timeout = ?")
-                                .bind(timeout)
-                                .execute(&mut *conn)
-                                .await?;
+                            // PRAGMA arguments cannot be bound as parameters.
+                            let stmt = format!("PRAGMA busy_timeout = {}", timeout);
+                            sqlx::query(&stmt).execute(&mut *conn).await?;
                         }
 
                         Ok(())
@@ -453,6 +472,22 @@ impl DbHandle {
         &self.dsn
     }
 
+    /// Current connection counts of the underlying pool.
+    pub fn pool_stats(&self) -> PoolStats {
+        let (size, idle) = match &self.pool {
+            #[cfg(feature = "pg")]
+            DbPool::Postgres(p) => (p.size(), p.num_idle()),
+            #[cfg(feature = "mysql")]
+            DbPool::MySql(p) => (p.size(), p.num_idle()),
+            #[cfg(feature = "sqlite")]
+            DbPool::Sqlite(p) => (p.size(), p.num_idle()),
+        };
+        PoolStats {
+            size,
+            idle: u32::try_from(idle).unwrap_or(u32::MAX),
+        }
+    }
+
     // --- sqlx accessors ---
     #[cfg(feature = "pg")]
     pub fn sqlx_postgres(&self) -> Option<&PgPool> {
@@ -506,6 +541,12 @@ impl DbHandle {
         crate::secure::SecureConn::new(self.sea.clone())
     }
 
+    /// Raw SeaORM connection for infrastructure code inside this crate (outbox, etc.).
+    #[cfg(feature = "sea-orm")]
+    pub(crate) fn sea_internal(&self) -> &DatabaseConnection {
+        &self.sea
+    }
+
     /// **INSECURE**: Get raw SeaORM connection (bypasses all security).
     ///
     /// This method is **only available** when compiled with `--features insecure-escape`.
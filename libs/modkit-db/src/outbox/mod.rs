@@ -0,0 +1,490 @@
+//! Transactional outbox for domain events.
+//!
+//! Publishing an event after a committed write is not atomic: a crash between the two
+//! loses the event, and publishing before the commit may announce a change that is later
+//! rolled back. The outbox closes that gap by writing the event into the `modkit_outbox`
+//! table **inside the same transaction** as the entity change. A background
+//! [`OutboxRelay`] then delivers committed rows to an [`OutboxSink`] at-least-once.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use modkit_db::outbox::{Outbox, OutboxRelay, RelayConfig};
+//! use sea_orm::TransactionTrait;
+//!
+//! let outbox = Outbox::new(&db, "users_info");
+//! outbox.migrate().await?;
+//!
+//! // Write path: entity change and event commit or roll back together.
+//! let txn = secure_conn.conn().begin().await?;
+//! let user = secure_insert::<user::Entity>(am, &ctx, &txn).await?;
+//! outbox.enqueue(&txn, &ctx, "users_info.user.created", &UserCreated { id: user.id }).await?;
+//! txn.commit().await?;
+//!
+//! // Background: only one relay per namespace is active across all replicas.
+//! let relay = OutboxRelay::new(outbox, sink, RelayConfig::default());
+//! tokio::spawn(relay.run(cancel));
+//! ```
+//!
+//! Consumers must be idempotent: a row is marked as published only after the sink
+//! acknowledged it, so a crash in between delivers it again. Use [`OutboxRecord::id`]
+//! as the deduplication key.
+
+mod relay;
+
+pub use relay::{OutboxRelay, OutboxSink, PrunePolicy, RelayConfig, RelayTick, RetryPolicy};
+
+use chrono::{DateTime, TimeZone, Utc};
+use modkit_security::SecurityCtx;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, QueryResult, Statement, Value};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{DbError, DbHandle, Result};
+
+/// Name of the table shared by all outbox namespaces.
+pub const OUTBOX_TABLE: &str = "modkit_outbox";
+
+/// Delivery state of an outbox row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    /// Waiting for (re)delivery.
+    Pending,
+    /// Acknowledged by the sink.
+    Published,
+    /// Retries exhausted or payload undecodable; kept for inspection.
+    Dead,
+}
+
+impl OutboxStatus {
+    fn as_i64(self) -> i64 {
+        match self {
+            OutboxStatus::Pending => 0,
+            OutboxStatus::Published => 1,
+            OutboxStatus::Dead => 2,
+        }
+    }
+
+    fn from_i64(v: i64) -> Option<Self> {
+        match v {
+            0 => Some(OutboxStatus::Pending),
+            1 => Some(OutboxStatus::Published),
+            2 => Some(OutboxStatus::Dead),
+            _ => None,
+        }
+    }
+}
+
+/// A committed outbox row handed to an [`OutboxSink`].
+#[derive(Debug, Clone)]
+pub struct OutboxRecord {
+    /// Stable event id (UUID v7); use it to deduplicate redeliveries.
+    pub id: Uuid,
+    pub namespace: String,
+    pub topic: String,
+    pub payload: serde_json::Value,
+    /// Security context of the request that produced the event.
+    pub security: SecurityCtx,
+    /// Number of delivery attempts made before this one.
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A row parked in the dead-letter state.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub topic: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Number of rows per status within one namespace.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct OutboxCounts {
+    pub pending: u64,
+    pub published: u64,
+    pub dead: u64,
+}
+
+/// Row as stored; decoded lazily so that a single bad payload cannot block the batch.
+#[derive(Debug)]
+pub(crate) struct RawRow {
+    pub(crate) id: String,
+    topic: String,
+    payload: String,
+    security: String,
+    attempts: i64,
+    created_at: i64,
+}
+
+impl RawRow {
+    fn from_query(row: &QueryResult) -> Result<Self> {
+        Ok(Self {
+            id: row.try_get("", "id")?,
+            topic: row.try_get("", "topic")?,
+            payload: row.try_get("", "payload")?,
+            security: row.try_get("", "security")?,
+            attempts: row.try_get("", "attempts")?,
+            created_at: row.try_get("", "created_at")?,
+        })
+    }
+
+    pub(crate) fn decode(&self, namespace: &str) -> std::result::Result<OutboxRecord, String> {
+        let id = Uuid::parse_str(&self.id).map_err(|e| format!("invalid id: {e}"))?;
+        let payload =
+            serde_json::from_str(&self.payload).map_err(|e| format!("invalid payload: {e}"))?;
+        let security = serde_json::from_str(&self.security)
+            .map_err(|e| format!("invalid security context: {e}"))?;
+        Ok(OutboxRecord {
+            id,
+            namespace: namespace.to_string(),
+            topic: self.topic.clone(),
+            payload,
+            security,
+            attempts: u32::try_from(self.attempts).unwrap_or(u32::MAX),
+            created_at: from_millis(self.created_at),
+        })
+    }
+}
+
+/// Outbox bound to one namespace (typically the owning module's name).
+///
+/// Cheap to clone; all clones share the underlying pool.
+#[derive(Clone)]
+pub struct Outbox {
+    db: DbHandle,
+    namespace: String,
+}
+
+impl Outbox {
+    pub fn new(db: &DbHandle, namespace: impl Into<String>) -> Self {
+        Self {
+            db: db.clone(),
+            namespace: namespace.into(),
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub(crate) fn db(&self) -> &DbHandle {
+        &self.db
+    }
+
+    fn conn(&self) -> &DatabaseConnection {
+        self.db.sea_internal()
+    }
+
+    /// Create the outbox table and its index if they do not exist yet.
+    ///
+    /// Safe to call on every start-up; the table is shared by all namespaces.
+    pub async fn migrate(&self) -> Result<()> {
+        let conn = self.conn();
+        let backend = conn.get_database_backend();
+        let inline_index = if backend == DbBackend::MySql {
+            ",\n    INDEX idx_modkit_outbox_due (namespace, status, next_attempt_at)"
+        } else {
+            ""
+        };
+        let ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {OUTBOX_TABLE} (
+    id VARCHAR(36) NOT NULL PRIMARY KEY,
+    namespace VARCHAR(128) NOT NULL,
+    topic VARCHAR(255) NOT NULL,
+    payload TEXT NOT NULL,
+    security TEXT NOT NULL,
+    status BIGINT NOT NULL,
+    attempts BIGINT NOT NULL,
+    next_attempt_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    processed_at BIGINT NULL,
+    last_error TEXT NULL{inline_index}
+)"
+        );
+        conn.execute(Statement::from_string(backend, ddl)).await?;
+
+        if backend != DbBackend::MySql {
+            conn.execute(Statement::from_string(
+                backend,
+                format!(
+                    "CREATE INDEX IF NOT EXISTS idx_modkit_outbox_due \
+                     ON {OUTBOX_TABLE} (namespace, status, next_attempt_at)"
+                ),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Enqueue an event as part of the caller's transaction.
+    ///
+    /// `conn` should be the same `DatabaseTransaction` used for the entity change; the
+    /// event becomes visible to the relay only once that transaction commits.
+    pub async fn enqueue<C, T>(
+        &self,
+        conn: &C,
+        ctx: &SecurityCtx,
+        topic: &str,
+        payload: &T,
+    ) -> Result<Uuid>
+    where
+        C: ConnectionTrait,
+        T: Serialize + ?Sized,
+    {
+        let id = Uuid::now_v7();
+        let payload = serde_json::to_string(payload)
+            .map_err(|e| DbError::Other(anyhow::anyhow!("outbox payload: {e}")))?;
+        let security = serde_json::to_string(ctx)
+            .map_err(|e| DbError::Other(anyhow::anyhow!("outbox security context: {e}")))?;
+        let now = now_millis();
+
+        conn.execute(stmt(
+            conn.get_database_backend(),
+            &format!(
+                "INSERT INTO {OUTBOX_TABLE} \
+                 (id, namespace, topic, payload, security, status, attempts, next_attempt_at, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, 0, ?, ?)"
+            ),
+            [
+                id.to_string().into(),
+                self.namespace.clone().into(),
+                topic.into(),
+                payload.into(),
+                security.into(),
+                OutboxStatus::Pending.as_i64().into(),
+                now.into(),
+                now.into(),
+            ],
+        ))
+        .await?;
+        Ok(id)
+    }
+
+    /// Pending rows that are due, oldest first.
+    pub(crate) async fn fetch_due(&self, limit: u32) -> Result<Vec<RawRow>> {
+        let conn = self.conn();
+        let rows = conn
+            .query_all(stmt(
+                conn.get_database_backend(),
+                &format!(
+                    "SELECT id, topic, payload, security, attempts, created_at FROM {OUTBOX_TABLE} \
+                     WHERE namespace = ? AND status = ? AND next_attempt_at <= ? \
+                     ORDER BY created_at, id LIMIT ?"
+                ),
+                [
+                    self.namespace.clone().into(),
+                    OutboxStatus::Pending.as_i64().into(),
+                    now_millis().into(),
+                    i64::from(limit).into(),
+                ],
+            ))
+            .await?;
+        rows.iter().map(RawRow::from_query).collect()
+    }
+
+    pub(crate) async fn mark_published(&self, id: &str, attempts: i64) -> Result<()> {
+        self.update_state(id, OutboxStatus::Published, attempts, None, None)
+            .await
+    }
+
+    pub(crate) async fn mark_dead(&self, id: &str, attempts: i64, error: &str) -> Result<()> {
+        self.update_state(id, OutboxStatus::Dead, attempts, None, Some(error))
+            .await
+    }
+
+    pub(crate) async fn schedule_retry(
+        &self,
+        id: &str,
+        attempts: i64,
+        next_attempt_at: i64,
+        error: &str,
+    ) -> Result<()> {
+        self.update_state(
+            id,
+            OutboxStatus::Pending,
+            attempts,
+            Some(next_attempt_at),
+            Some(error),
+        )
+        .await
+    }
+
+    async fn update_state(
+        &self,
+        id: &str,
+        status: OutboxStatus,
+        attempts: i64,
+        next_attempt_at: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn();
+        let now = now_millis();
+        let processed_at = (status != OutboxStatus::Pending).then_some(now);
+        conn.execute(stmt(
+            conn.get_database_backend(),
+            &format!(
+                "UPDATE {OUTBOX_TABLE} SET status = ?, attempts = ?, \
+                 next_attempt_at = ?, processed_at = ?, last_error = ? WHERE id = ?"
+            ),
+            [
+                status.as_i64().into(),
+                attempts.into(),
+                next_attempt_at.unwrap_or(now).into(),
+                processed_at.into(),
+                error.map(str::to_string).into(),
+                id.into(),
+            ],
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Dead-lettered rows of this namespace, oldest first.
+    pub async fn dead_letters(&self, limit: u32) -> Result<Vec<DeadLetter>> {
+        let conn = self.conn();
+        let rows = conn
+            .query_all(stmt(
+                conn.get_database_backend(),
+                &format!(
+                    "SELECT id, topic, payload, attempts, last_error, created_at FROM {OUTBOX_TABLE} \
+                     WHERE namespace = ? AND status = ? ORDER BY created_at, id LIMIT ?"
+                ),
+                [
+                    self.namespace.clone().into(),
+                    OutboxStatus::Dead.as_i64().into(),
+                    i64::from(limit).into(),
+                ],
+            ))
+            .await?;
+
+        rows.iter()
+            .map(|row| -> Result<DeadLetter> {
+                let id: String = row.try_get("", "id")?;
+                let attempts: i64 = row.try_get("", "attempts")?;
+                Ok(DeadLetter {
+                    id: Uuid::parse_str(&id).unwrap_or_default(),
+                    topic: row.try_get("", "topic")?,
+                    payload: row.try_get("", "payload")?,
+                    attempts: u32::try_from(attempts).unwrap_or(u32::MAX),
+                    last_error: row.try_get("", "last_error")?,
+                    created_at: from_millis(row.try_get("", "created_at")?),
+                })
+            })
+            .collect()
+    }
+
+    /// Move a dead-lettered row back to pending with a fresh retry budget.
+    ///
+    /// Returns `false` if no dead row with this id exists in the namespace.
+    pub async fn requeue_dead(&self, id: Uuid) -> Result<bool> {
+        let conn = self.conn();
+        let res = conn
+            .execute(stmt(
+                conn.get_database_backend(),
+                &format!(
+                    "UPDATE {OUTBOX_TABLE} SET status = ?, attempts = 0, next_attempt_at = ?, \
+                     processed_at = NULL WHERE id = ? AND namespace = ? AND status = ?"
+                ),
+                [
+                    OutboxStatus::Pending.as_i64().into(),
+                    now_millis().into(),
+                    id.to_string().into(),
+                    self.namespace.clone().into(),
+                    OutboxStatus::Dead.as_i64().into(),
+                ],
+            ))
+            .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Delete rows in `status` that reached it before `older_than`.
+    ///
+    /// Returns the number of deleted rows. Pending rows are never pruned.
+    pub async fn prune(&self, status: OutboxStatus, older_than: DateTime<Utc>) -> Result<u64> {
+        if status == OutboxStatus::Pending {
+            return Ok(0);
+        }
+        let conn = self.conn();
+        let res = conn
+            .execute(stmt(
+                conn.get_database_backend(),
+                &format!(
+                    "DELETE FROM {OUTBOX_TABLE} \
+                     WHERE namespace = ? AND status = ? AND processed_at < ?"
+                ),
+                [
+                    self.namespace.clone().into(),
+                    status.as_i64().into(),
+                    older_than.timestamp_millis().into(),
+                ],
+            ))
+            .await?;
+        Ok(res.rows_affected())
+    }
+
+    /// Row counts per status in this namespace.
+    pub async fn counts(&self) -> Result<OutboxCounts> {
+        let conn = self.conn();
+        let rows = conn
+            .query_all(stmt(
+                conn.get_database_backend(),
+                &format!(
+                    "SELECT status, COUNT(*) AS n FROM {OUTBOX_TABLE} \
+                     WHERE namespace = ? GROUP BY status"
+                ),
+                [self.namespace.clone().into()],
+            ))
+            .await?;
+
+        let mut counts = OutboxCounts::default();
+        for row in rows {
+            let status: i64 = row.try_get("", "status")?;
+            let n: i64 = row.try_get("", "n")?;
+            let n = u64::try_from(n).unwrap_or(0);
+            match OutboxStatus::from_i64(status) {
+                Some(OutboxStatus::Pending) => counts.pending = n,
+                Some(OutboxStatus::Published) => counts.published = n,
+                Some(OutboxStatus::Dead) => counts.dead = n,
+                None => {}
+            }
+        }
+        Ok(counts)
+    }
+}
+
+/// Build a statement from `?`-style SQL, rewriting placeholders for PostgreSQL.
+fn stmt<I>(backend: DbBackend, sql: &str, values: I) -> Statement
+where
+    I: IntoIterator<Item = Value>,
+{
+    let sql = if backend == DbBackend::Postgres {
+        let mut out = String::with_capacity(sql.len() + 8);
+        let mut n = 0;
+        for ch in sql.chars() {
+            if ch == '?' {
+                n += 1;
+                out.push('$');
+                out.push_str(&n.to_string());
+            } else {
+                out.push(ch);
+            }
+        }
+        out
+    } else {
+        sql.to_string()
+    };
+    Statement::from_sql_and_values(backend, sql, values)
+}
+
+pub(crate) fn now_millis() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+fn from_millis(ms: i64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(ms).single().unwrap_or_default()
+}
@@ -0,0 +1,280 @@
+//! Background relay delivering committed outbox rows to a sink.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use super::{now_millis, Outbox, OutboxRecord, OutboxStatus};
+use crate::leader::{LeaderConfig, LeaderElector};
+use crate::Result;
+
+/// Advisory lock key (within the outbox namespace) held by the active relay.
+const RELAY_LOCK_KEY: &str = "outbox_relay";
+
+/// Destination for outbox records (in-process bus, webhook, SSE, broker, ...).
+///
+/// Returning `Ok` acknowledges the record; it is then never delivered again. Returning
+/// `Err` schedules a retry according to the relay's [`RetryPolicy`].
+#[async_trait]
+pub trait OutboxSink: Send + Sync {
+    async fn deliver(&self, record: &OutboxRecord) -> anyhow::Result<()>;
+}
+
+/// Exponential backoff between delivery attempts.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Attempts before a row is moved to the dead-letter state.
+    pub max_attempts: u32,
+    #[serde(with = "humantime_serde")]
+    pub initial_backoff: Duration,
+    #[serde(with = "humantime_serde")]
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt after `attempts` failed ones (`attempts >= 1`).
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let exp = i32::try_from(attempts.saturating_sub(1)).unwrap_or(i32::MAX);
+        let secs = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.0).powi(exp);
+        if secs.is_finite() && secs < self.max_backoff.as_secs_f64() {
+            Duration::from_secs_f64(secs)
+        } else {
+            self.max_backoff
+        }
+    }
+}
+
+/// Retention of rows in terminal states.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrunePolicy {
+    /// How often the leader prunes.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Keep published rows this long (useful for debugging redeliveries).
+    #[serde(with = "humantime_serde")]
+    pub published_retention: Duration,
+    /// Keep dead letters this long; `None` keeps them until requeued or removed manually.
+    #[serde(with = "humantime_serde")]
+    pub dead_retention: Option<Duration>,
+}
+
+impl Default for PrunePolicy {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(3600),
+            published_retention: Duration::from_secs(24 * 3600),
+            dead_retention: None,
+        }
+    }
+}
+
+/// Relay configuration; deserializable from a module's config section.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RelayConfig {
+    /// Delay between polls when the previous batch was not full.
+    #[serde(with = "humantime_serde")]
+    pub poll_interval: Duration,
+    /// Maximum rows fetched per poll.
+    pub batch_size: u32,
+    /// How often a non-leader replica retries to become the relay; also the interval at
+    /// which the active relay verifies it still holds the lock.
+    #[serde(with = "humantime_serde")]
+    pub standby_interval: Duration,
+    pub retry: RetryPolicy,
+    pub prune: PrunePolicy,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(1),
+            batch_size: 100,
+            standby_interval: Duration::from_secs(5),
+            retry: RetryPolicy::default(),
+            prune: PrunePolicy::default(),
+        }
+    }
+}
+
+/// Outcome of one relay poll.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RelayTick {
+    pub fetched: usize,
+    pub published: usize,
+    pub retried: usize,
+    pub dead: usize,
+}
+
+/// Polls the outbox and hands due rows to the sink.
+///
+/// Only the replica holding the namespace's advisory lock relays; the others stay in
+/// standby and take over when the lock is released.
+pub struct OutboxRelay {
+    outbox: Outbox,
+    sink: Arc<dyn OutboxSink>,
+    config: RelayConfig,
+}
+
+impl OutboxRelay {
+    pub fn new(outbox: Outbox, sink: Arc<dyn OutboxSink>, config: RelayConfig) -> Self {
+        Self {
+            outbox,
+            sink,
+            config,
+        }
+    }
+
+    /// Run until `cancel` fires. Intended to be spawned from a stateful module's `start`.
+    pub async fn run(self, cancel: CancellationToken) -> Result<()> {
+        let elector = LeaderElector::new(
+            self.outbox.db(),
+            self.outbox.namespace(),
+            RELAY_LOCK_KEY,
+            LeaderConfig {
+                retry_interval: self.config.standby_interval,
+                check_interval: self.config.standby_interval,
+            },
+        );
+        elector.run(cancel, |lost| self.lead(lost)).await?;
+        tracing::info!(namespace = %self.outbox.namespace(), "Outbox relay stopped");
+        Ok(())
+    }
+
+    async fn lead(&self, cancel: CancellationToken) {
+        let mut next_prune = Instant::now();
+        while !cancel.is_cancelled() {
+            if Instant::now() >= next_prune {
+                if let Err(e) = self.prune().await {
+                    tracing::warn!(namespace = %self.outbox.namespace(), error = %e, "Outbox prune failed");
+                }
+                next_prune = Instant::now() + self.config.prune.interval;
+            }
+
+            let full = match self.tick().await {
+                Ok(tick) => tick.fetched >= self.config.batch_size as usize,
+                Err(e) => {
+                    tracing::warn!(namespace = %self.outbox.namespace(), error = %e, "Outbox poll failed");
+                    false
+                }
+            };
+            if !full {
+                sleep_or_cancel(&cancel, self.config.poll_interval).await;
+            }
+        }
+    }
+
+    /// Deliver one batch of due rows.
+    ///
+    /// Each row is marked only after the sink returned, so a crash mid-batch results in
+    /// redelivery rather than loss. Does not take the relay lock; [`run`](Self::run) does.
+    pub async fn tick(&self) -> Result<RelayTick> {
+        let rows = self.outbox.fetch_due(self.config.batch_size).await?;
+        let mut tick = RelayTick {
+            fetched: rows.len(),
+            ..Default::default()
+        };
+
+        for row in rows {
+            let attempts = row.attempts.max(0) + 1;
+            let record = match row.decode(self.outbox.namespace()) {
+                Ok(record) => record,
+                Err(err) => {
+                    tracing::error!(id = %row.id, error = %err, "Undecodable outbox row moved to dead letters");
+                    self.outbox.mark_dead(&row.id, attempts, &err).await?;
+                    tick.dead += 1;
+                    continue;
+                }
+            };
+
+            match self.sink.deliver(&record).await {
+                Ok(()) => {
+                    self.outbox.mark_published(&row.id, attempts).await?;
+                    tick.published += 1;
+                }
+                Err(e) => {
+                    let err = format!("{e:#}");
+                    if attempts >= i64::from(self.config.retry.max_attempts) {
+                        tracing::error!(
+                            id = %record.id,
+                            topic = %record.topic,
+                            attempts,
+                            error = %err,
+                            "Outbox delivery exhausted retries; moved to dead letters"
+                        );
+                        self.outbox.mark_dead(&row.id, attempts, &err).await?;
+                        tick.dead += 1;
+                    } else {
+                        let delay = self
+                            .config
+                            .retry
+                            .backoff(u32::try_from(attempts).unwrap_or(u32::MAX));
+                        let next =
+                            now_millis() + i64::try_from(delay.as_millis()).unwrap_or(i64::MAX / 2);
+                        tracing::warn!(
+                            id = %record.id,
+                            topic = %record.topic,
+                            attempts,
+                            retry_in = ?delay,
+                            error = %err,
+                            "Outbox delivery failed"
+                        );
+                        self.outbox
+                            .schedule_retry(&row.id, attempts, next, &err)
+                            .await?;
+                        tick.retried += 1;
+                    }
+                }
+            }
+        }
+        Ok(tick)
+    }
+
+    async fn prune(&self) -> Result<()> {
+        let policy = &self.config.prune;
+        let cutoff = |d: Duration| {
+            chrono::Duration::from_std(d)
+                .ok()
+                .and_then(|d| Utc::now().checked_sub_signed(d))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        };
+
+        let published = self
+            .outbox
+            .prune(OutboxStatus::Published, cutoff(policy.published_retention))
+            .await?;
+        let dead = match policy.dead_retention {
+            Some(d) => self.outbox.prune(OutboxStatus::Dead, cutoff(d)).await?,
+            None => 0,
+        };
+        if published + dead > 0 {
+            tracing::debug!(namespace = %self.outbox.namespace(), published, dead, "Pruned outbox rows");
+        }
+        Ok(())
+    }
+}
+
+async fn sleep_or_cancel(cancel: &CancellationToken, d: Duration) {
+    tokio::select! {
+        _ = cancel.cancelled() => {}
+        _ = tokio::time::sleep(d) => {}
+    }
+}
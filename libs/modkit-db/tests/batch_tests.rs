@@ -0,0 +1,72 @@
+//! Tests for the transactions shared by atomic batch groups.
+#![cfg(all(feature = "sqlite", feature = "sea-orm"))]
+
+use modkit_db::batch::BatchTransaction;
+use modkit_db::{ConnectOpts, DbHandle};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use tempfile::TempDir;
+
+async fn setup(dir: &TempDir) -> DatabaseConnection {
+    let dsn = format!(
+        "sqlite://{}?mode=rwc",
+        dir.path().join("batch.db").display()
+    );
+    let db = DbHandle::connect(&dsn, ConnectOpts::default())
+        .await
+        .unwrap();
+    let conn = db.sea_secure().conn().clone();
+    conn.execute_unprepared("CREATE TABLE notes (body TEXT NOT NULL)")
+        .await
+        .unwrap();
+    conn
+}
+
+async fn count(conn: &DatabaseConnection) -> i64 {
+    let row = conn
+        .query_one(sea_orm::Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT COUNT(*) AS n FROM notes",
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    row.try_get("", "n").unwrap()
+}
+
+#[tokio::test]
+async fn items_share_one_transaction_per_key() {
+    let dir = TempDir::new().unwrap();
+    let conn = setup(&dir).await;
+
+    let batch = BatchTransaction::new();
+    assert!(batch.is_empty().await);
+    for body in ["a", "b"] {
+        let txn = batch.on("notes", &conn).await.unwrap();
+        txn.execute_unprepared(&format!("INSERT INTO notes (body) VALUES ('{body}')"))
+            .await
+            .unwrap();
+    }
+    batch.rollback().await.unwrap();
+    assert_eq!(count(&conn).await, 0);
+
+    let batch = BatchTransaction::new();
+    let txn = batch.on("notes", &conn).await.unwrap();
+    txn.execute_unprepared("INSERT INTO notes (body) VALUES ('c')")
+        .await
+        .unwrap();
+    drop(txn);
+    batch.commit().await.unwrap();
+    assert_eq!(count(&conn).await, 1);
+}
+
+#[tokio::test]
+async fn commit_fails_while_a_handler_holds_the_transaction() {
+    let dir = TempDir::new().unwrap();
+    let conn = setup(&dir).await;
+
+    let batch = BatchTransaction::new();
+    let txn = batch.on("notes", &conn).await.unwrap();
+    assert!(batch.commit().await.is_err());
+    drop(txn);
+    assert_eq!(count(&conn).await, 0);
+}
@@ -0,0 +1,80 @@
+//! Tests for leader election over the SQLite file-lock fallback.
+#![cfg(feature = "sqlite")]
+
+use std::time::Duration;
+
+use modkit_db::{ConnectOpts, DbHandle, LeaderConfig, LeaderElector, LeaderState};
+use tokio_util::sync::CancellationToken;
+
+fn fast() -> LeaderConfig {
+    LeaderConfig {
+        retry_interval: Duration::from_millis(20),
+        check_interval: Duration::from_millis(20),
+    }
+}
+
+async fn connect(name: &str) -> DbHandle {
+    let dsn = format!("sqlite:file:memdb_{name}?mode=memory&cache=shared");
+    DbHandle::connect(&dsn, ConnectOpts::default())
+        .await
+        .unwrap()
+}
+
+fn unique_key(prefix: &str) -> String {
+    format!("{prefix}_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[tokio::test]
+async fn only_one_elector_leads_and_leadership_is_handed_over() {
+    let db = connect("leader_handover").await;
+    let key = unique_key("handover");
+    let a = LeaderElector::new(&db, "test_module", &key, fast());
+    let b = LeaderElector::new(&db, "test_module", &key, fast());
+    let cancel = CancellationToken::new();
+
+    let lead_a = a.campaign(&cancel).await.unwrap().unwrap();
+    assert!(a.is_leader());
+
+    let campaign_b = tokio::time::timeout(Duration::from_millis(150), b.campaign(&cancel)).await;
+    assert!(campaign_b.is_err(), "second elector must not become leader");
+    assert_eq!(*b.watch().borrow(), LeaderState::Follower);
+
+    lead_a.step_down().await;
+    assert!(!a.is_leader());
+
+    let lead_b = tokio::time::timeout(Duration::from_secs(2), b.campaign(&cancel))
+        .await
+        .expect("leadership handed over")
+        .unwrap()
+        .unwrap();
+    assert!(b.is_leader());
+    lead_b.step_down().await;
+}
+
+#[tokio::test]
+async fn shutdown_cancels_leadership_token_and_stops() {
+    let db = connect("leader_shutdown").await;
+    let elector = LeaderElector::new(&db, "test_module", unique_key("shutdown"), fast());
+    let mut state = elector.watch();
+    let cancel = CancellationToken::new();
+
+    let run = {
+        let cancel = cancel.clone();
+        async move {
+            elector
+                .run(cancel, |lost| async move { lost.cancelled().await })
+                .await
+        }
+    };
+    let handle = tokio::spawn(run);
+
+    state.wait_for(|s| *s == LeaderState::Leader).await.unwrap();
+    cancel.cancel();
+
+    tokio::time::timeout(Duration::from_secs(2), handle)
+        .await
+        .expect("run returns after shutdown")
+        .unwrap()
+        .unwrap();
+    assert_eq!(*state.borrow(), LeaderState::Stopped);
+}
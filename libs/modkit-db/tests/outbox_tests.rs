@@ -0,0 +1,194 @@
+//! Tests for the transactional outbox and its relay.
+#![cfg(all(feature = "sqlite", feature = "sea-orm"))]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use modkit_db::outbox::{
+    Outbox, OutboxRecord, OutboxRelay, OutboxSink, OutboxStatus, RelayConfig, RetryPolicy,
+};
+use modkit_db::{ConnectOpts, DbHandle};
+use modkit_security::SecurityCtx;
+use sea_orm::TransactionTrait;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct RecordingSink {
+    fail: AtomicBool,
+    seen: Mutex<Vec<OutboxRecord>>,
+}
+
+#[async_trait]
+impl OutboxSink for RecordingSink {
+    async fn deliver(&self, record: &OutboxRecord) -> anyhow::Result<()> {
+        if self.fail.load(Ordering::SeqCst) {
+            anyhow::bail!("sink unavailable");
+        }
+        self.seen.lock().unwrap().push(record.clone());
+        Ok(())
+    }
+}
+
+async fn setup(dir: &TempDir) -> Outbox {
+    let dsn = format!(
+        "sqlite://{}?mode=rwc",
+        dir.path().join("outbox.db").display()
+    );
+    let db = DbHandle::connect(&dsn, ConnectOpts::default())
+        .await
+        .unwrap();
+    let outbox = Outbox::new(&db, "test_module");
+    outbox.migrate().await.unwrap();
+    // Idempotent.
+    outbox.migrate().await.unwrap();
+    outbox
+}
+
+fn immediate_retry(max_attempts: u32) -> RelayConfig {
+    RelayConfig {
+        retry: RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            multiplier: 1.0,
+        },
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn committed_events_are_delivered_and_rolled_back_are_not() {
+    let dir = TempDir::new().unwrap();
+    let outbox = setup(&dir).await;
+    let secure = outbox_conn(&dir).await;
+    let tenant = Uuid::new_v4();
+    let ctx = SecurityCtx::for_tenant(tenant, Uuid::new_v4());
+
+    let txn = secure.conn().begin().await.unwrap();
+    let id = outbox
+        .enqueue(&txn, &ctx, "user.created", &serde_json::json!({"n": 1}))
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    let txn = secure.conn().begin().await.unwrap();
+    outbox
+        .enqueue(&txn, &ctx, "user.created", &serde_json::json!({"n": 2}))
+        .await
+        .unwrap();
+    txn.rollback().await.unwrap();
+
+    let sink = Arc::new(RecordingSink::default());
+    let relay = OutboxRelay::new(outbox.clone(), sink.clone(), RelayConfig::default());
+    let tick = relay.tick().await.unwrap();
+    assert_eq!(tick.fetched, 1);
+    assert_eq!(tick.published, 1);
+
+    let seen = sink.seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].id, id);
+    assert_eq!(seen[0].topic, "user.created");
+    assert_eq!(seen[0].payload["n"], 1);
+    assert_eq!(seen[0].security.scope().tenant_ids(), &[tenant]);
+
+    // Published rows are not delivered again.
+    assert_eq!(relay.tick().await.unwrap().fetched, 0);
+    let counts = outbox.counts().await.unwrap();
+    assert_eq!((counts.pending, counts.published, counts.dead), (0, 1, 0));
+}
+
+#[tokio::test]
+async fn failed_deliveries_retry_then_dead_letter_and_can_be_requeued() {
+    let dir = TempDir::new().unwrap();
+    let outbox = setup(&dir).await;
+    let ctx = SecurityCtx::root_ctx();
+
+    let id = outbox
+        .enqueue(
+            outbox_conn(&dir).await.conn(),
+            &ctx,
+            "t",
+            &serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+
+    let sink = Arc::new(RecordingSink::default());
+    sink.fail.store(true, Ordering::SeqCst);
+    let relay = OutboxRelay::new(outbox.clone(), sink.clone(), immediate_retry(2));
+
+    assert_eq!(relay.tick().await.unwrap().retried, 1);
+    assert_eq!(relay.tick().await.unwrap().dead, 1);
+    assert_eq!(relay.tick().await.unwrap().fetched, 0);
+
+    let dead = outbox.dead_letters(10).await.unwrap();
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].id, id);
+    assert_eq!(dead[0].attempts, 2);
+    assert!(dead[0]
+        .last_error
+        .as_deref()
+        .unwrap()
+        .contains("sink unavailable"));
+
+    assert!(outbox.requeue_dead(id).await.unwrap());
+    assert!(!outbox.requeue_dead(id).await.unwrap());
+    sink.fail.store(false, Ordering::SeqCst);
+    assert_eq!(relay.tick().await.unwrap().published, 1);
+    assert_eq!(sink.seen.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn prune_removes_only_terminal_rows() {
+    let dir = TempDir::new().unwrap();
+    let outbox = setup(&dir).await;
+    let ctx = SecurityCtx::root_ctx();
+    let conn = outbox_conn(&dir).await;
+
+    outbox.enqueue(conn.conn(), &ctx, "a", &1).await.unwrap();
+    let relay = OutboxRelay::new(
+        outbox.clone(),
+        Arc::new(RecordingSink::default()),
+        RelayConfig::default(),
+    );
+    relay.tick().await.unwrap();
+    outbox.enqueue(conn.conn(), &ctx, "b", &2).await.unwrap();
+
+    let future = chrono::Utc::now() + chrono::Duration::minutes(1);
+    assert_eq!(
+        outbox.prune(OutboxStatus::Pending, future).await.unwrap(),
+        0
+    );
+    assert_eq!(
+        outbox.prune(OutboxStatus::Published, future).await.unwrap(),
+        1
+    );
+    let counts = outbox.counts().await.unwrap();
+    assert_eq!((counts.pending, counts.published), (1, 0));
+}
+
+#[test]
+fn retry_backoff_is_exponential_and_capped() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        initial_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(10),
+        multiplier: 2.0,
+    };
+    assert_eq!(policy.backoff(1), Duration::from_secs(1));
+    assert_eq!(policy.backoff(3), Duration::from_secs(4));
+    assert_eq!(policy.backoff(50), Duration::from_secs(10));
+}
+
+async fn outbox_conn(dir: &TempDir) -> modkit_db::secure::SecureConn {
+    DbHandle::connect(
+        &format!("sqlite://{}", dir.path().join("outbox.db").display()),
+        ConnectOpts::default(),
+    )
+    .await
+    .unwrap()
+    .sea_secure()
+}
@@ -0,0 +1,213 @@
+//! AsyncAPI 3.0 document for the streaming operations
+//!
+//! SSE operations (`text/event-stream` responses) and WebSocket operations become channels
+//! addressed by their path. Payloads reference the same component schemas as the OpenAPI
+//! document, which are copied into `components.schemas`.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+use utoipa::openapi::{schema::Schema, RefOr};
+
+use crate::api::openapi_registry::OpenApiInfo;
+use crate::api::operation_builder::{axum_to_openapi_path, OperationSpec};
+
+const EVENT_STREAM: &str = "text/event-stream";
+/// Event type of SSE frames without an `event:` line
+const DEFAULT_SSE_EVENT: &str = "message";
+
+/// Build the AsyncAPI document from operation specs and component schemas.
+pub fn build_asyncapi(
+    info: &OpenApiInfo,
+    specs: &[OperationSpec],
+    schemas: &HashMap<String, RefOr<Schema>>,
+) -> Value {
+    let mut specs: Vec<&OperationSpec> = specs.iter().collect();
+    specs.sort_by(|a, b| a.path.cmp(&b.path).then(a.handler_id.cmp(&b.handler_id)));
+
+    let mut channels = Map::new();
+    let mut operations = Map::new();
+    for spec in specs {
+        let channel_id = ident(spec.operation_id.as_deref().unwrap_or(&spec.handler_id));
+        let mut messages = Map::new();
+        // (action from the server's point of view, message id)
+        let mut actions = Vec::new();
+
+        if let Some(r) = spec
+            .responses
+            .iter()
+            .find(|r| r.content_type == EVENT_STREAM)
+        {
+            let event = spec.sse_event_name.as_deref().unwrap_or(DEFAULT_SSE_EVENT);
+            let id = ident(event);
+            messages.insert(id.clone(), message(event, r.schema_name.as_deref()));
+            actions.push(("send", id));
+        }
+        if let Some(ws) = &spec.websocket {
+            messages.insert(
+                "incoming".to_string(),
+                message("incoming", Some(&ws.incoming_schema)),
+            );
+            messages.insert(
+                "outgoing".to_string(),
+                message("outgoing", Some(&ws.outgoing_schema)),
+            );
+            actions.push(("receive", "incoming".to_string()));
+            actions.push(("send", "outgoing".to_string()));
+        }
+        if actions.is_empty() {
+            continue;
+        }
+
+        let mut channel = json!({
+            "address": axum_to_openapi_path(&spec.path),
+            "messages": messages,
+        });
+        if let Some(description) = spec.description.as_ref().or(spec.summary.as_ref()) {
+            channel["description"] = json!(description);
+        }
+        if spec.websocket.is_some() {
+            channel["bindings"] = json!({ "ws": { "method": "GET", "bindingVersion": "0.1.0" } });
+        }
+        channels.insert(channel_id.clone(), channel);
+
+        for (action, message_id) in actions {
+            let op_id = format!("{channel_id}_{action}");
+            let mut op = json!({
+                "action": action,
+                "channel": { "$ref": format!("#/channels/{channel_id}") },
+                "messages": [
+                    { "$ref": format!("#/channels/{channel_id}/messages/{message_id}") }
+                ],
+            });
+            if let Some(summary) = &spec.summary {
+                op["summary"] = json!(summary);
+            }
+            if !spec.tags.is_empty() {
+                op["tags"] = json!(spec
+                    .tags
+                    .iter()
+                    .map(|t| json!({ "name": t }))
+                    .collect::<Vec<_>>());
+            }
+            if spec.sec_requirement.is_some() {
+                op["security"] = json!([{ "$ref": "#/components/securitySchemes/bearerAuth" }]);
+            }
+            if spec.websocket.is_none() {
+                op["bindings"] = json!({ "http": { "method": "GET", "bindingVersion": "0.3.0" } });
+            }
+            operations.insert(op_id, op);
+        }
+    }
+
+    let mut names: Vec<&String> = schemas.keys().collect();
+    names.sort();
+    let schemas: Map<String, Value> = names
+        .into_iter()
+        .map(|name| {
+            (
+                name.clone(),
+                serde_json::to_value(&schemas[name]).unwrap_or(Value::Null),
+            )
+        })
+        .collect();
+
+    let mut doc_info = json!({ "title": info.title, "version": info.version });
+    if let Some(description) = &info.description {
+        doc_info["description"] = json!(description);
+    }
+    json!({
+        "asyncapi": "3.0.0",
+        "info": doc_info,
+        "defaultContentType": "application/json",
+        "channels": channels,
+        "operations": operations,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" }
+            }
+        }
+    })
+}
+
+fn message(name: &str, schema_name: Option<&str>) -> Value {
+    let payload = match schema_name {
+        Some(schema) => json!({ "$ref": format!("#/components/schemas/{schema}") }),
+        None => json!({}),
+    };
+    json!({ "name": name, "contentType": "application/json", "payload": payload })
+}
+
+/// AsyncAPI component keys allow `[A-Za-z0-9_.-]`; everything else becomes `_`.
+fn ident(raw: &str) -> String {
+    raw.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::openapi_registry::{OpenApiInfo, OpenApiRegistryImpl};
+    use crate::api::operation_builder::{Missing, OperationBuilder};
+    use crate::api::problem::Problem;
+
+    #[test]
+    fn test_streaming_operations_become_channels() {
+        let registry = OpenApiRegistryImpl::new();
+        let router = OperationBuilder::<Missing, Missing, ()>::get("/users/events")
+            .operation_id("users.events")
+            .summary("User change feed")
+            .sse_event_name("user_changed")
+            .require_auth("users", "read")
+            .handler(|| async {})
+            .sse_json::<Problem>(&registry, "Stream of changes")
+            .register(axum::Router::new(), &registry);
+        let router = OperationBuilder::<Missing, Missing, ()>::get("/chat")
+            .operation_id("chat.connect")
+            .public()
+            .handler(|| async {})
+            .websocket::<serde_json::Value, Problem>(&registry, "Chat channel")
+            .register(router, &registry);
+        // Plain JSON operations are not channels
+        let _router = OperationBuilder::<Missing, Missing, ()>::get("/users")
+            .public()
+            .handler(|| async {})
+            .json_response(http::StatusCode::OK, "ok")
+            .register(router, &registry);
+
+        let doc = registry.build_asyncapi(&OpenApiInfo::default());
+        assert_eq!(doc["asyncapi"], "3.0.0");
+        assert_eq!(doc["channels"].as_object().unwrap().len(), 2);
+
+        let sse = &doc["channels"]["users.events"];
+        assert_eq!(sse["address"], "/users/events");
+        assert_eq!(
+            sse["messages"]["user_changed"]["payload"]["$ref"],
+            "#/components/schemas/Problem"
+        );
+        let op = &doc["operations"]["users.events_send"];
+        assert_eq!(op["action"], "send");
+        assert_eq!(
+            op["messages"][0]["$ref"],
+            "#/channels/users.events/messages/user_changed"
+        );
+        assert!(op["security"].is_array());
+
+        assert_eq!(
+            doc["operations"]["chat.connect_receive"]["action"],
+            "receive"
+        );
+        assert_eq!(
+            doc["channels"]["chat.connect"]["bindings"]["ws"]["method"],
+            "GET"
+        );
+        assert!(doc["components"]["schemas"].get("Problem").is_some());
+    }
+}
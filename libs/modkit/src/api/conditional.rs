@@ -0,0 +1,300 @@
+//! Conditional requests and optimistic concurrency with strong ETags (RFC 9110 §13)
+//!
+//! Handlers compute an [`ETag`] for the current state of a resource, either from a version
+//! column or from a hash of its representation, and check it against the request's
+//! [`Preconditions`]:
+//!
+//! ```rust,ignore
+//! pub async fn get_user(pre: Preconditions, /* ... */) -> UsersResult<Response> {
+//!     let dto = UserDto::from(svc.get_user(&ctx, id).await?);
+//!     Ok(conditional_json(&pre, ETag::from_content(&dto), dto))
+//! }
+//!
+//! pub async fn update_user(pre: Preconditions, /* ... */) -> UsersResult<Response> {
+//!     let current = UserDto::from(svc.get_user(&ctx, id).await?);
+//!     pre.check_if_match(Some(&ETag::from_content(&current)))?; // 412 on mismatch
+//!     let dto = UserDto::from(svc.update_user(&ctx, id, patch).await?);
+//!     Ok(with_etag(&ETag::from_content(&dto), Json(dto)))
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::fmt;
+
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::api::problem::Problem;
+use crate::http_errors::ErrorCode;
+
+/// Strong entity tag. Holds the opaque value; quoting happens when rendered as a header.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ETag(String);
+
+impl ETag {
+    /// ETag from a version column (a counter or a last-modified timestamp).
+    pub fn from_version(version: impl fmt::Display) -> Self {
+        Self(version.to_string())
+    }
+
+    /// ETag from a SHA-256 of the value's JSON representation.
+    ///
+    /// # Panics
+    /// If `value` cannot be serialized to JSON, which for response DTOs is a programming error.
+    pub fn from_content<T: Serialize + ?Sized>(value: &T) -> Self {
+        let bytes = serde_json::to_vec(value).expect("ETag content must serialize to JSON");
+        Self::from_bytes(&bytes)
+    }
+
+    /// ETag from a SHA-256 of raw bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let digest = Sha256::digest(bytes);
+        // 128 bits are plenty to tell representations apart
+        Self(digest[..16].iter().map(|b| format!("{b:02x}")).collect())
+    }
+
+    /// Opaque value without quotes.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn to_header_value(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.to_string()).expect("ETag values are visible ASCII")
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\"", self.0)
+    }
+}
+
+/// Parsed `If-Match` / `If-None-Match` header field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TagList {
+    /// `*`
+    Any,
+    /// `(weak, opaque)` pairs
+    Tags(Vec<(bool, String)>),
+}
+
+impl TagList {
+    fn parse(headers: &HeaderMap, name: header::HeaderName) -> Option<Self> {
+        let mut tags = Vec::new();
+        let mut present = false;
+        for value in headers.get_all(name) {
+            present = true;
+            let Ok(value) = value.to_str() else { continue };
+            if value.trim() == "*" {
+                return Some(TagList::Any);
+            }
+            tags.extend(parse_tags(value));
+        }
+        present.then_some(TagList::Tags(tags))
+    }
+
+    /// Strong comparison: weak tags never match.
+    fn matches_strong(&self, etag: &ETag) -> bool {
+        match self {
+            TagList::Any => true,
+            TagList::Tags(tags) => tags.iter().any(|(weak, t)| !weak && t == etag.as_str()),
+        }
+    }
+
+    /// Weak comparison: only the opaque values are compared.
+    fn matches_weak(&self, etag: &ETag) -> bool {
+        match self {
+            TagList::Any => true,
+            TagList::Tags(tags) => tags.iter().any(|(_, t)| t == etag.as_str()),
+        }
+    }
+}
+
+/// Parse `W/"a", "b"`; malformed entries are skipped.
+fn parse_tags(mut s: &str) -> Vec<(bool, String)> {
+    let mut tags = Vec::new();
+    loop {
+        s = s.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+        if s.is_empty() {
+            return tags;
+        }
+        let weak = s.starts_with("W/");
+        if weak {
+            s = &s[2..];
+        }
+        let Some(rest) = s.strip_prefix('"') else {
+            // Skip to the next list member
+            match s.find(',') {
+                Some(i) => {
+                    s = &s[i..];
+                    continue;
+                }
+                None => return tags,
+            }
+        };
+        match rest.find('"') {
+            Some(end) => {
+                tags.push((weak, rest[..end].to_string()));
+                s = &rest[end + 1..];
+            }
+            None => return tags,
+        }
+    }
+}
+
+/// Request preconditions from `If-Match` and `If-None-Match`. Extract it in a handler like any
+/// other axum extractor; extraction never fails.
+#[derive(Clone, Debug, Default)]
+pub struct Preconditions {
+    if_match: Option<TagList>,
+    if_none_match: Option<TagList>,
+}
+
+impl Preconditions {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            if_match: TagList::parse(headers, header::IF_MATCH),
+            if_none_match: TagList::parse(headers, header::IF_NONE_MATCH),
+        }
+    }
+
+    pub fn has_if_match(&self) -> bool {
+        self.if_match.is_some()
+    }
+
+    /// Check `If-Match` before a mutation. `current` is `None` when the resource does not exist.
+    /// Passes when the header is absent; returns a 412 Problem when it does not match.
+    #[allow(clippy::result_large_err)] // propagated with `?` from handlers returning Problem
+    pub fn check_if_match(&self, current: Option<&ETag>) -> Result<(), Problem> {
+        let Some(list) = &self.if_match else {
+            return Ok(());
+        };
+        let matched = current.is_some_and(|etag| list.matches_strong(etag));
+        if matched {
+            Ok(())
+        } else {
+            Err(ErrorCode::modkit_errors_precondition_failed_v1()
+                .to_problem("The resource has changed since it was read; fetch it and retry"))
+        }
+    }
+
+    /// Like [`check_if_match`](Self::check_if_match), but a missing `If-Match` is a 428 Problem.
+    #[allow(clippy::result_large_err)]
+    pub fn require_if_match(&self, current: Option<&ETag>) -> Result<(), Problem> {
+        if self.if_match.is_none() {
+            return Err(ErrorCode::modkit_errors_precondition_required_v1()
+                .to_problem("This request must carry an If-Match header"));
+        }
+        self.check_if_match(current)
+    }
+
+    /// True when `If-None-Match` matches `current`, i.e. a GET should answer 304.
+    pub fn is_not_modified(&self, current: &ETag) -> bool {
+        self.if_none_match
+            .as_ref()
+            .is_some_and(|list| list.matches_weak(current))
+    }
+}
+
+impl<S> FromRequestParts<S> for Preconditions
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    #[allow(clippy::manual_async_fn)]
+    fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> impl core::future::Future<Output = Result<Self, Self::Rejection>> + Send {
+        let pre = Self::from_headers(&parts.headers);
+        async move { Ok(pre) }
+    }
+}
+
+/// Attach `ETag` to any response.
+pub fn with_etag<R: IntoResponse>(etag: &ETag, resp: R) -> Response {
+    let mut resp = resp.into_response();
+    resp.headers_mut()
+        .insert(header::ETAG, etag.to_header_value());
+    resp
+}
+
+/// 304 Not Modified carrying the current `ETag`.
+pub fn not_modified(etag: &ETag) -> Response {
+    with_etag(etag, StatusCode::NOT_MODIFIED)
+}
+
+/// 304 when `If-None-Match` matches `etag`, otherwise 200 with the JSON body and `ETag`.
+pub fn conditional_json<T: Serialize>(pre: &Preconditions, etag: ETag, value: T) -> Response {
+    if pre.is_not_modified(&etag) {
+        not_modified(&etag)
+    } else {
+        with_etag(&etag, Json(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pre(name: header::HeaderName, value: &str) -> Preconditions {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_str(value).unwrap());
+        Preconditions::from_headers(&headers)
+    }
+
+    #[test]
+    fn test_etag_rendering_and_content_hash() {
+        assert_eq!(ETag::from_version(7).to_string(), "\"7\"");
+        let a = ETag::from_content(&serde_json::json!({ "name": "a" }));
+        let b = ETag::from_content(&serde_json::json!({ "name": "b" }));
+        assert_ne!(a, b);
+        assert_eq!(a.as_str().len(), 32);
+    }
+
+    #[test]
+    fn test_if_match_uses_strong_comparison() {
+        let current = ETag::from_version(3);
+
+        assert!(Preconditions::default()
+            .check_if_match(Some(&current))
+            .is_ok());
+        assert!(pre(header::IF_MATCH, "\"2\", \"3\"")
+            .check_if_match(Some(&current))
+            .is_ok());
+        assert!(pre(header::IF_MATCH, "*")
+            .check_if_match(Some(&current))
+            .is_ok());
+        assert!(pre(header::IF_MATCH, "*").check_if_match(None).is_err());
+
+        let err = pre(header::IF_MATCH, "W/\"3\"")
+            .check_if_match(Some(&current))
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::PRECONDITION_FAILED);
+
+        let err = Preconditions::default()
+            .require_if_match(Some(&current))
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::PRECONDITION_REQUIRED);
+    }
+
+    #[test]
+    fn test_if_none_match_answers_not_modified() {
+        let current = ETag::from_version(3);
+        let p = pre(header::IF_NONE_MATCH, "W/\"3\"");
+        assert!(p.is_not_modified(&current));
+
+        let resp = conditional_json(&p, current.clone(), "body");
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers()[header::ETAG], "\"3\"");
+
+        let resp = conditional_json(&Preconditions::default(), current, "body");
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[header::ETAG], "\"3\"");
+    }
+}
@@ -18,6 +18,10 @@ pub enum ApiError<D> {
     /// Domain business logic errors
     #[error(transparent)]
     Domain(D),
+
+    /// A ready-made Problem, e.g. a failed `If-Match` precondition
+    #[error("{}", .0.title)]
+    Problem(Problem),
 }
 
 // Manual implementations to avoid conflicts with generic From trait
@@ -39,6 +43,12 @@ impl<D> From<ODataError> for ApiError<D> {
     }
 }
 
+impl<D> From<Problem> for ApiError<D> {
+    fn from(p: Problem) -> Self {
+        ApiError::Problem(p)
+    }
+}
+
 impl<D> IntoResponse for ApiError<D>
 where
     D: Into<Problem>,
@@ -54,6 +64,7 @@ where
                 // Convert the domain error to a Problem, which then becomes a response
                 e.into().into_response()
             }
+            ApiError::Problem(p) => p.into_response(),
         }
     }
 }
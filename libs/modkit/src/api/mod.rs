@@ -4,6 +4,8 @@
 //! that API operations cannot be registered unless both a handler and at least one
 //! response are specified.
 
+pub mod asyncapi;
+pub mod conditional;
 pub mod error;
 pub mod error_layer;
 pub mod odata;
@@ -16,18 +18,21 @@ pub mod trace_layer;
 #[cfg(test)]
 mod odata_policy_tests;
 
+pub use conditional::{conditional_json, not_modified, with_etag, ETag, Preconditions};
 pub use error::ApiError;
 pub use error_layer::{
     error_mapping_middleware, extract_trace_id, map_error_to_problem, IntoProblem,
 };
 pub use openapi_registry::{ensure_schema, OpenApiInfo, OpenApiRegistry, OpenApiRegistryImpl};
 pub use operation_builder::{
-    state, Missing, OperationBuilder, OperationSpec, ParamLocation, ParamSpec, Present,
-    RateLimitSpec, ResponseSpec,
+    state, ApiVersionSpec, DeprecationSpec, Missing, OperationBuilder, OperationSpec,
+    ParamLocation, ParamSpec, Present, RateLimitPartition, RateLimitSpec, ResponseHeaderSpec,
+    ResponseSpec, VersionRouting, WebSocketSpec, DEPRECATION_HEADERS, ETAG_HEADER,
+    RATE_LIMIT_HEADERS,
 };
 pub use problem::{
     bad_request, conflict, internal_error, not_found, Problem, ValidationError,
-    APPLICATION_PROBLEM_JSON,
+    ValidationViolation, APPLICATION_PROBLEM_JSON,
 };
 pub use trace_layer::{WithRequestContext, WithTraceContext};
 
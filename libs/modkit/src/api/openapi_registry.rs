@@ -10,6 +10,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use utoipa::openapi::{
     content::ContentBuilder,
+    header::HeaderBuilder,
     info::InfoBuilder,
     path::{
         HttpMethod, OperationBuilder as UOperationBuilder, ParameterBuilder, ParameterIn,
@@ -19,10 +20,10 @@ use utoipa::openapi::{
     response::{ResponseBuilder, ResponsesBuilder},
     schema::{ComponentsBuilder, ObjectBuilder, Schema, SchemaFormat, SchemaType},
     security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
-    OpenApi, OpenApiBuilder, Ref, RefOr, Required,
+    Deprecated, OpenApi, OpenApiBuilder, Ref, RefOr, Required,
 };
 
-use crate::api::{operation_builder, problem};
+use crate::api::{asyncapi, operation_builder, problem};
 
 /// Type alias for schema collections used in API operations.
 type SchemaCollection = Vec<(String, RefOr<Schema>)>;
@@ -101,6 +102,43 @@ impl OpenApiRegistryImpl {
     /// # Arguments
     /// * `info` - OpenAPI document metadata (title, version, description)
     pub fn build_openapi(&self, info: &OpenApiInfo) -> Result<OpenApi> {
+        self.build_document(info, None)
+    }
+
+    /// Build the OpenAPI document of one API version: only operations declared with that
+    /// version, listed under the path clients call (without the prefix for media-type
+    /// versioned operations). `info.version` is replaced by the version name.
+    pub fn build_openapi_for_version(&self, info: &OpenApiInfo, version: &str) -> Result<OpenApi> {
+        let info = OpenApiInfo {
+            version: version.to_string(),
+            ..info.clone()
+        };
+        self.build_document(&info, Some(version))
+    }
+
+    /// Build the AsyncAPI 3.0 document of the SSE and WebSocket operations.
+    pub fn build_asyncapi(&self, info: &OpenApiInfo) -> serde_json::Value {
+        let specs: Vec<_> = self
+            .operation_specs
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        asyncapi::build_asyncapi(info, &specs, &self.components_registry.load())
+    }
+
+    /// Names of the API versions declared by registered operations, sorted.
+    pub fn api_versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = self
+            .operation_specs
+            .iter()
+            .filter_map(|e| e.value().version.as_ref().map(|v| v.name.clone()))
+            .collect();
+        versions.sort();
+        versions.dedup();
+        versions
+    }
+
+    fn build_document(&self, info: &OpenApiInfo, version: Option<&str>) -> Result<OpenApi> {
         use http::Method;
 
         // Log operation count for visibility
@@ -111,6 +149,11 @@ impl OpenApiRegistryImpl {
         let mut paths = PathsBuilder::new();
 
         for spec in self.operation_specs.iter().map(|e| e.value().clone()) {
+            let spec_version = spec.version.as_ref();
+            if version.is_some() && spec_version.map(|v| v.name.as_str()) != version {
+                continue;
+            }
+
             let mut op = UOperationBuilder::new()
                 .operation_id(spec.operation_id.clone().or(Some(spec.handler_id.clone())))
                 .summary(spec.summary.clone())
@@ -120,9 +163,9 @@ impl OpenApiRegistryImpl {
                 op = op.tag(tag.clone());
             }
 
-            // Vendor extensions for rate limit, if present (string values)
+            // Vendor extensions for rate limit and versioning, if present
+            let mut ext = utoipa::openapi::extensions::Extensions::default();
             if let Some(rl) = spec.rate_limit.as_ref() {
-                let mut ext = utoipa::openapi::extensions::Extensions::default();
                 ext.insert("x-rate-limit-rps".to_string(), serde_json::json!(rl.rps));
                 ext.insert(
                     "x-rate-limit-burst".to_string(),
@@ -132,8 +175,33 @@ impl OpenApiRegistryImpl {
                     "x-in-flight-limit".to_string(),
                     serde_json::json!(rl.in_flight),
                 );
+            }
+            if let Some(v) = spec_version {
+                ext.insert("x-api-version".to_string(), serde_json::json!(v.name));
+            }
+            // WebSocket messages from the server's point of view, as AsyncAPI 3 puts them
+            if let Some(ws) = &spec.websocket {
+                let schema_ref = |name: &str| format!("#/components/schemas/{name}");
+                ext.insert(
+                    "x-websocket".to_string(),
+                    serde_json::json!({
+                        "receive": { "$ref": schema_ref(&ws.incoming_schema) },
+                        "send": { "$ref": schema_ref(&ws.outgoing_schema) },
+                    }),
+                );
+            }
+            if let Some(sunset) = spec.deprecation.as_ref().and_then(|d| d.sunset) {
+                ext.insert(
+                    "x-sunset".to_string(),
+                    serde_json::json!(sunset.to_rfc3339()),
+                );
+            }
+            if !ext.is_empty() {
                 op = op.extensions(Some(ext));
             }
+            if spec.deprecation.is_some() {
+                op = op.deprecated(Some(Deprecated::True));
+            }
 
             // Parameters
             for p in &spec.params {
@@ -150,13 +218,11 @@ impl OpenApiRegistryImpl {
                         Required::False
                     };
 
-                let schema_type = match p.param_type.as_str() {
-                    "integer" => SchemaType::Type(utoipa::openapi::schema::Type::Integer),
-                    "number" => SchemaType::Type(utoipa::openapi::schema::Type::Number),
-                    "boolean" => SchemaType::Type(utoipa::openapi::schema::Type::Boolean),
-                    _ => SchemaType::Type(utoipa::openapi::schema::Type::String),
-                };
-                let schema = Schema::Object(ObjectBuilder::new().schema_type(schema_type).build());
+                let schema = Schema::Object(
+                    ObjectBuilder::new()
+                        .schema_type(json_schema_type(&p.param_type))
+                        .build(),
+                );
 
                 let param = ParameterBuilder::new()
                     .name(&p.name)
@@ -233,26 +299,18 @@ impl OpenApiRegistryImpl {
                 let is_json_like = r.content_type == "application/json"
                     || r.content_type == problem::APPLICATION_PROBLEM_JSON
                     || r.content_type == "text/event-stream";
-                let resp = if is_json_like {
+                let content = if is_json_like {
                     if let Some(name) = &r.schema_name {
                         // Manually build content to preserve the correct content type
-                        let content = ContentBuilder::new()
+                        ContentBuilder::new()
                             .schema(Some(RefOr::Ref(Ref::new(format!(
                                 "#/components/schemas/{}",
                                 name
                             )))))
-                            .build();
-                        ResponseBuilder::new()
-                            .description(&r.description)
-                            .content(r.content_type, content)
                             .build()
                     } else {
-                        let content = ContentBuilder::new()
+                        ContentBuilder::new()
                             .schema(Some(Schema::Object(ObjectBuilder::new().build())))
-                            .build();
-                        ResponseBuilder::new()
-                            .description(&r.description)
-                            .content(r.content_type, content)
                             .build()
                     }
                 } else {
@@ -262,12 +320,39 @@ impl OpenApiRegistryImpl {
                             .format(Some(SchemaFormat::Custom(r.content_type.into())))
                             .build(),
                     );
-                    let content = ContentBuilder::new().schema(Some(schema)).build();
-                    ResponseBuilder::new()
-                        .description(&r.description)
-                        .content(r.content_type, content)
-                        .build()
+                    ContentBuilder::new().schema(Some(schema)).build()
+                };
+                let mut resp = ResponseBuilder::new().description(&r.description);
+                // The 101 of a WebSocket upgrade has no body; messages are under `x-websocket`
+                if r.content_type != operation_builder::WEBSOCKET_CONTENT_TYPE {
+                    resp = resp.content(r.content_type, content);
+                }
+                let deprecation_headers: Vec<_> = match &spec.deprecation {
+                    Some(d) => operation_builder::DEPRECATION_HEADERS
+                        .iter()
+                        .filter(|h| match h.name {
+                            "Sunset" => d.sunset.is_some(),
+                            "Link" => d.successor.is_some(),
+                            _ => true,
+                        })
+                        .collect(),
+                    None => Vec::new(),
                 };
+                for h in r.headers.iter().chain(deprecation_headers) {
+                    let schema = Schema::Object(
+                        ObjectBuilder::new()
+                            .schema_type(json_schema_type(h.header_type))
+                            .build(),
+                    );
+                    resp = resp.header(
+                        h.name,
+                        HeaderBuilder::new()
+                            .schema(schema)
+                            .description(Some(h.description))
+                            .build(),
+                    );
+                }
+                let resp = resp.build();
                 responses = responses.response(r.status.to_string(), resp);
             }
             op = op.responses(responses.build());
@@ -292,7 +377,16 @@ impl OpenApiRegistryImpl {
 
             let item = PathItemBuilder::new().operation(method, op.build()).build();
             // Convert Axum-style path to OpenAPI-style path
-            let openapi_path = operation_builder::axum_to_openapi_path(&spec.path);
+            let path = match spec_version {
+                Some(v)
+                    if version.is_some()
+                        && v.routing == operation_builder::VersionRouting::MediaType =>
+                {
+                    spec.unversioned_path()
+                }
+                _ => &spec.path,
+            };
+            let openapi_path = operation_builder::axum_to_openapi_path(path);
             paths = paths.path(openapi_path, item);
         }
 
@@ -380,6 +474,16 @@ impl OpenApiRegistry for OpenApiRegistryImpl {
     }
 }
 
+/// Map a JSON Schema type name to its OpenAPI schema type (unknown names map to string).
+fn json_schema_type(name: &str) -> SchemaType {
+    match name {
+        "integer" => SchemaType::Type(utoipa::openapi::schema::Type::Integer),
+        "number" => SchemaType::Type(utoipa::openapi::schema::Type::Number),
+        "boolean" => SchemaType::Type(utoipa::openapi::schema::Type::Boolean),
+        _ => SchemaType::Type(utoipa::openapi::schema::Type::String),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -410,11 +514,20 @@ mod tests {
                 content_type: "application/json",
                 description: "Success".to_string(),
                 schema_name: None,
+                headers: Vec::new(),
             }],
             handler_id: "get_test".to_string(),
             sec_requirement: None,
             is_public: false,
             rate_limit: None,
+            rate_limit_partition: None,
+            idempotent: false,
+            version: None,
+            deprecation: None,
+            websocket: None,
+            sse_event_name: None,
+            batch_atomic: false,
+            timeout: None,
             allowed_request_content_types: None,
         };
 
@@ -466,16 +579,34 @@ mod tests {
                 param_type: "string".to_string(),
             }],
             request_body: None,
-            responses: vec![ResponseSpec {
-                status: 200,
-                content_type: "application/json",
-                description: "User found".to_string(),
-                schema_name: None,
-            }],
+            responses: vec![
+                ResponseSpec {
+                    status: 200,
+                    content_type: "application/json",
+                    description: "User found".to_string(),
+                    schema_name: None,
+                    headers: Vec::new(),
+                },
+                ResponseSpec {
+                    status: 429,
+                    content_type: problem::APPLICATION_PROBLEM_JSON,
+                    description: "Too Many Requests".to_string(),
+                    schema_name: None,
+                    headers: operation_builder::RATE_LIMIT_HEADERS.to_vec(),
+                },
+            ],
             handler_id: "get_users_id".to_string(),
             sec_requirement: None,
             is_public: false,
             rate_limit: None,
+            rate_limit_partition: None,
+            idempotent: false,
+            version: None,
+            deprecation: None,
+            websocket: None,
+            sse_event_name: None,
+            batch_atomic: false,
+            timeout: None,
             allowed_request_content_types: None,
         };
 
@@ -492,6 +623,87 @@ mod tests {
         let get_op = paths.get("/users/{id}").unwrap().get("get").unwrap();
         assert_eq!(get_op.get("operationId").unwrap(), "get_user");
         assert_eq!(get_op.get("summary").unwrap(), "Get user by ID");
+
+        // Response headers are documented as integers
+        let retry_after = &get_op["responses"]["429"]["headers"]["Retry-After"];
+        assert_eq!(retry_after["schema"]["type"], "integer");
+        assert!(get_op["responses"]["429"]["headers"]
+            .get("RateLimit-Reset")
+            .is_some());
+    }
+
+    #[test]
+    fn test_websocket_operation_documents_messages() {
+        use crate::api::operation_builder::{Missing, OperationBuilder};
+        use crate::api::problem::Problem;
+
+        let registry = OpenApiRegistryImpl::new();
+        let _router = OperationBuilder::<Missing, Missing, ()>::get("/events")
+            .operation_id("events.stream")
+            .public()
+            .handler(|| async {})
+            .websocket::<serde_json::Value, Problem>(&registry, "Event channel")
+            .register(axum::Router::new(), &registry);
+
+        let doc = registry.build_openapi(&OpenApiInfo::default()).unwrap();
+        let json = serde_json::to_value(&doc).unwrap();
+        let op = &json["paths"]["/events"]["get"];
+        assert_eq!(
+            op["x-websocket"]["send"]["$ref"],
+            "#/components/schemas/Problem"
+        );
+        assert!(op["x-websocket"]["receive"]["$ref"].is_string());
+        assert!(op["responses"]["101"].get("content").is_none());
+    }
+
+    #[test]
+    fn test_build_openapi_for_version() {
+        use crate::api::operation_builder::{Missing, OperationBuilder};
+
+        let registry = OpenApiRegistryImpl::new();
+        let ok = ResponseSpec {
+            status: 200,
+            content_type: "application/json",
+            description: "Success".to_string(),
+            schema_name: None,
+            headers: Vec::new(),
+        };
+        let mut v1 = OperationBuilder::<Missing, Missing, ()>::get("/users")
+            .version("v1")
+            .successor_version("/v2/users")
+            .spec()
+            .clone();
+        v1.responses.push(ok.clone());
+        let mut v2 = OperationBuilder::<Missing, Missing, ()>::get("/users")
+            .version_by_media_type("v2")
+            .spec()
+            .clone();
+        v2.responses.push(ok);
+        registry.register_operation(&v1);
+        registry.register_operation(&v2);
+        assert_eq!(registry.api_versions(), vec!["v1", "v2"]);
+
+        let info = OpenApiInfo::default();
+        let all = serde_json::to_value(registry.build_openapi(&info).unwrap()).unwrap();
+        assert!(all["paths"].get("/v1/users").is_some());
+        assert!(all["paths"].get("/v2/users").is_some());
+
+        let doc = registry.build_openapi_for_version(&info, "v1").unwrap();
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["info"]["version"], "v1");
+        let get_op = &json["paths"]["/v1/users"]["get"];
+        assert_eq!(get_op["deprecated"], true);
+        assert!(get_op["responses"]["200"]["headers"].get("Link").is_some());
+        assert!(get_op["responses"]["200"]["headers"]
+            .get("Sunset")
+            .is_none());
+        assert!(json["paths"].get("/v2/users").is_none());
+
+        // Media-type versioned operations are listed under the plain path
+        let doc = registry.build_openapi_for_version(&info, "v2").unwrap();
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["paths"]["/users"]["get"]["x-api-version"], "v2");
+        assert!(json["paths"]["/users"]["get"].get("deprecated").is_none());
     }
 
     #[test]
@@ -529,11 +741,20 @@ mod tests {
                 content_type: "application/json",
                 description: "Upload successful".to_string(),
                 schema_name: None,
+                headers: Vec::new(),
             }],
             handler_id: "post_upload".to_string(),
             sec_requirement: None,
             is_public: false,
             rate_limit: None,
+            rate_limit_partition: None,
+            idempotent: false,
+            version: None,
+            deprecation: None,
+            websocket: None,
+            sse_event_name: None,
+            batch_atomic: false,
+            timeout: None,
             allowed_request_content_types: Some(vec!["application/octet-stream"]),
         };
 
@@ -13,6 +13,7 @@
 
 use axum::{handler::Handler, routing::MethodRouter, Router};
 use http::Method;
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
 use crate::api::problem;
@@ -159,8 +160,52 @@ pub struct ResponseSpec {
     pub description: String,
     /// Name of a registered component schema (if any).
     pub schema_name: Option<String>,
+    /// Response headers to document.
+    pub headers: Vec<ResponseHeaderSpec>,
 }
 
+/// Response header documented in OpenAPI
+#[derive(Clone, Debug)]
+pub struct ResponseHeaderSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema type (string, integer, etc.)
+    pub header_type: &'static str,
+}
+
+const RETRY_AFTER_HEADER: ResponseHeaderSpec = ResponseHeaderSpec {
+    name: "Retry-After",
+    description: "Seconds to wait before retrying",
+    header_type: "integer",
+};
+
+/// Header carrying the representation's entity tag
+pub const ETAG_HEADER: ResponseHeaderSpec = ResponseHeaderSpec {
+    name: "ETag",
+    description: "Strong entity tag of the returned representation",
+    header_type: "string",
+};
+
+/// Headers the ingress sends with 429 responses
+pub const RATE_LIMIT_HEADERS: &[ResponseHeaderSpec] = &[
+    RETRY_AFTER_HEADER,
+    ResponseHeaderSpec {
+        name: "RateLimit-Limit",
+        description: "Request quota of the route (bucket capacity)",
+        header_type: "integer",
+    },
+    ResponseHeaderSpec {
+        name: "RateLimit-Remaining",
+        description: "Requests left in the current quota",
+        header_type: "integer",
+    },
+    ResponseHeaderSpec {
+        name: "RateLimit-Reset",
+        description: "Seconds until the quota is fully restored",
+        header_type: "integer",
+    },
+];
+
 /// Security requirement for an operation (resource:action pattern)
 #[derive(Clone, Debug)]
 pub struct OperationSecRequirement {
@@ -188,6 +233,23 @@ pub struct OperationSpec {
     pub is_public: bool,
     /// Optional rate & concurrency limits for this operation
     pub rate_limit: Option<RateLimitSpec>,
+    /// Optional rate-limit partition overriding the ingress default
+    pub rate_limit_partition: Option<RateLimitPartition>,
+    /// Ingress honours the `Idempotency-Key` header: retries replay the first response
+    pub idempotent: bool,
+    /// API version the operation belongs to and how clients select it
+    pub version: Option<ApiVersionSpec>,
+    /// Deprecation notice, announced by the ingress with `Deprecation`/`Sunset`/`Link` headers
+    pub deprecation: Option<DeprecationSpec>,
+    /// Message schemas when the operation upgrades to a WebSocket
+    pub websocket: Option<WebSocketSpec>,
+    /// `event:` name of the frames of an SSE operation (AsyncAPI message name)
+    pub sse_event_name: Option<String>,
+    /// May run inside an atomic `$batch` group, on the group's shared transaction
+    pub batch_atomic: bool,
+    /// Optional request timeout overriding the ingress default.
+    /// Streaming (SSE / octet-stream) operations are exempt from timeouts.
+    pub timeout: Option<std::time::Duration>,
     /// Optional whitelist of allowed request Content-Type values (without parameters).
     /// Example: Some(vec!["application/json", "multipart/form-data", "application/pdf"])
     /// When set, ingress middleware will enforce these types and return HTTP 415 for
@@ -196,6 +258,89 @@ pub struct OperationSpec {
     pub allowed_request_content_types: Option<Vec<&'static str>>,
 }
 
+impl OperationSpec {
+    /// Path without the version prefix, as clients using media-type versioning call it.
+    pub fn unversioned_path(&self) -> &str {
+        match &self.version {
+            Some(v) => self
+                .path
+                .strip_prefix('/')
+                .and_then(|p| p.strip_prefix(v.name.as_str()))
+                .filter(|p| p.starts_with('/'))
+                .unwrap_or(&self.path),
+            None => &self.path,
+        }
+    }
+}
+
+/// How clients select one version of a versioned operation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VersionRouting {
+    /// The version is the first path segment: `/v2/users`
+    #[default]
+    PathPrefix,
+    /// The plain path with a `version` parameter on the `Accept` (or `Content-Type`) media
+    /// type, e.g. `application/json; version=2`. The prefixed path keeps working too.
+    MediaType,
+}
+
+/// Version declaration of an operation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiVersionSpec {
+    /// Version name, also the path prefix (`v1`, `v2`)
+    pub name: String,
+    pub routing: VersionRouting,
+}
+
+impl ApiVersionSpec {
+    /// Whether a media-type `version` parameter selects this version; `2` and `v2` both
+    /// select `v2`.
+    pub fn matches_param(&self, value: &str) -> bool {
+        let value = value.trim_matches('"');
+        self.name == value || self.name.strip_prefix('v') == Some(value)
+    }
+}
+
+/// Deprecation notice of an operation
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeprecationSpec {
+    /// When the operation stops being served (`Sunset` header, RFC 8594)
+    pub sunset: Option<chrono::DateTime<chrono::Utc>>,
+    /// URL of the replacement (`Link: <...>; rel="successor-version"`)
+    pub successor: Option<String>,
+}
+
+/// Pseudo content type of the `101 Switching Protocols` response of WebSocket operations
+pub const WEBSOCKET_CONTENT_TYPE: &str = "websocket";
+
+/// Message schemas of a WebSocket operation (component schema names)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebSocketSpec {
+    /// Messages the client sends
+    pub incoming_schema: String,
+    /// Messages the server sends
+    pub outgoing_schema: String,
+}
+
+/// Headers the ingress sends with responses of deprecated operations
+pub const DEPRECATION_HEADERS: &[ResponseHeaderSpec] = &[
+    ResponseHeaderSpec {
+        name: "Deprecation",
+        description: "Present when the operation is deprecated",
+        header_type: "string",
+    },
+    ResponseHeaderSpec {
+        name: "Sunset",
+        description: "HTTP date after which the operation is no longer served",
+        header_type: "string",
+    },
+    ResponseHeaderSpec {
+        name: "Link",
+        description: "Successor version of the operation (rel=\"successor-version\")",
+        header_type: "string",
+    },
+];
+
 /// Per-operation rate & concurrency limit specification
 #[derive(Clone, Debug, Default)]
 pub struct RateLimitSpec {
@@ -207,6 +352,23 @@ pub struct RateLimitSpec {
     pub in_flight: u32,
 }
 
+/// What a route's rate-limit buckets are keyed by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitPartition {
+    /// One bucket shared by all callers of the route
+    #[default]
+    Route,
+    /// Client IP, taken from forwarding headers only when the peer is a trusted proxy
+    ClientIp,
+    /// Authenticated subject id
+    Subject,
+    /// Tenant from the caller's `SecurityCtx` scope
+    Tenant,
+    /// Value of the configured API key header
+    ApiKey,
+}
+
 //
 pub trait OperationBuilderODataExt<S, H, R> {
     /// Adds optional `$filter` query parameter to OpenAPI.
@@ -244,6 +406,14 @@ where
     }
 }
 
+fn handler_id(method: &Method, path: &str) -> String {
+    format!(
+        "{}:{}",
+        method.as_str().to_lowercase(),
+        path.replace(['/', '{', '}'], "_")
+    )
+}
+
 // Re-export from openapi_registry for backward compatibility
 pub use crate::api::openapi_registry::{ensure_schema, OpenApiRegistry};
 
@@ -275,11 +445,7 @@ impl<S> OperationBuilder<Missing, Missing, S, AuthNotSet> {
     /// Create a new operation builder with an HTTP method and path
     pub fn new(method: Method, path: impl Into<String>) -> Self {
         let path_str = path.into();
-        let handler_id = format!(
-            "{}:{}",
-            method.as_str().to_lowercase(),
-            path_str.replace(['/', '{', '}'], "_")
-        );
+        let handler_id = handler_id(&method, &path_str);
 
         Self {
             spec: OperationSpec {
@@ -296,6 +462,14 @@ impl<S> OperationBuilder<Missing, Missing, S, AuthNotSet> {
                 sec_requirement: None,
                 is_public: false,
                 rate_limit: None,
+                rate_limit_partition: None,
+                idempotent: false,
+                version: None,
+                deprecation: None,
+                websocket: None,
+                sse_event_name: None,
+                batch_atomic: false,
+                timeout: None,
                 allowed_request_content_types: None,
             },
             method_router: (), // no router in Missing state
@@ -367,6 +541,87 @@ where
         self
     }
 
+    /// Key this route's rate-limit buckets by `partition` instead of the ingress default.
+    pub fn rate_limit_by(mut self, partition: RateLimitPartition) -> Self {
+        self.spec.rate_limit_partition = Some(partition);
+        self
+    }
+
+    /// Honour an `Idempotency-Key` request header on this (unsafe) operation.
+    /// The ingress stores the first response per caller and key and replays it on retries;
+    /// a retry still in progress gets 409 and a key reused with another payload gets 422.
+    pub fn idempotent(mut self) -> Self {
+        self.spec.idempotent = true;
+        self.spec.params.push(ParamSpec {
+            name: "Idempotency-Key".to_string(),
+            location: ParamLocation::Header,
+            required: false,
+            description: Some(
+                "Client-chosen unique key; retries with the same key replay the first response"
+                    .to_string(),
+            ),
+            param_type: "string".to_string(),
+        });
+        self
+    }
+
+    /// Publish the operation as version `name` under the `/{name}` path prefix,
+    /// e.g. `get("/users").version("v2")` serves `/v2/users`.
+    pub fn version(self, name: impl Into<String>) -> Self {
+        self.with_version(name.into(), VersionRouting::PathPrefix)
+    }
+
+    /// Publish the operation as version `name`, selected by a `version` media-type parameter
+    /// on the plain path (`Accept: application/json; version=2`) or by the `/{name}` prefix.
+    pub fn version_by_media_type(self, name: impl Into<String>) -> Self {
+        self.with_version(name.into(), VersionRouting::MediaType)
+    }
+
+    fn with_version(mut self, name: String, routing: VersionRouting) -> Self {
+        let name = name.trim_matches('/').to_string();
+        let path = format!("/{}{}", name, self.spec.unversioned_path());
+        self.spec.handler_id = handler_id(&self.spec.method, &path);
+        self.spec.path = path;
+        self.spec.version = Some(ApiVersionSpec { name, routing });
+        self
+    }
+
+    /// Mark the operation deprecated, optionally with the date it will be removed.
+    /// OpenAPI flags it and the ingress adds `Deprecation` (and `Sunset`) response headers.
+    pub fn deprecated(mut self, sunset: Option<chrono::DateTime<chrono::Utc>>) -> Self {
+        self.spec
+            .deprecation
+            .get_or_insert_with(Default::default)
+            .sunset = sunset;
+        self
+    }
+
+    /// Point clients of this deprecated operation at its replacement (`Link` response header).
+    /// Implies [`deprecated`](Self::deprecated).
+    pub fn successor_version(mut self, href: impl Into<String>) -> Self {
+        self.spec
+            .deprecation
+            .get_or_insert_with(Default::default)
+            .successor = Some(href.into());
+        self
+    }
+
+    /// Name of the SSE event this operation streams (the `event:` line), as sent by
+    /// [`SseBroadcaster::sse_response_named`](crate::SseBroadcaster::sse_response_named).
+    /// Documented in the AsyncAPI document; unnamed streams use `message`.
+    pub fn sse_event_name(mut self, name: impl Into<String>) -> Self {
+        self.spec.sse_event_name = Some(name.into());
+        self
+    }
+
+    /// Allow this operation in atomic `$batch` groups. Its handler must run its writes on
+    /// the transaction of the `modkit_db::batch::BatchTransaction` request extension when
+    /// one is present, so the ingress can roll the whole group back.
+    pub fn atomic_in_batch(mut self) -> Self {
+        self.spec.batch_atomic = true;
+        self
+    }
+
     /// Set the operation summary
     pub fn summary(mut self, text: impl Into<String>) -> Self {
         self.spec.summary = Some(text.into());
@@ -626,6 +881,25 @@ where
         self.spec.allowed_request_content_types = Some(types.to_vec());
         self
     }
+
+    /// Override the ingress request timeout for this operation.
+    ///
+    /// When the handler does not produce a response in time, ingress answers with a
+    /// problem+json 504. Without this, `defaults.request_timeout_ms` of the ingress applies.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// OperationBuilder::post("/upload")
+    ///     .operation_id("upload_file")
+    ///     .timeout(Duration::from_secs(120))
+    ///     .handler(upload_handler)
+    ///     .json_response(200, "Upload successful")
+    ///     .register(router, &api);
+    /// ```
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.spec.timeout = Some(timeout);
+        self
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -775,6 +1049,7 @@ where
             content_type: "application/json",
             description: description.into(),
             schema_name: None,
+            headers: Vec::new(),
         });
         OperationBuilder {
             spec: self.spec,
@@ -802,6 +1077,7 @@ where
             content_type: "application/json",
             description: description.into(),
             schema_name: Some(name),
+            headers: Vec::new(),
         });
         OperationBuilder {
             spec: self.spec,
@@ -836,6 +1112,7 @@ where
             content_type,
             description: description.into(),
             schema_name: None,
+            headers: Vec::new(),
         });
         OperationBuilder {
             spec: self.spec,
@@ -858,6 +1135,7 @@ where
             content_type: "text/html",
             description: description.into(),
             schema_name: None,
+            headers: Vec::new(),
         });
         OperationBuilder {
             spec: self.spec,
@@ -883,6 +1161,42 @@ where
             content_type: problem::APPLICATION_PROBLEM_JSON,
             description: description.into(),
             schema_name: Some(problem_name),
+            headers: Vec::new(),
+        });
+        OperationBuilder {
+            spec: self.spec,
+            method_router: self.method_router,
+            _has_handler: self._has_handler,
+            _has_response: PhantomData::<Present>,
+            _state: self._state,
+            _auth_state: self._auth_state,
+        }
+    }
+
+    /// First response: upgrade to a WebSocket exchanging JSON `In` (client to server) and
+    /// `Out` (server to client) messages. Use on a GET operation whose handler answers with
+    /// [`JsonWebSocket::on_upgrade`](crate::http::ws::JsonWebSocket::on_upgrade).
+    pub fn websocket<In, Out>(
+        mut self,
+        openapi: &dyn OpenApiRegistry,
+        description: impl Into<String>,
+    ) -> OperationBuilder<H, Present, S, A>
+    where
+        In: utoipa::ToSchema + utoipa::PartialSchema + 'static,
+        Out: utoipa::ToSchema + utoipa::PartialSchema + 'static,
+    {
+        let incoming_schema = ensure_schema::<In>(openapi);
+        let outgoing_schema = ensure_schema::<Out>(openapi);
+        self.spec.responses.push(ResponseSpec {
+            status: http::StatusCode::SWITCHING_PROTOCOLS.as_u16(),
+            content_type: WEBSOCKET_CONTENT_TYPE,
+            description: description.into(),
+            schema_name: None,
+            headers: Vec::new(),
+        });
+        self.spec.websocket = Some(WebSocketSpec {
+            incoming_schema,
+            outgoing_schema,
         });
         OperationBuilder {
             spec: self.spec,
@@ -909,6 +1223,7 @@ where
             content_type: "text/event-stream",
             description: description.into(),
             schema_name: Some(name),
+            headers: Vec::new(),
         });
         OperationBuilder {
             spec: self.spec,
@@ -940,6 +1255,7 @@ where
             content_type: "application/json",
             description: description.into(),
             schema_name: None,
+            headers: Vec::new(),
         });
         self
     }
@@ -960,6 +1276,7 @@ where
             content_type: "application/json",
             description: description.into(),
             schema_name: Some(name),
+            headers: Vec::new(),
         });
         self
     }
@@ -987,6 +1304,7 @@ where
             content_type,
             description: description.into(),
             schema_name: None,
+            headers: Vec::new(),
         });
         self
     }
@@ -1002,6 +1320,7 @@ where
             content_type: "text/html",
             description: description.into(),
             schema_name: None,
+            headers: Vec::new(),
         });
         self
     }
@@ -1019,10 +1338,69 @@ where
             content_type: problem::APPLICATION_PROBLEM_JSON,
             description: description.into(),
             schema_name: Some(problem_name),
+            headers: Vec::new(),
         });
         self
     }
 
+    /// Document a conditional GET: optional `If-None-Match`, `ETag` on the success responses
+    /// declared so far and `304 Not Modified`.
+    /// Pair with [`conditional_json`](crate::api::conditional::conditional_json).
+    pub fn conditional_get(mut self) -> Self {
+        self.spec.params.push(ParamSpec {
+            name: "If-None-Match".to_string(),
+            location: ParamLocation::Header,
+            required: false,
+            description: Some("Answer 304 when the current ETag is listed".to_string()),
+            param_type: "string".to_string(),
+        });
+        self.document_etag_on_success();
+        self.spec.responses.push(ResponseSpec {
+            status: http::StatusCode::NOT_MODIFIED.as_u16(),
+            content_type: "application/json",
+            description: "Not Modified".to_string(),
+            schema_name: None,
+            headers: vec![ETAG_HEADER],
+        });
+        self
+    }
+
+    /// Document optimistic concurrency on a mutation: `If-Match`, `ETag` on the success
+    /// responses declared so far, 412 and, when `required`, 428.
+    /// Pair with [`Preconditions::check_if_match`](crate::api::conditional::Preconditions::check_if_match)
+    /// or `require_if_match`.
+    pub fn conditional_update(mut self, registry: &dyn OpenApiRegistry, required: bool) -> Self {
+        self.spec.params.push(ParamSpec {
+            name: "If-Match".to_string(),
+            location: ParamLocation::Header,
+            required,
+            description: Some("Apply only if the resource still has this ETag".to_string()),
+            param_type: "string".to_string(),
+        });
+        self.document_etag_on_success();
+        self = self.problem_response(
+            registry,
+            http::StatusCode::PRECONDITION_FAILED,
+            "Precondition Failed",
+        );
+        if required {
+            self = self.problem_response(
+                registry,
+                http::StatusCode::PRECONDITION_REQUIRED,
+                "Precondition Required",
+            );
+        }
+        self
+    }
+
+    fn document_etag_on_success(&mut self) {
+        for r in &mut self.spec.responses {
+            if (200..300).contains(&r.status) && !r.headers.iter().any(|h| h.name == "ETag") {
+                r.headers.push(ETAG_HEADER);
+            }
+        }
+    }
+
     /// Additional SSE response (if the operation already has a response).
     pub fn sse_json<T>(
         mut self,
@@ -1038,6 +1416,7 @@ where
             content_type: "text/event-stream",
             description: description.into(),
             schema_name: Some(name),
+            headers: Vec::new(),
         });
         self
     }
@@ -1064,8 +1443,9 @@ where
     /// - 404 Not Found
     /// - 409 Conflict
     /// - 422 Unprocessable Entity
-    /// - 429 Too Many Requests
+    /// - 429 Too Many Requests (with `Retry-After` and `RateLimit-*` headers)
     /// - 500 Internal Server Error
+    /// - 503 Service Unavailable (with `Retry-After`)
     pub fn standard_errors(mut self, registry: &dyn OpenApiRegistry) -> Self {
         use http::StatusCode;
         let problem_name = ensure_schema::<crate::api::problem::Problem>(registry);
@@ -1079,14 +1459,22 @@ where
             (StatusCode::UNPROCESSABLE_ENTITY, "Unprocessable Entity"),
             (StatusCode::TOO_MANY_REQUESTS, "Too Many Requests"),
             (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error"),
+            (StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable"),
         ];
 
         for (status, description) in standard_errors {
+            // Throttling responses produced by the ingress rate limiter carry backoff hints
+            let headers = match status {
+                StatusCode::TOO_MANY_REQUESTS => RATE_LIMIT_HEADERS.to_vec(),
+                StatusCode::SERVICE_UNAVAILABLE => vec![RETRY_AFTER_HEADER],
+                _ => Vec::new(),
+            };
             self.spec.responses.push(ResponseSpec {
                 status: status.as_u16(),
                 content_type: problem::APPLICATION_PROBLEM_JSON,
                 description: description.to_string(),
                 schema_name: Some(problem_name.clone()),
+                headers,
             });
         }
 
@@ -1117,6 +1505,7 @@ where
             content_type: problem::APPLICATION_PROBLEM_JSON,
             description: "Validation Error".to_string(),
             schema_name: Some(validation_error_name),
+            headers: Vec::new(),
         });
 
         self
@@ -1407,6 +1796,75 @@ mod tests {
         assert_eq!(builder.spec.path, "/simple");
     }
 
+    #[test]
+    fn test_conditional_get_and_update_document_etags() {
+        let registry = MockRegistry::new();
+        let get = OperationBuilder::<Missing, Missing, ()>::get("/users/{id}")
+            .public()
+            .handler(test_handler)
+            .json_response(http::StatusCode::OK, "User")
+            .conditional_get();
+        let if_none_match = get
+            .spec
+            .params
+            .iter()
+            .find(|p| p.name == "If-None-Match")
+            .unwrap();
+        assert_eq!(if_none_match.location, ParamLocation::Header);
+        assert_eq!(get.spec.responses[0].headers[0].name, "ETag");
+        assert!(get.spec.responses.iter().any(|r| r.status == 304));
+
+        let put = OperationBuilder::<Missing, Missing, ()>::put("/users/{id}")
+            .public()
+            .handler(test_handler)
+            .json_response(http::StatusCode::OK, "Updated")
+            .conditional_update(&registry, true);
+        assert!(put
+            .spec
+            .params
+            .iter()
+            .any(|p| p.name == "If-Match" && p.required));
+        let statuses: Vec<u16> = put.spec.responses.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [200, 412, 428]);
+    }
+
+    #[test]
+    fn test_idempotent_marks_spec_and_documents_header() {
+        let builder = OperationBuilder::<Missing, Missing, ()>::post("/users").idempotent();
+        assert!(builder.spec.idempotent);
+        assert!(builder
+            .spec
+            .params
+            .iter()
+            .any(|p| p.name == "Idempotency-Key" && p.location == ParamLocation::Header));
+        assert!(
+            !OperationBuilder::<Missing, Missing, ()>::post("/users")
+                .spec
+                .idempotent
+        );
+    }
+
+    #[test]
+    fn test_version_prefixes_path_and_deprecation() {
+        let builder = OperationBuilder::<Missing, Missing, ()>::get("/users/{id}")
+            .version_by_media_type("v2")
+            .deprecated(Some(chrono::DateTime::UNIX_EPOCH))
+            .successor_version("/v3/users/{id}");
+        assert_eq!(builder.spec.path, "/v2/users/{id}");
+        assert_eq!(builder.spec.unversioned_path(), "/users/{id}");
+        assert_eq!(builder.spec.handler_id, "get:_v2_users__id_");
+        let version = builder.spec.version.as_ref().unwrap();
+        assert_eq!(version.routing, VersionRouting::MediaType);
+        assert!(version.matches_param("2") && version.matches_param("v2"));
+        assert!(!version.matches_param("3"));
+        let deprecation = builder.spec.deprecation.as_ref().unwrap();
+        assert_eq!(deprecation.successor.as_deref(), Some("/v3/users/{id}"));
+
+        // Re-declaring the version replaces the prefix rather than stacking it
+        let builder = builder.version("v3");
+        assert_eq!(builder.spec.path, "/v3/users/{id}");
+    }
+
     #[test]
     fn test_standard_errors() {
         let registry = MockRegistry::new();
@@ -1416,8 +1874,8 @@ mod tests {
             .json_response(http::StatusCode::OK, "Success")
             .standard_errors(&registry);
 
-        // Should have 1 success response + 8 standard error responses
-        assert_eq!(builder.spec.responses.len(), 9);
+        // Should have 1 success response + 9 standard error responses
+        assert_eq!(builder.spec.responses.len(), 10);
 
         // Check that all standard error status codes are present
         let statuses: Vec<u16> = builder.spec.responses.iter().map(|r| r.status).collect();
@@ -1430,6 +1888,25 @@ mod tests {
         assert!(statuses.contains(&422));
         assert!(statuses.contains(&429));
         assert!(statuses.contains(&500));
+        assert!(statuses.contains(&503));
+
+        // Throttling responses document the backoff headers
+        let too_many = builder
+            .spec
+            .responses
+            .iter()
+            .find(|r| r.status == 429)
+            .unwrap();
+        let names: Vec<_> = too_many.headers.iter().map(|h| h.name).collect();
+        assert_eq!(
+            names,
+            [
+                "Retry-After",
+                "RateLimit-Limit",
+                "RateLimit-Remaining",
+                "RateLimit-Reset"
+            ]
+        );
 
         // All error responses should use Problem content type
         let error_responses: Vec<_> = builder
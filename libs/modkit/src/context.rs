@@ -49,6 +49,12 @@ impl ModuleContextBuilder {
         } else {
             None
         };
+        if let (Some(db), Ok(metrics)) = (
+            &db_handle,
+            self.client_hub.get::<crate::metrics::MetricsRegistry>(),
+        ) {
+            metrics.track_db_pool(module_name, db.clone());
+        }
 
         Ok(ModuleCtx::new(
             Arc::<str>::from(module_name),
@@ -100,6 +106,20 @@ impl ModuleCtx {
         &self.cancellation_token
     }
 
+    /// The runtime-wide typed event bus, registered in the `ClientHub` by the host runtime.
+    pub fn event_bus(
+        &self,
+    ) -> Result<Arc<crate::event_bus::EventBus>, crate::client_hub::ClientHubError> {
+        self.client_hub.get::<crate::event_bus::EventBus>()
+    }
+
+    /// The runtime-wide metrics registry, registered in the `ClientHub` by the host runtime.
+    pub fn metrics(
+        &self,
+    ) -> Result<Arc<crate::metrics::MetricsRegistry>, crate::client_hub::ClientHubError> {
+        self.client_hub.get::<crate::metrics::MetricsRegistry>()
+    }
+
     pub fn db_optional(&self) -> Option<Arc<modkit_db::DbHandle>> {
         self.db_handle.clone()
     }
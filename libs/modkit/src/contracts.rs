@@ -72,7 +72,19 @@ pub trait RestHostModule: Send + Sync + 'static {
 #[async_trait]
 pub trait StatefulModule: Send + Sync {
     async fn start(&self, cancel: CancellationToken) -> anyhow::Result<()>;
+    /// Stop the module. `cancel` is fired by the runtime once the stop deadline has passed,
+    /// asking the module to give up on graceful shutdown.
     async fn stop(&self, cancel: CancellationToken) -> anyhow::Result<()>;
+
+    /// Deadline the module expects for a graceful stop; `None` uses the runtime default.
+    fn stop_timeout(&self) -> Option<std::time::Duration> {
+        None
+    }
+
+    /// Current lifecycle status, for modules that track one (e.g. `WithLifecycle`).
+    fn status(&self) -> Option<crate::lifecycle::Status> {
+        None
+    }
 }
 
 /// Represents a gRPC service registration callback used by the gRPC hub.
@@ -4,7 +4,10 @@ use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
 
-use crate::runtime::{Endpoint, ModuleManager};
+use crate::runtime::{
+    ConsistentHash, Endpoint, LbStrategy, LeastOutstanding, LoadBalancer, ModuleInstance,
+    ModuleManager, ModuleName, RequestGuard, RoundRobin, VersionPinned, Weighted,
+};
 
 /// Information about a service instance
 #[derive(Debug, Clone)]
@@ -15,23 +18,109 @@ pub struct ServiceInstanceInfo {
     pub version: Option<String>,
 }
 
+/// Endpoint picked for one call. While it is alive, the call counts as in flight
+/// on the chosen instance (see [`LbStrategy::LeastOutstanding`]).
+#[derive(Debug)]
+pub struct ResolvedEndpoint {
+    pub endpoint: Endpoint,
+    _guard: Option<RequestGuard>,
+}
+
+impl ResolvedEndpoint {
+    /// An endpoint whose calls are not counted (e.g. resolved by a remote directory).
+    pub fn untracked(endpoint: Endpoint) -> Self {
+        Self {
+            endpoint,
+            _guard: None,
+        }
+    }
+}
+
 /// Directory API trait for service discovery and instance management
 #[async_trait]
 pub trait DirectoryApi: Send + Sync {
     /// Resolve a gRPC service by its logical name to an endpoint
     async fn resolve_grpc_service(&self, service_name: &str) -> Result<Endpoint>;
 
+    /// Resolve a gRPC service using a specific load-balancing strategy.
+    ///
+    /// The default implementation ignores the strategy and falls back to
+    /// [`DirectoryApi::resolve_grpc_service`].
+    async fn resolve_grpc_service_with(
+        &self,
+        service_name: &str,
+        strategy: &LbStrategy,
+    ) -> Result<Endpoint> {
+        let _ = strategy;
+        self.resolve_grpc_service(service_name).await
+    }
+
+    /// Resolve a gRPC service for a single call and keep the call counted as in flight
+    /// until the returned [`ResolvedEndpoint`] is dropped.
+    ///
+    /// The default implementation resolves with [`DirectoryApi::resolve_grpc_service_with`]
+    /// and does not track the call.
+    async fn resolve_grpc_service_tracked(
+        &self,
+        service_name: &str,
+        strategy: &LbStrategy,
+    ) -> Result<ResolvedEndpoint> {
+        let endpoint = self
+            .resolve_grpc_service_with(service_name, strategy)
+            .await?;
+        Ok(ResolvedEndpoint::untracked(endpoint))
+    }
+
     /// List all service instances for a given module
     async fn list_instances(&self, module: &str) -> Result<Vec<ServiceInstanceInfo>>;
 }
 
 pub struct LocalDirectoryApi {
     mgr: Arc<ModuleManager>,
+    // Stateful strategies keep their rotation counters across calls
+    round_robin: Arc<RoundRobin>,
+    least_outstanding: LeastOutstanding,
+    weighted: Weighted,
 }
 
 impl LocalDirectoryApi {
     pub fn new(mgr: Arc<ModuleManager>) -> Self {
-        Self { mgr }
+        Self {
+            mgr,
+            round_robin: Arc::new(RoundRobin::new()),
+            least_outstanding: LeastOutstanding::new(),
+            weighted: Weighted::new(),
+        }
+    }
+
+    fn pick(
+        &self,
+        service_name: &str,
+        strategy: &LbStrategy,
+    ) -> Result<(ModuleName, Arc<ModuleInstance>, Endpoint)> {
+        let picked = match strategy {
+            LbStrategy::RoundRobin => self.mgr.pick_service(service_name, &*self.round_robin),
+            LbStrategy::LeastOutstanding => {
+                self.mgr.pick_service(service_name, &self.least_outstanding)
+            }
+            LbStrategy::Weighted => self.mgr.pick_service(service_name, &self.weighted),
+            LbStrategy::VersionPinned(version) => {
+                let inner: Arc<dyn LoadBalancer> = self.round_robin.clone();
+                let lb = VersionPinned::new(version.clone(), inner);
+                self.mgr.pick_service(service_name, &lb)
+            }
+            LbStrategy::ConsistentHash(key) => self
+                .mgr
+                .pick_service(service_name, &ConsistentHash::new(key.clone())),
+        };
+
+        picked.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Service not found or no matching healthy instances for {:?}: {}",
+                strategy,
+                service_name
+            )
+        })
     }
 }
 
@@ -48,6 +137,27 @@ impl DirectoryApi for LocalDirectoryApi {
         )
     }
 
+    async fn resolve_grpc_service_with(
+        &self,
+        service_name: &str,
+        strategy: &LbStrategy,
+    ) -> Result<Endpoint> {
+        let (_module, _inst, ep) = self.pick(service_name, strategy)?;
+        Ok(ep)
+    }
+
+    async fn resolve_grpc_service_tracked(
+        &self,
+        service_name: &str,
+        strategy: &LbStrategy,
+    ) -> Result<ResolvedEndpoint> {
+        let (_module, inst, endpoint) = self.pick(service_name, strategy)?;
+        Ok(ResolvedEndpoint {
+            endpoint,
+            _guard: Some(inst.track_request()),
+        })
+    }
+
     async fn list_instances(&self, module: &str) -> Result<Vec<ServiceInstanceInfo>> {
         let mut result = Vec::new();
 
@@ -124,6 +234,84 @@ mod tests {
         assert_eq!(instances[0].module, "test_module".to_string());
     }
 
+    #[tokio::test]
+    async fn test_resolve_with_strategy() {
+        let dir = Arc::new(ModuleManager::new());
+        let api = LocalDirectoryApi::new(dir.clone());
+
+        for (id, port, version) in [("instance1", 8001, "1.0"), ("instance2", 8002, "2.0")] {
+            dir.register_instance(Arc::new(
+                ModuleInstance::new("test_module", id)
+                    .with_version(version)
+                    .with_grpc_service("test.Service", Endpoint::tcp("127.0.0.1", port)),
+            ));
+            dir.update_heartbeat("test_module", id, Instant::now());
+        }
+
+        let pinned = LbStrategy::VersionPinned("2.0".to_string());
+        for _ in 0..3 {
+            let ep = api
+                .resolve_grpc_service_with("test.Service", &pinned)
+                .await
+                .unwrap();
+            assert_eq!(ep, Endpoint::tcp("127.0.0.1", 8002));
+        }
+
+        let by_tenant = LbStrategy::ConsistentHash("tenant-1".to_string());
+        let first = api
+            .resolve_grpc_service_with("test.Service", &by_tenant)
+            .await
+            .unwrap();
+        let second = api
+            .resolve_grpc_service_with("test.Service", &by_tenant)
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+
+        // Quarantined instances are skipped by every strategy
+        dir.mark_quarantined("test_module", "instance2");
+        let result = api.resolve_grpc_service_with("test.Service", &pinned).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tracked_resolution_feeds_least_outstanding() {
+        let dir = Arc::new(ModuleManager::new());
+        let api = LocalDirectoryApi::new(dir.clone());
+
+        for (id, port) in [("instance1", 8001), ("instance2", 8002)] {
+            dir.register_instance(Arc::new(
+                ModuleInstance::new("test_module", id)
+                    .with_grpc_service("test.Service", Endpoint::tcp("127.0.0.1", port)),
+            ));
+            dir.update_heartbeat("test_module", id, Instant::now());
+        }
+
+        let strategy = LbStrategy::LeastOutstanding;
+        let busy = api
+            .resolve_grpc_service_tracked("test.Service", &strategy)
+            .await
+            .unwrap();
+
+        // While the first call is in flight, every pick goes to the idle instance
+        for _ in 0..3 {
+            let next = api
+                .resolve_grpc_service_tracked("test.Service", &strategy)
+                .await
+                .unwrap();
+            assert_ne!(next.endpoint, busy.endpoint);
+        }
+
+        let busy_instance = dir
+            .instances_of("test_module")
+            .into_iter()
+            .find(|inst| inst.grpc_services.get("test.Service") == Some(&busy.endpoint))
+            .unwrap();
+        assert_eq!(busy_instance.outstanding(), 1);
+        drop(busy);
+        assert_eq!(busy_instance.outstanding(), 0);
+    }
+
     #[tokio::test]
     async fn test_resolve_filters_unhealthy() {
         let dir = Arc::new(ModuleManager::new());
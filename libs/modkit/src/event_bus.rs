@@ -0,0 +1,477 @@
+//! In-process, typed publish/subscribe bus shared by all modules.
+//!
+//! Design goals:
+//! - Topics are keyed by the *event type* (`TypeId`), so a subscriber only needs the event
+//!   type from a contract crate, not a dependency on the publishing module.
+//! - Every subscriber owns a bounded queue; a slow subscriber drops its own deliveries
+//!   (counted as lag) without slowing down the publisher or other subscribers.
+//! - Each delivery carries the publisher's `SecurityCtx` so subscribers can apply tenant scoping.
+//! - The bus is bound to the runtime cancellation token: after cancellation publishing is
+//!   rejected and subscriptions drain what is already queued, then end.
+//!
+//! The runtime registers one `EventBus` in the `ClientHub`; modules reach it through
+//! [`ModuleCtx::event_bus`](crate::context::ModuleCtx::event_bus).
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use modkit_security::SecurityCtx;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Default per-subscriber queue capacity.
+pub const DEFAULT_SUBSCRIBER_CAPACITY: usize = 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EventBusError {
+    #[error("topic for event type '{type_name}' is not registered")]
+    TopicNotRegistered { type_name: &'static str },
+
+    #[error("topic for event type '{type_name}' is already registered as '{existing}'")]
+    TopicConflict {
+        type_name: &'static str,
+        existing: String,
+    },
+
+    #[error("event bus is shut down")]
+    Closed,
+}
+
+/// A single delivery: the event plus the context it was published under.
+pub struct Envelope<E> {
+    pub id: Uuid,
+    pub topic: Arc<str>,
+    pub published_at: DateTime<Utc>,
+    pub security: SecurityCtx,
+    pub event: Arc<E>,
+}
+
+impl<E> Clone for Envelope<E> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            topic: self.topic.clone(),
+            published_at: self.published_at,
+            security: self.security.clone(),
+            event: self.event.clone(),
+        }
+    }
+}
+
+impl<E> Envelope<E> {
+    /// Tenants of the originating security context.
+    #[inline]
+    pub fn tenant_ids(&self) -> &[Uuid] {
+        self.security.scope().tenant_ids()
+    }
+
+    /// Whether a subscriber acting under `ctx` may observe this delivery.
+    ///
+    /// Root contexts see everything; otherwise at least one tenant must overlap.
+    /// Events published under a root context are visible only to root subscribers.
+    pub fn visible_to(&self, ctx: &SecurityCtx) -> bool {
+        if ctx.scope().is_root() {
+            return true;
+        }
+        let origin = self.tenant_ids();
+        ctx.scope().tenant_ids().iter().any(|t| origin.contains(t))
+    }
+}
+
+/// Counters for a single subscriber.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SubscriberStats {
+    pub name: String,
+    pub capacity: usize,
+    pub queued: usize,
+    pub delivered: u64,
+    pub dropped: u64,
+}
+
+/// Counters for a single topic.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TopicStats {
+    pub topic: String,
+    pub event_type: &'static str,
+    pub published: u64,
+    pub subscribers: Vec<SubscriberStats>,
+}
+
+struct SubscriberSlot<E> {
+    name: String,
+    capacity: usize,
+    tx: mpsc::Sender<Envelope<E>>,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    lagging: AtomicBool,
+}
+
+struct Topic<E> {
+    name: RwLock<Option<Arc<str>>>,
+    published: AtomicU64,
+    subscribers: Mutex<Vec<Arc<SubscriberSlot<E>>>>,
+}
+
+impl<E> Topic<E> {
+    fn new() -> Self {
+        Self {
+            name: RwLock::new(None),
+            published: AtomicU64::new(0),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+/// Type-erased view used for stats collection.
+trait AnyTopic: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn stats(&self) -> TopicStats;
+}
+
+struct TypedTopic<E: Send + Sync + 'static> {
+    type_name: &'static str,
+    inner: Arc<Topic<E>>,
+}
+
+impl<E: Send + Sync + 'static> AnyTopic for TypedTopic<E> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn stats(&self) -> TopicStats {
+        let subscribers = self
+            .inner
+            .subscribers
+            .lock()
+            .iter()
+            .map(|s| SubscriberStats {
+                name: s.name.clone(),
+                capacity: s.capacity,
+                queued: s.capacity.saturating_sub(s.tx.capacity()),
+                delivered: s.delivered.load(Ordering::Relaxed),
+                dropped: s.dropped.load(Ordering::Relaxed),
+            })
+            .collect();
+        TopicStats {
+            topic: self
+                .inner
+                .name
+                .read()
+                .as_deref()
+                .unwrap_or("<unregistered>")
+                .to_string(),
+            event_type: self.type_name,
+            published: self.inner.published.load(Ordering::Relaxed),
+            subscribers,
+        }
+    }
+}
+
+/// Typed in-process event bus.
+pub struct EventBus {
+    topics: RwLock<HashMap<TypeId, Arc<dyn AnyTopic>>>,
+    cancel: CancellationToken,
+}
+
+impl EventBus {
+    /// Create a bus bound to the given cancellation token (normally the runtime root token).
+    pub fn new(cancel: CancellationToken) -> Self {
+        Self {
+            topics: RwLock::new(HashMap::new()),
+            cancel,
+        }
+    }
+
+    fn topic<E: Send + Sync + 'static>(&self) -> Arc<Topic<E>> {
+        let key = TypeId::of::<E>();
+        if let Some(t) = self.topics.read().get(&key) {
+            if let Some(typed) = t.as_any().downcast_ref::<TypedTopic<E>>() {
+                return typed.inner.clone();
+            }
+        }
+        let mut w = self.topics.write();
+        let entry = w.entry(key).or_insert_with(|| {
+            Arc::new(TypedTopic::<E> {
+                type_name: std::any::type_name::<E>(),
+                inner: Arc::new(Topic::new()),
+            })
+        });
+        entry
+            .as_any()
+            .downcast_ref::<TypedTopic<E>>()
+            .map(|t| t.inner.clone())
+            .expect("topic map is keyed by TypeId of E")
+    }
+
+    /// Register the topic for event type `E` under a human-readable name.
+    ///
+    /// Publishing requires a registered topic. Registering the same name twice is a no-op;
+    /// registering a different name for the same type is an error.
+    pub fn register_topic<E: Send + Sync + 'static>(
+        &self,
+        name: impl Into<Arc<str>>,
+    ) -> Result<(), EventBusError> {
+        let name = name.into();
+        let topic = self.topic::<E>();
+        let mut guard = topic.name.write();
+        match guard.as_ref() {
+            Some(existing) if *existing != name => Err(EventBusError::TopicConflict {
+                type_name: std::any::type_name::<E>(),
+                existing: existing.to_string(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                tracing::debug!(
+                    topic = %name,
+                    event_type = std::any::type_name::<E>(),
+                    "Registered event topic"
+                );
+                *guard = Some(name);
+                Ok(())
+            }
+        }
+    }
+
+    /// Subscribe to events of type `E` with the default queue capacity.
+    ///
+    /// Subscribing does not require the topic to be registered yet, so subscribers do not
+    /// depend on the init order of the publishing module.
+    pub fn subscribe<E: Send + Sync + 'static>(
+        &self,
+        subscriber: impl Into<String>,
+    ) -> Subscription<E> {
+        self.subscribe_with_capacity(subscriber, DEFAULT_SUBSCRIBER_CAPACITY)
+    }
+
+    /// Subscribe to events of type `E` with an explicit queue capacity.
+    pub fn subscribe_with_capacity<E: Send + Sync + 'static>(
+        &self,
+        subscriber: impl Into<String>,
+        capacity: usize,
+    ) -> Subscription<E> {
+        let capacity = capacity.max(1);
+        let (tx, rx) = mpsc::channel(capacity);
+        let slot = Arc::new(SubscriberSlot {
+            name: subscriber.into(),
+            capacity,
+            tx,
+            delivered: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            lagging: AtomicBool::new(false),
+        });
+        self.topic::<E>().subscribers.lock().push(slot.clone());
+        Subscription {
+            rx,
+            slot,
+            cancel: self.cancel.clone(),
+        }
+    }
+
+    /// Publish an event under the caller's security context.
+    ///
+    /// Returns the number of subscribers the event was queued for. Subscribers whose queue is
+    /// full miss this delivery; the miss is recorded in their `dropped` counter.
+    pub fn publish<E: Send + Sync + 'static>(
+        &self,
+        ctx: &SecurityCtx,
+        event: E,
+    ) -> Result<usize, EventBusError> {
+        if self.cancel.is_cancelled() {
+            return Err(EventBusError::Closed);
+        }
+
+        let topic = self.topic::<E>();
+        let Some(name) = topic.name.read().clone() else {
+            return Err(EventBusError::TopicNotRegistered {
+                type_name: std::any::type_name::<E>(),
+            });
+        };
+
+        let envelope = Envelope {
+            id: Uuid::now_v7(),
+            topic: name,
+            published_at: Utc::now(),
+            security: ctx.clone(),
+            event: Arc::new(event),
+        };
+        topic.published.fetch_add(1, Ordering::Relaxed);
+
+        let mut delivered = 0;
+        let mut subscribers = topic.subscribers.lock();
+        subscribers.retain(|s| !s.tx.is_closed());
+        for s in subscribers.iter() {
+            match s.tx.try_send(envelope.clone()) {
+                Ok(()) => {
+                    s.delivered.fetch_add(1, Ordering::Relaxed);
+                    s.lagging.store(false, Ordering::Relaxed);
+                    delivered += 1;
+                }
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    let dropped = s.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    if !s.lagging.swap(true, Ordering::Relaxed) {
+                        tracing::warn!(
+                            topic = %envelope.topic,
+                            subscriber = %s.name,
+                            dropped,
+                            "Event subscriber is lagging; dropping deliveries"
+                        );
+                    }
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {}
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Snapshot of per-topic and per-subscriber counters.
+    pub fn stats(&self) -> Vec<TopicStats> {
+        let mut stats: Vec<TopicStats> = self.topics.read().values().map(|t| t.stats()).collect();
+        stats.sort_by(|a, b| a.topic.cmp(&b.topic));
+        stats
+    }
+
+    /// Whether the bus has been shut down.
+    pub fn is_closed(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+}
+
+/// Receiving side of a subscription.
+///
+/// Dropping the subscription unsubscribes it on the next publish.
+pub struct Subscription<E> {
+    rx: mpsc::Receiver<Envelope<E>>,
+    slot: Arc<SubscriberSlot<E>>,
+    cancel: CancellationToken,
+}
+
+impl<E: Send + Sync + 'static> Subscription<E> {
+    /// Receive the next delivery.
+    ///
+    /// After the runtime is cancelled, already-queued deliveries are still returned;
+    /// `None` is returned once the queue is drained.
+    pub async fn recv(&mut self) -> Option<Envelope<E>> {
+        if !self.cancel.is_cancelled() {
+            tokio::select! {
+                biased;
+                ev = self.rx.recv() => return ev,
+                _ = self.cancel.cancelled() => {}
+            }
+        }
+        self.rx.close();
+        self.rx.recv().await
+    }
+
+    /// Number of deliveries this subscriber missed because its queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.slot.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Subscriber name as reported in stats.
+    pub fn name(&self) -> &str {
+        &self.slot.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[derive(Debug, PartialEq)]
+    struct Ping(u32);
+
+    #[tokio::test]
+    async fn publish_requires_registered_topic() {
+        let bus = EventBus::new(CancellationToken::new());
+        let err = bus.publish(&SecurityCtx::root_ctx(), Ping(1)).unwrap_err();
+        assert!(matches!(err, EventBusError::TopicNotRegistered { .. }));
+
+        bus.register_topic::<Ping>("test.ping").unwrap();
+        bus.register_topic::<Ping>("test.ping").unwrap();
+        assert!(matches!(
+            bus.register_topic::<Ping>("other"),
+            Err(EventBusError::TopicConflict { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn delivers_to_every_subscriber_with_tenant() {
+        let bus = EventBus::new(CancellationToken::new());
+        let mut a = bus.subscribe::<Ping>("a");
+        bus.register_topic::<Ping>("test.ping").unwrap();
+        let mut b = bus.subscribe::<Ping>("b");
+
+        let tenant = Uuid::new_v4();
+        let ctx = SecurityCtx::for_tenant(tenant, Uuid::new_v4());
+        assert_eq!(bus.publish(&ctx, Ping(7)).unwrap(), 2);
+
+        let ea = a.recv().await.unwrap();
+        let eb = b.recv().await.unwrap();
+        assert_eq!(*ea.event, Ping(7));
+        assert_eq!(ea.id, eb.id);
+        assert_eq!(ea.tenant_ids(), &[tenant]);
+        assert!(ea.visible_to(&SecurityCtx::for_tenant(tenant, Uuid::new_v4())));
+        assert!(!ea.visible_to(&SecurityCtx::for_tenant(Uuid::new_v4(), Uuid::new_v4())));
+        assert!(ea.visible_to(&SecurityCtx::root_ctx()));
+    }
+
+    #[tokio::test]
+    async fn full_queue_counts_lag() {
+        let bus = EventBus::new(CancellationToken::new());
+        bus.register_topic::<Ping>("test.ping").unwrap();
+        let sub = bus.subscribe_with_capacity::<Ping>("slow", 2);
+
+        let ctx = SecurityCtx::root_ctx();
+        for i in 0..5 {
+            bus.publish(&ctx, Ping(i)).unwrap();
+        }
+        assert_eq!(sub.dropped(), 3);
+
+        let stats = bus.stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].published, 5);
+        assert_eq!(stats[0].subscribers[0].queued, 2);
+        assert_eq!(stats[0].subscribers[0].delivered, 2);
+        assert_eq!(stats[0].subscribers[0].dropped, 3);
+    }
+
+    #[tokio::test]
+    async fn cancellation_drains_then_ends() {
+        let cancel = CancellationToken::new();
+        let bus = EventBus::new(cancel.clone());
+        bus.register_topic::<Ping>("test.ping").unwrap();
+        let mut sub = bus.subscribe::<Ping>("drain");
+
+        let ctx = SecurityCtx::root_ctx();
+        bus.publish(&ctx, Ping(1)).unwrap();
+        bus.publish(&ctx, Ping(2)).unwrap();
+        cancel.cancel();
+
+        assert!(matches!(
+            bus.publish(&ctx, Ping(3)),
+            Err(EventBusError::Closed)
+        ));
+        assert_eq!(*sub.recv().await.unwrap().event, Ping(1));
+        assert_eq!(*sub.recv().await.unwrap().event, Ping(2));
+        let end = tokio::time::timeout(Duration::from_millis(100), sub.recv())
+            .await
+            .expect("recv should not hang after cancellation");
+        assert!(end.is_none());
+    }
+
+    #[tokio::test]
+    async fn dropped_subscription_is_pruned() {
+        let bus = EventBus::new(CancellationToken::new());
+        bus.register_topic::<Ping>("test.ping").unwrap();
+        let sub = bus.subscribe::<Ping>("gone");
+        drop(sub);
+        assert_eq!(bus.publish(&SecurityCtx::root_ctx(), Ping(1)).unwrap(), 0);
+        assert!(bus.stats()[0].subscribers.is_empty());
+    }
+}
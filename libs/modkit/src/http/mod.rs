@@ -6,3 +6,4 @@
 pub mod client;
 pub mod otel;
 pub mod sse;
+pub mod ws;
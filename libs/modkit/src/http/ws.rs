@@ -0,0 +1,402 @@
+//! Typed JSON WebSocket channels.
+//!
+//! Handlers take a [`JsonWebSocket<In, Out>`] extractor next to their usual ones
+//! (`Extension<SecurityCtx>`, `State`, ...) and answer with [`JsonWebSocket::on_upgrade`]. The
+//! upgrade request passes the ingress middleware like any other GET, so auth, rate limits and
+//! `SecurityCtx` injection apply before the connection is accepted.
+//!
+//! ```rust,ignore
+//! async fn chat(
+//!     Extension(ctx): Extension<SecurityCtx>,
+//!     ws: JsonWebSocket<ChatIn, ChatOut>,
+//! ) -> Response {
+//!     ws.on_upgrade(move |mut channel| async move {
+//!         while let Some(msg) = channel.recv().await {
+//!             if channel.send(ChatOut::echo(&ctx, msg)).await.is_err() {
+//!                 break;
+//!             }
+//!         }
+//!     })
+//! }
+//! ```
+//!
+//! The connection is pinged every `ping_interval` and closed after `idle_timeout` without any
+//! frame from the client, when a message is not valid JSON for `In`, when the handler falls
+//! behind reading client messages, when the handler returns, and when the server shuts down
+//! (the ingress puts a [`WsShutdown`] token on every request).
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade};
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::response::Response;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Messages buffered per direction. A handler that lets this many client messages pile up
+/// gets its connection closed; its own sends wait for the socket instead.
+const CHANNEL_CAPACITY: usize = 32;
+
+/// Keepalive and size limits of WebSocket connections; the ingress sets them from its config
+/// as a request extension, handlers without one use the defaults.
+#[derive(Clone, Debug)]
+pub struct WsSettings {
+    pub ping_interval: Duration,
+    pub idle_timeout: Duration,
+    pub max_message_bytes: usize,
+}
+
+impl Default for WsSettings {
+    fn default() -> Self {
+        Self {
+            ping_interval: Duration::from_secs(30),
+            idle_timeout: Duration::from_secs(300),
+            max_message_bytes: 64 * 1024,
+        }
+    }
+}
+
+/// Request extension cancelled when the server shuts down; open connections are closed
+/// with `1001 Going Away`.
+#[derive(Clone, Debug, Default)]
+pub struct WsShutdown(pub CancellationToken);
+
+/// Error returned by [`WsChannel::send`] once the connection is closed.
+#[derive(Debug, thiserror::Error)]
+#[error("WebSocket connection closed")]
+pub struct WsClosed;
+
+/// Extractor for a WebSocket upgrade exchanging JSON `In` and `Out` messages.
+pub struct JsonWebSocket<In, Out> {
+    upgrade: WebSocketUpgrade,
+    settings: WsSettings,
+    shutdown: CancellationToken,
+    _messages: PhantomData<fn(In) -> Out>,
+}
+
+impl<S, In, Out> FromRequestParts<S> for JsonWebSocket<In, Out>
+where
+    S: Send + Sync,
+{
+    type Rejection = <WebSocketUpgrade as FromRequestParts<S>>::Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let upgrade = WebSocketUpgrade::from_request_parts(parts, state).await?;
+        Ok(Self {
+            upgrade,
+            settings: parts.extensions.get().cloned().unwrap_or_default(),
+            shutdown: parts
+                .extensions
+                .get::<WsShutdown>()
+                .map(|s| s.0.clone())
+                .unwrap_or_default(),
+            _messages: PhantomData,
+        })
+    }
+}
+
+impl<In, Out> JsonWebSocket<In, Out>
+where
+    In: DeserializeOwned + Send + 'static,
+    Out: Serialize + Send + 'static,
+{
+    /// Override the idle timeout for this connection.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.settings.idle_timeout = timeout;
+        self
+    }
+
+    /// Accept the upgrade and run `handler` with the connection's typed channel.
+    pub fn on_upgrade<F, Fut>(self, handler: F) -> Response
+    where
+        F: FnOnce(WsChannel<In, Out>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let Self {
+            upgrade,
+            settings,
+            shutdown,
+            ..
+        } = self;
+        upgrade
+            .max_message_size(settings.max_message_bytes)
+            .on_upgrade(move |socket| async move {
+                let (in_tx, in_rx) = mpsc::channel(CHANNEL_CAPACITY);
+                let (out_tx, out_rx) = mpsc::channel(CHANNEL_CAPACITY);
+                let channel = WsChannel {
+                    incoming: in_rx,
+                    outgoing: out_tx,
+                };
+                tokio::join!(
+                    handler(channel),
+                    pump(socket, in_tx, out_rx, settings, shutdown)
+                );
+            })
+    }
+}
+
+/// Handler side of a JSON WebSocket connection.
+pub struct WsChannel<In, Out> {
+    incoming: mpsc::Receiver<In>,
+    outgoing: mpsc::Sender<Out>,
+}
+
+impl<In, Out> WsChannel<In, Out> {
+    /// Next message from the client; `None` once the connection is closed.
+    pub async fn recv(&mut self) -> Option<In> {
+        self.incoming.recv().await
+    }
+
+    /// Queue a message for the client.
+    pub async fn send(&self, msg: Out) -> Result<(), WsClosed> {
+        self.outgoing.send(msg).await.map_err(|_| WsClosed)
+    }
+
+    /// A second sender, e.g. for a task pushing server-side events.
+    pub fn sender(&self) -> WsSender<Out> {
+        WsSender(self.outgoing.clone())
+    }
+}
+
+/// Cloneable sending half of a [`WsChannel`].
+pub struct WsSender<Out>(mpsc::Sender<Out>);
+
+impl<Out> Clone for WsSender<Out> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Out> WsSender<Out> {
+    pub async fn send(&self, msg: Out) -> Result<(), WsClosed> {
+        self.0.send(msg).await.map_err(|_| WsClosed)
+    }
+}
+
+/// Moves messages between the socket and the handler's channel until either side is done.
+async fn pump<In, Out>(
+    mut socket: WebSocket,
+    incoming: mpsc::Sender<In>,
+    mut outgoing: mpsc::Receiver<Out>,
+    settings: WsSettings,
+    shutdown: CancellationToken,
+) where
+    In: DeserializeOwned,
+    Out: Serialize,
+{
+    let mut ping = tokio::time::interval_at(
+        Instant::now() + settings.ping_interval,
+        settings.ping_interval,
+    );
+    let idle = tokio::time::sleep(settings.idle_timeout);
+    tokio::pin!(idle);
+
+    let close = loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break Some((close_code::AWAY, "server shutting down")),
+            _ = &mut idle => break Some((close_code::AWAY, "idle timeout")),
+            _ = ping.tick() => {
+                if socket.send(Message::Ping(Default::default())).await.is_err() {
+                    break None;
+                }
+            }
+            msg = outgoing.recv() => {
+                let Some(msg) = msg else {
+                    // Handler returned
+                    break Some((close_code::NORMAL, ""));
+                };
+                let text = match serde_json::to_string(&msg) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to serialize WebSocket message");
+                        break Some((close_code::ERROR, "internal error"));
+                    }
+                };
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break None;
+                }
+            }
+            frame = socket.recv() => {
+                let Some(Ok(frame)) = frame else {
+                    break None;
+                };
+                idle.as_mut().reset(Instant::now() + settings.idle_timeout);
+                let decoded = match &frame {
+                    Message::Text(text) => serde_json::from_str::<In>(text.as_str()),
+                    Message::Binary(bytes) => serde_json::from_slice::<In>(bytes),
+                    // Pings are answered by the socket itself; pongs only count as activity
+                    Message::Ping(_) | Message::Pong(_) => continue,
+                    Message::Close(_) => break None,
+                };
+                match decoded {
+                    // Never wait on the handler here, or pings, shutdown and its own sends stall
+                    Ok(msg) => match incoming.try_send(msg) {
+                        Ok(()) => {}
+                        Err(mpsc::error::TrySendError::Full(_)) => {
+                            tracing::debug!("WebSocket handler is not keeping up with messages");
+                            break Some((close_code::POLICY, "too many pending messages"));
+                        }
+                        // The handler stopped reading; keep serving its sends
+                        Err(mpsc::error::TrySendError::Closed(_)) => {}
+                    },
+                    Err(e) => {
+                        tracing::debug!(error = %e, "Invalid WebSocket message");
+                        break Some((close_code::INVALID, "invalid message"));
+                    }
+                }
+            }
+        }
+    };
+
+    if let Some((code, reason)) = close {
+        let _ = socket
+            .send(Message::Close(Some(CloseFrame {
+                code,
+                reason: reason.into(),
+            })))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use futures::{SinkExt, StreamExt};
+    use serde::Deserialize;
+    use tokio::time::timeout;
+    use tokio_tungstenite::tungstenite::{self, protocol::frame::coding::CloseCode};
+
+    #[derive(Deserialize)]
+    struct Ping {
+        n: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Pong {
+        n: u32,
+    }
+
+    type Client = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    async fn echo(ws: JsonWebSocket<Ping, Pong>) -> Response {
+        ws.on_upgrade(|mut channel| async move {
+            while let Some(Ping { n }) = channel.recv().await {
+                if channel.send(Pong { n: n + 1 }).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Holds the channel without ever reading from it.
+    async fn stalled(ws: JsonWebSocket<Ping, Pong>) -> Response {
+        ws.on_upgrade(|channel| async move {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            drop(channel);
+        })
+    }
+
+    async fn connect(router: Router) -> Client {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        let (client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws"))
+            .await
+            .unwrap();
+        client
+    }
+
+    /// Skips data frames and returns the close frame the server sent.
+    async fn close_frame(client: &mut Client) -> tungstenite::protocol::CloseFrame {
+        timeout(Duration::from_secs(5), async {
+            loop {
+                match client.next().await {
+                    Some(Ok(tungstenite::Message::Close(frame))) => {
+                        return frame.expect("close frame carries a code")
+                    }
+                    Some(Ok(_)) => continue,
+                    other => panic!("connection ended without a close frame: {other:?}"),
+                }
+            }
+        })
+        .await
+        .expect("server did not close the connection")
+    }
+
+    #[tokio::test]
+    async fn test_messages_round_trip_as_json() {
+        let mut client = connect(Router::new().route("/ws", get(echo))).await;
+
+        client
+            .send(tungstenite::Message::text(r#"{"n":1}"#))
+            .await
+            .unwrap();
+        let reply = timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.into_text().unwrap().as_str(), r#"{"n":2}"#);
+
+        client
+            .send(tungstenite::Message::text("not json"))
+            .await
+            .unwrap();
+        let frame = close_frame(&mut client).await;
+        assert_eq!(frame.code, CloseCode::Invalid);
+    }
+
+    #[tokio::test]
+    async fn test_idle_connections_are_closed() {
+        let settings = WsSettings {
+            idle_timeout: Duration::from_millis(100),
+            ..WsSettings::default()
+        };
+        let router = Router::new()
+            .route("/ws", get(echo))
+            .layer(Extension(settings));
+        let mut client = connect(router).await;
+
+        let frame = close_frame(&mut client).await;
+        assert_eq!(frame.code, CloseCode::Away);
+        assert_eq!(frame.reason.as_str(), "idle timeout");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_closes_open_connections() {
+        let shutdown = CancellationToken::new();
+        let router = Router::new()
+            .route("/ws", get(echo))
+            .layer(Extension(WsShutdown(shutdown.clone())));
+        let mut client = connect(router).await;
+
+        shutdown.cancel();
+        let frame = close_frame(&mut client).await;
+        assert_eq!(frame.code, CloseCode::Away);
+        assert_eq!(frame.reason.as_str(), "server shutting down");
+    }
+
+    #[tokio::test]
+    async fn test_handler_not_reading_does_not_stall_the_connection() {
+        let mut client = connect(Router::new().route("/ws", get(stalled))).await;
+
+        for n in 0..=CHANNEL_CAPACITY as u32 {
+            client
+                .send(tungstenite::Message::text(format!(r#"{{"n":{n}}}"#)))
+                .await
+                .unwrap();
+        }
+        let frame = close_frame(&mut client).await;
+        assert_eq!(frame.code, CloseCode::Policy);
+    }
+}
@@ -0,0 +1,10 @@
+//! Error catalog for HTTP-level failures raised by modkit helpers (conditional requests).
+//! Source of truth: gts/errors_http.json
+
+use modkit_errors_macro::declare_errors;
+
+declare_errors! {
+    path = "gts/errors_http.json",
+    namespace = "http_errors",
+    vis = "pub"
+}
@@ -84,8 +84,15 @@ pub use context::{ModuleContextBuilder, ModuleCtx};
 pub mod client_hub;
 pub mod registry;
 
+// In-process typed event bus
+pub mod event_bus;
+
+// Sinks for the transactional outbox relay
+pub mod outbox;
+
 // Re-export main types
 pub use client_hub::ClientHub;
+pub use event_bus::{Envelope, EventBus, Subscription};
 pub use registry::ModuleRegistry;
 
 // Re-export the macros from the proc-macro crate
@@ -108,15 +115,25 @@ pub use api::problem::{
 };
 pub use http::client::TracedClient;
 pub use http::sse::SseBroadcaster;
+pub use http::ws::{JsonWebSocket, WsChannel};
 
 // Telemetry utilities
 pub mod telemetry;
 
+// Prometheus metrics shared by the runtime and modules
+pub mod metrics;
+pub use metrics::MetricsRegistry;
+
 pub mod lifecycle;
 pub mod runtime;
 
+// In-process harness for integration tests
+#[cfg(feature = "testing")]
+pub mod testing;
+
 // Error catalog runtime support
 pub mod errors;
+pub mod http_errors;
 
 // Ergonomic result types
 pub mod result;
@@ -124,12 +141,14 @@ pub use result::ApiResult;
 
 // Directory API for service discovery
 pub mod directory;
-pub use directory::{DirectoryApi, ServiceInstanceInfo};
+pub use directory::{DirectoryApi, ResolvedEndpoint, ServiceInstanceInfo};
 
 pub use lifecycle::{Lifecycle, Runnable, Status, StopReason, WithLifecycle};
 pub use runtime::{
-    run, BackendKind, DbOptions, Endpoint, InstanceHandle, LocalProcessBackend, ModuleInstance,
-    ModuleManager, ModuleName, ModuleRuntimeBackend, OopModuleConfig, RunOptions, ShutdownOptions,
+    run, BackendKind, DbOptions, Endpoint, InstanceHandle, LbStrategy, LoadBalancer,
+    LocalProcessBackend, MockBackend, MockEvent, ModuleInstance, ModuleManager, ModuleName,
+    ModuleRuntimeBackend, ModuleStopReport, OopModuleConfig, RunOptions, ShutdownOptions,
+    ShutdownReport, StaticBackend, StopPolicy,
 };
 
 #[cfg(test)]
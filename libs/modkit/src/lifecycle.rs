@@ -28,7 +28,8 @@ type ReadyFn<T> = fn(
 // ----- Status model ----------------------------------------------------------
 
 /// Terminal/transition states for a background job.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 #[repr(u8)]
 pub enum Status {
     Stopped,
@@ -582,6 +583,14 @@ impl<T: Runnable> crate::contracts::StatefulModule for WithLifecycle<T> {
             }
         }
     }
+
+    fn stop_timeout(&self) -> Option<Duration> {
+        Some(self.stop_timeout)
+    }
+
+    fn status(&self) -> Option<Status> {
+        Some(self.lc.status())
+    }
 }
 
 impl<T: Runnable> Drop for WithLifecycle<T> {
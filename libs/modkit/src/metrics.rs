@@ -0,0 +1,123 @@
+//! Process-wide Prometheus metrics registry.
+//!
+//! The runtime registers one [`MetricsRegistry`] in the `ClientHub`; modules reach it through
+//! [`ModuleCtx::metrics`](crate::context::ModuleCtx::metrics) and register their own collectors.
+//! `api_ingress` serves the text exposition on `/metrics`.
+//!
+//! ```rust,ignore
+//! use modkit::metrics::prometheus::{IntCounter, Opts};
+//!
+//! let jobs = IntCounter::with_opts(Opts::new("scheduler_jobs_run_total", "Jobs run"))?;
+//! ctx.metrics()?.register(jobs.clone())?;
+//! jobs.inc();
+//! ```
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use prometheus::core::Collector;
+use prometheus::{Encoder, IntGaugeVec, Opts, Registry, TextEncoder};
+
+pub use prometheus;
+
+/// Content type of the Prometheus text exposition format.
+pub const TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub struct MetricsRegistry {
+    registry: Registry,
+    /// Module database pools, sampled on every scrape
+    db_pools: RwLock<Vec<(Arc<str>, Arc<modkit_db::DbHandle>)>>,
+    db_connections: IntGaugeVec,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let db_connections = IntGaugeVec::new(
+            Opts::new(
+                "db_pool_connections",
+                "Database pool connections by module and state (idle, in_use)",
+            ),
+            &["module", "state"],
+        )
+        .expect("valid metric definition");
+        registry
+            .register(Box::new(db_connections.clone()))
+            .expect("fresh registry has no conflicting metrics");
+        Self {
+            registry,
+            db_pools: RwLock::new(Vec::new()),
+            db_connections,
+        }
+    }
+
+    /// Underlying registry, for code that needs the raw `prometheus` API.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Register a collector (a metric or a metric vector). Fails when a metric with the same
+    /// name and labels is already registered.
+    pub fn register<C>(&self, collector: C) -> Result<(), prometheus::Error>
+    where
+        C: Collector + 'static,
+    {
+        self.registry.register(Box::new(collector))
+    }
+
+    /// Report the pool of `module`'s database handle; replaces an earlier handle of the module.
+    pub fn track_db_pool(&self, module: &str, db: Arc<modkit_db::DbHandle>) {
+        let mut pools = self.db_pools.write();
+        match pools.iter_mut().find(|(m, _)| &**m == module) {
+            Some(entry) => entry.1 = db,
+            None => pools.push((Arc::from(module), db)),
+        }
+    }
+
+    /// Current values of all metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        for (module, db) in self.db_pools.read().iter() {
+            let stats = db.pool_stats();
+            self.db_connections
+                .with_label_values(&[&**module, "idle"])
+                .set(i64::from(stats.idle));
+            self.db_connections
+                .with_label_values(&[&**module, "in_use"])
+                .set(i64::from(stats.size.saturating_sub(stats.idle)));
+        }
+
+        let mut buf = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buf) {
+            tracing::warn!(error = %e, "Failed to encode metrics");
+        }
+        String::from_utf8(buf).unwrap_or_default()
+    }
+}
+
+impl Default for MetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::IntCounter;
+
+    #[test]
+    fn test_registered_metrics_are_rendered() {
+        let metrics = MetricsRegistry::new();
+        let counter = IntCounter::new("jobs_total", "Jobs run").unwrap();
+        metrics.register(counter.clone()).unwrap();
+        counter.inc_by(3);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE jobs_total counter"));
+        assert!(text.contains("jobs_total 3"));
+
+        // Same name twice is rejected
+        let dup = IntCounter::new("jobs_total", "Jobs run").unwrap();
+        assert!(metrics.register(dup).is_err());
+    }
+}
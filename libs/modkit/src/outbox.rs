@@ -0,0 +1,169 @@
+//! Ready-made [`OutboxSink`] implementations for the relay in `modkit_db::outbox`.
+//!
+//! - [`EventBusSink`]: republish on the in-process [`EventBus`].
+//! - [`WebhookSink`]: POST each record as JSON to an HTTP endpoint.
+//! - [`SseSink`]: fan out to an [`SseBroadcaster`] feeding an SSE route.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use uuid::Uuid;
+
+pub use modkit_db::outbox::{
+    Outbox, OutboxRecord, OutboxRelay, OutboxSink, PrunePolicy, RelayConfig, RetryPolicy,
+};
+
+use crate::event_bus::EventBus;
+use crate::http::client::TracedClient;
+use crate::http::sse::SseBroadcaster;
+
+/// Bus topic carrying relayed outbox records.
+pub const OUTBOX_BUS_TOPIC: &str = "modkit.outbox";
+
+/// Wire/bus representation of a relayed outbox record.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub namespace: String,
+    pub topic: String,
+    pub payload: serde_json::Value,
+}
+
+impl From<&OutboxRecord> for OutboxEvent {
+    fn from(r: &OutboxRecord) -> Self {
+        Self {
+            id: r.id,
+            namespace: r.namespace.clone(),
+            topic: r.topic.clone(),
+            payload: r.payload.clone(),
+        }
+    }
+}
+
+/// Publishes records as [`OutboxEvent`] on the in-process bus, under the security
+/// context captured at enqueue time.
+pub struct EventBusSink {
+    bus: Arc<EventBus>,
+}
+
+impl EventBusSink {
+    pub fn new(bus: Arc<EventBus>) -> anyhow::Result<Self> {
+        bus.register_topic::<OutboxEvent>(OUTBOX_BUS_TOPIC)?;
+        Ok(Self { bus })
+    }
+}
+
+#[async_trait]
+impl OutboxSink for EventBusSink {
+    async fn deliver(&self, record: &OutboxRecord) -> anyhow::Result<()> {
+        self.bus
+            .publish(&record.security, OutboxEvent::from(record))?;
+        Ok(())
+    }
+}
+
+/// POSTs each record as JSON; any non-2xx response is treated as a failed delivery.
+///
+/// The `x-outbox-id` header carries the record id so receivers can deduplicate.
+pub struct WebhookSink {
+    client: TracedClient,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(client: TracedClient, url: impl Into<String>) -> Self {
+        Self {
+            client,
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl OutboxSink for WebhookSink {
+    async fn deliver(&self, record: &OutboxRecord) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&OutboxEvent::from(record))?;
+        let req = self
+            .client
+            .request(reqwest::Method::POST, &self.url)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header("x-outbox-id", record.id.to_string())
+            .header("x-outbox-topic", record.topic.as_str())
+            .body(body)
+            .build()?;
+        let resp = self.client.execute(req).await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("webhook {} responded with {}", self.url, resp.status());
+        }
+        Ok(())
+    }
+}
+
+/// Sends records to an SSE broadcaster. Delivery is acknowledged even when no client is
+/// connected; SSE is best-effort by nature.
+pub struct SseSink {
+    broadcaster: SseBroadcaster<OutboxEvent>,
+}
+
+impl SseSink {
+    pub fn new(broadcaster: SseBroadcaster<OutboxEvent>) -> Self {
+        Self { broadcaster }
+    }
+}
+
+#[async_trait]
+impl OutboxSink for SseSink {
+    async fn deliver(&self, record: &OutboxRecord) -> anyhow::Result<()> {
+        self.broadcaster.send(OutboxEvent::from(record));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use modkit_security::SecurityCtx;
+    use tokio_util::sync::CancellationToken;
+
+    fn record(tenant: Uuid) -> OutboxRecord {
+        OutboxRecord {
+            id: Uuid::now_v7(),
+            namespace: "users_info".into(),
+            topic: "user.created".into(),
+            payload: serde_json::json!({"id": 1}),
+            security: SecurityCtx::for_tenant(tenant, Uuid::new_v4()),
+            attempts: 0,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn event_bus_sink_preserves_security_context() {
+        let bus = Arc::new(EventBus::new(CancellationToken::new()));
+        let sink = EventBusSink::new(bus.clone()).unwrap();
+        let mut sub = bus.subscribe::<OutboxEvent>("test");
+
+        let tenant = Uuid::new_v4();
+        let rec = record(tenant);
+        sink.deliver(&rec).await.unwrap();
+
+        let env = sub.recv().await.unwrap();
+        assert_eq!(env.event.id, rec.id);
+        assert_eq!(env.event.topic, "user.created");
+        assert_eq!(env.tenant_ids(), &[tenant]);
+    }
+
+    #[tokio::test]
+    async fn sse_sink_broadcasts_records() {
+        use futures::StreamExt;
+
+        let broadcaster = SseBroadcaster::new(8);
+        let mut stream = Box::pin(broadcaster.subscribe_stream());
+        let sink = SseSink::new(broadcaster);
+
+        let rec = record(Uuid::new_v4());
+        sink.deliver(&rec).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().id, rec.id);
+    }
+}
@@ -0,0 +1,306 @@
+//! Read-only view of a built [`ModuleRegistry`] for tooling and diagnostics.
+//!
+//! The graph mirrors exactly what `HostRuntime` iterates over, so the phase order
+//! printed by the CLI is the order the runtime will actually use.
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use super::ModuleRegistry;
+
+/// Static metadata of a single module as declared by `#[module(...)]`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleInfo {
+    pub name: &'static str,
+    pub deps: Vec<&'static str>,
+    pub capabilities: Vec<&'static str>,
+}
+
+/// Modules participating in one lifecycle phase, in execution order.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseOrder {
+    pub phase: &'static str,
+    pub modules: Vec<&'static str>,
+}
+
+/// Module dependency graph together with the resolved per-phase order.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleGraph {
+    /// Modules in topological order (dependencies first).
+    pub modules: Vec<ModuleInfo>,
+    /// Phases in the order the runtime drives them.
+    pub phases: Vec<PhaseOrder>,
+}
+
+impl ModuleGraph {
+    /// Build the graph from an already validated registry.
+    pub fn from_registry(registry: &ModuleRegistry) -> Self {
+        let modules = registry
+            .modules()
+            .iter()
+            .map(|e| ModuleInfo {
+                name: e.name,
+                deps: e.deps.to_vec(),
+                capabilities: e.capabilities(),
+            })
+            .collect();
+
+        let by_priority = registry.modules_by_system_priority();
+
+        // REST host prepares first and finalizes last; providers register in topo order.
+        let mut rest: Vec<&'static str> = registry
+            .modules()
+            .iter()
+            .filter(|e| e.rest_host.is_some())
+            .map(|e| e.name)
+            .collect();
+        rest.extend(
+            registry
+                .modules()
+                .iter()
+                .filter(|e| e.rest.is_some())
+                .map(|e| e.name),
+        );
+
+        let mut grpc: Vec<&'static str> = registry
+            .modules()
+            .iter()
+            .filter(|e| e.is_grpc_hub)
+            .map(|e| e.name)
+            .collect();
+        grpc.extend(registry.modules().iter().filter_map(|e| {
+            registry
+                .grpc_services
+                .iter()
+                .any(|(name, _)| name == e.name)
+                .then_some(e.name)
+        }));
+
+        let phases = vec![
+            PhaseOrder {
+                phase: "system_wire",
+                modules: registry
+                    .modules()
+                    .iter()
+                    .filter(|e| e.is_system)
+                    .map(|e| e.name)
+                    .collect(),
+            },
+            PhaseOrder {
+                phase: "db",
+                modules: by_priority
+                    .iter()
+                    .filter(|e| e.db.is_some())
+                    .map(|e| e.name)
+                    .collect(),
+            },
+            PhaseOrder {
+                phase: "init",
+                modules: by_priority.iter().map(|e| e.name).collect(),
+            },
+            PhaseOrder {
+                phase: "rest",
+                modules: rest,
+            },
+            PhaseOrder {
+                phase: "grpc",
+                modules: grpc,
+            },
+            PhaseOrder {
+                phase: "start",
+                modules: by_priority
+                    .iter()
+                    .filter(|e| e.stateful.is_some())
+                    .map(|e| e.name)
+                    .collect(),
+            },
+            PhaseOrder {
+                phase: "stop",
+                modules: registry
+                    .modules()
+                    .iter()
+                    .rev()
+                    .filter(|e| e.stateful.is_some())
+                    .map(|e| e.name)
+                    .collect(),
+            },
+        ];
+
+        Self { modules, phases }
+    }
+
+    /// Render a plain-text table of modules followed by the phase order.
+    pub fn to_table(&self) -> String {
+        const NAME: &str = "MODULE";
+        const DEPS: &str = "DEPENDS ON";
+        const CAPS: &str = "CAPABILITIES";
+
+        let rows: Vec<(&str, String, String)> = self
+            .modules
+            .iter()
+            .map(|m| (m.name, join_or_dash(&m.deps), join_or_dash(&m.capabilities)))
+            .collect();
+
+        let name_w = rows
+            .iter()
+            .map(|r| r.0.len())
+            .chain([NAME.len()])
+            .max()
+            .unwrap_or(0);
+        let deps_w = rows
+            .iter()
+            .map(|r| r.1.len())
+            .chain([DEPS.len()])
+            .max()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{NAME:<name_w$}  {DEPS:<deps_w$}  {CAPS}");
+        for (name, deps, caps) in &rows {
+            let _ = writeln!(out, "{name:<name_w$}  {deps:<deps_w$}  {caps}");
+        }
+
+        let phase_w = self.phases.iter().map(|p| p.phase.len()).max().unwrap_or(0);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "PHASE ORDER");
+        for p in &self.phases {
+            let _ = writeln!(
+                out,
+                "{:<phase_w$}  {}",
+                p.phase,
+                if p.modules.is_empty() {
+                    "-".to_string()
+                } else {
+                    p.modules.join(" -> ")
+                }
+            );
+        }
+        out
+    }
+
+    /// Render the dependency graph in Graphviz DOT format.
+    ///
+    /// Edges point from a module to the module it depends on. System modules are filled.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph modules {{");
+        let _ = writeln!(out, "    rankdir=LR;");
+        let _ = writeln!(out, "    node [shape=box, fontname=\"monospace\"];");
+        for m in &self.modules {
+            let style = if m.capabilities.contains(&"system") {
+                ", style=filled, fillcolor=\"lightgrey\""
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "    \"{}\" [label=\"{}\\n[{}]\"{}];",
+                m.name,
+                m.name,
+                m.capabilities.join(", "),
+                style
+            );
+        }
+        for m in &self.modules {
+            for dep in &m.deps {
+                let _ = writeln!(out, "    \"{}\" -> \"{}\";", m.name, dep);
+            }
+        }
+        for p in &self.phases {
+            let _ = writeln!(out, "    // {}: {}", p.phase, p.modules.join(" -> "));
+        }
+        let _ = writeln!(out, "}}");
+        out
+    }
+}
+
+fn join_or_dash(items: &[&str]) -> String {
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::ModuleCtx;
+    use crate::contracts;
+    use crate::registry::RegistryBuilder;
+    use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
+
+    #[derive(Default)]
+    struct DummyCore;
+    #[async_trait::async_trait]
+    impl contracts::Module for DummyCore {
+        async fn init(&self, _ctx: &ModuleCtx) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl contracts::StatefulModule for DummyCore {
+        async fn start(&self, _cancel: CancellationToken) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn stop(&self, _cancel: CancellationToken) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn sample_graph() -> ModuleGraph {
+        let mut b = RegistryBuilder::default();
+        b.register_core_with_meta("app", &["sys"], Arc::new(DummyCore));
+        b.register_core_with_meta("sys", &[], Arc::new(DummyCore));
+        b.register_system_with_meta("sys");
+        b.register_stateful_with_meta("sys", Arc::new(DummyCore));
+        b.register_stateful_with_meta("app", Arc::new(DummyCore));
+        let reg = b.build_topo_sorted().unwrap();
+        ModuleGraph::from_registry(&reg)
+    }
+
+    #[test]
+    fn graph_reports_capabilities_and_phase_order() {
+        let g = sample_graph();
+
+        let sys = g.modules.iter().find(|m| m.name == "sys").unwrap();
+        assert_eq!(sys.capabilities, vec!["stateful", "system"]);
+        let app = g.modules.iter().find(|m| m.name == "app").unwrap();
+        assert_eq!(app.deps, vec!["sys"]);
+
+        let phase = |name: &str| {
+            g.phases
+                .iter()
+                .find(|p| p.phase == name)
+                .unwrap()
+                .modules
+                .clone()
+        };
+        assert_eq!(phase("system_wire"), vec!["sys"]);
+        assert_eq!(phase("start"), vec!["sys", "app"]);
+        assert_eq!(phase("stop"), vec!["app", "sys"]);
+        assert!(phase("db").is_empty());
+    }
+
+    #[test]
+    fn dot_output_contains_edges() {
+        let dot = sample_graph().to_dot();
+        assert!(dot.starts_with("digraph modules {"));
+        assert!(dot.contains("\"app\" -> \"sys\";"));
+        assert!(dot.contains("fillcolor"));
+    }
+
+    #[test]
+    fn table_output_lists_modules_and_phases() {
+        let table = sample_graph().to_table();
+        assert!(table.contains("MODULE"));
+        assert!(table.contains("PHASE ORDER"));
+        assert!(table.contains("sys -> app"));
+    }
+}
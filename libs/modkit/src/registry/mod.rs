@@ -7,6 +7,9 @@ use thiserror::Error;
 // Re-exported contracts are referenced but not defined here.
 use crate::contracts;
 
+mod graph;
+pub use graph::{ModuleGraph, ModuleInfo, PhaseOrder};
+
 /// Type alias for REST host module configuration.
 type RestHostEntry = (&'static str, Arc<dyn contracts::RestHostModule>);
 
@@ -23,6 +26,35 @@ pub struct ModuleEntry {
     pub grpc_service: Option<Arc<dyn contracts::GrpcServiceModule>>,
 }
 
+impl ModuleEntry {
+    /// Capability names declared for this module, in the same spelling as `#[module(capabilities = [...])]`.
+    pub fn capabilities(&self) -> Vec<&'static str> {
+        let mut caps = Vec::new();
+        if self.db.is_some() {
+            caps.push("db");
+        }
+        if self.rest.is_some() {
+            caps.push("rest");
+        }
+        if self.rest_host.is_some() {
+            caps.push("rest_host");
+        }
+        if self.stateful.is_some() {
+            caps.push("stateful");
+        }
+        if self.is_system {
+            caps.push("system");
+        }
+        if self.grpc_service.is_some() {
+            caps.push("grpc");
+        }
+        if self.is_grpc_hub {
+            caps.push("grpc_hub");
+        }
+        caps
+    }
+}
+
 impl std::fmt::Debug for ModuleEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ModuleEntry")
@@ -96,6 +128,17 @@ impl ModuleRegistry {
         b.build_topo_sorted()
     }
 
+    /// Like [`ModuleRegistry::discover_and_build`], but keep only `names` and their
+    /// transitive dependencies.
+    pub fn discover_and_build_only(names: &[&str]) -> Result<Self, RegistryError> {
+        let mut b = RegistryBuilder::default();
+        for r in ::inventory::iter::<Registrator> {
+            r.0(&mut b);
+        }
+        b.retain_with_deps(names)?;
+        b.build_topo_sorted()
+    }
+
     /// (Optional) quick lookup if you need it.
     pub fn get_module(&self, name: &str) -> Option<Arc<dyn contracts::Module>> {
         self.modules
@@ -195,6 +238,53 @@ impl RegistryBuilder {
         self.grpc_services.insert(name, m);
     }
 
+    /// Drop every module that is neither in `names` nor a transitive dependency of one.
+    pub fn retain_with_deps(&mut self, names: &[&str]) -> Result<(), RegistryError> {
+        let mut keep = std::collections::HashSet::new();
+        let mut stack = Vec::new();
+        for &n in names {
+            let (&name, _) = self
+                .core
+                .get_key_value(n)
+                .ok_or_else(|| RegistryError::UnknownModule(n.to_string()))?;
+            stack.push(name);
+        }
+        while let Some(name) = stack.pop() {
+            if !keep.insert(name) {
+                continue;
+            }
+            for &d in self.deps.get(name).copied().unwrap_or_default() {
+                let (&dep, _) =
+                    self.core
+                        .get_key_value(d)
+                        .ok_or_else(|| RegistryError::UnknownDependency {
+                            module: name.to_string(),
+                            depends_on: d.to_string(),
+                        })?;
+                stack.push(dep);
+            }
+        }
+
+        self.core.retain(|n, _| keep.contains(n));
+        self.deps.retain(|n, _| keep.contains(n));
+        self.rest.retain(|n, _| keep.contains(n));
+        self.db.retain(|n, _| keep.contains(n));
+        self.stateful.retain(|n, _| keep.contains(n));
+        self.system_modules.retain(|n| keep.contains(n));
+        self.grpc_services.retain(|n, _| keep.contains(n));
+        if self
+            .rest_host
+            .as_ref()
+            .is_some_and(|(n, _)| !keep.contains(n))
+        {
+            self.rest_host = None;
+        }
+        if self.grpc_hub.is_some_and(|n| !keep.contains(n)) {
+            self.grpc_hub = None;
+        }
+        Ok(())
+    }
+
     /// Detect cycles in the dependency graph using DFS with path tracking.
     /// Returns the cycle path if found, None otherwise.
     fn detect_cycle_with_path(
@@ -533,6 +623,27 @@ mod tests {
         assert_eq!(order, vec!["core_a", "core_b"]);
     }
 
+    #[test]
+    fn retain_with_deps_keeps_transitive_dependencies() {
+        let mut b = RegistryBuilder::default();
+        b.register_core_with_meta("core_a", &[], Arc::new(DummyCore));
+        b.register_core_with_meta("core_b", &["core_a"], Arc::new(DummyCore));
+        b.register_core_with_meta("core_c", &["core_b"], Arc::new(DummyCore));
+        b.register_core_with_meta("other", &[], Arc::new(DummyCore));
+
+        b.retain_with_deps(&["core_c"]).unwrap();
+        let reg = b.build_topo_sorted().unwrap();
+        let order: Vec<_> = reg.modules().iter().map(|m| m.name).collect();
+        assert_eq!(order, vec!["core_a", "core_b", "core_c"]);
+
+        let mut b = RegistryBuilder::default();
+        b.register_core_with_meta("core_a", &[], Arc::new(DummyCore));
+        assert!(matches!(
+            b.retain_with_deps(&["missing"]),
+            Err(RegistryError::UnknownModule(_))
+        ));
+    }
+
     #[test]
     fn unknown_dependency_error() {
         let mut b = RegistryBuilder::default();
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Instant;
 
+use crate::runtime::Endpoint;
+
 /// The kind of backend used to spawn and manage module instances
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BackendKind {
@@ -21,6 +23,12 @@ pub struct OopModuleConfig {
     pub env: HashMap<String, String>,
     pub backend: BackendKind,
     pub version: Option<String>,
+    /// Control endpoint of an existing instance (`Static`) or a faked one (`Mock`).
+    pub control: Option<Endpoint>,
+    /// gRPC services served by the instance, keyed by service name.
+    pub grpc_services: HashMap<String, Endpoint>,
+    /// Relative share of traffic for weighted load balancing (default 1, 0 takes none).
+    pub weight: u32,
 }
 
 impl OopModuleConfig {
@@ -32,6 +40,9 @@ impl OopModuleConfig {
             env: HashMap::new(),
             backend,
             version: None,
+            control: None,
+            grpc_services: HashMap::new(),
+            weight: 1,
         }
     }
 }
@@ -0,0 +1,366 @@
+//! Mock backend implementation
+//!
+//! Fakes module instances in the [`ModuleManager`] and lets tests drive their lifecycle:
+//! slow starts, spawn failures, crashes and heartbeat loss. No processes are spawned.
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::super::backend::{BackendKind, OopModuleConfig};
+use super::ModuleRuntimeBackend;
+use crate::runtime::{Endpoint, InstanceHandle, ModuleInstance, ModuleManager, ModuleName};
+
+/// Lifecycle event recorded by [`MockBackend`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockEvent {
+    Spawned {
+        module: ModuleName,
+        instance_id: String,
+    },
+    SpawnFailed {
+        module: ModuleName,
+    },
+    Ready {
+        module: ModuleName,
+        instance_id: String,
+    },
+    Crashed {
+        module: ModuleName,
+        instance_id: String,
+    },
+    HeartbeatLost {
+        module: ModuleName,
+        instance_id: String,
+    },
+    Stopped {
+        module: ModuleName,
+        instance_id: String,
+    },
+}
+
+/// Per-module behavior applied to subsequent spawns.
+#[derive(Clone, Debug, Default)]
+struct MockBehavior {
+    start_delay: Duration,
+    fail_spawns: usize,
+}
+
+struct MockInstance {
+    handle: InstanceHandle,
+    heartbeat_lost: bool,
+    starting: Option<JoinHandle<()>>,
+}
+
+/// Backend that fakes instances for testing supervision, directory and load-balancing logic.
+///
+/// Spawned instances are registered in the [`ModuleManager`] immediately and become ready
+/// after the configured start delay. Heartbeats are sent explicitly with [`MockBackend::heartbeat`]
+/// so tests fully control liveness.
+pub struct MockBackend {
+    manager: Arc<ModuleManager>,
+    behavior: RwLock<HashMap<ModuleName, MockBehavior>>,
+    instances: Arc<RwLock<HashMap<String, MockInstance>>>,
+    events: Arc<Mutex<Vec<MockEvent>>>,
+}
+
+impl MockBackend {
+    pub fn new(manager: Arc<ModuleManager>) -> Self {
+        Self {
+            manager,
+            behavior: RwLock::new(HashMap::new()),
+            instances: Arc::new(RwLock::new(HashMap::new())),
+            events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Delay before new instances of `module` become ready.
+    pub fn set_start_delay(&self, module: ModuleName, delay: Duration) {
+        self.behavior.write().entry(module).or_default().start_delay = delay;
+    }
+
+    /// Make the next `count` spawns of `module` fail.
+    pub fn fail_next_spawns(&self, module: ModuleName, count: usize) {
+        self.behavior.write().entry(module).or_default().fail_spawns = count;
+    }
+
+    /// Simulate an unexpected exit: the instance disappears from the backend and the directory.
+    pub fn crash(&self, handle: &InstanceHandle) -> Result<()> {
+        let inst = self
+            .instances
+            .write()
+            .remove(&handle.instance_id)
+            .ok_or_else(|| anyhow!("unknown mock instance '{}'", handle.instance_id))?;
+        if let Some(task) = inst.starting {
+            task.abort();
+        }
+        self.manager.deregister(handle.module, &handle.instance_id);
+        self.record(MockEvent::Crashed {
+            module: handle.module,
+            instance_id: handle.instance_id.clone(),
+        });
+        Ok(())
+    }
+
+    /// Stop delivering heartbeats for the instance; it goes stale under the manager's policy.
+    pub fn lose_heartbeat(&self, handle: &InstanceHandle) -> Result<()> {
+        let mut instances = self.instances.write();
+        let inst = instances
+            .get_mut(&handle.instance_id)
+            .ok_or_else(|| anyhow!("unknown mock instance '{}'", handle.instance_id))?;
+        inst.heartbeat_lost = true;
+        drop(instances);
+        self.record(MockEvent::HeartbeatLost {
+            module: handle.module,
+            instance_id: handle.instance_id.clone(),
+        });
+        Ok(())
+    }
+
+    /// Resume heartbeats after [`MockBackend::lose_heartbeat`].
+    pub fn restore_heartbeat(&self, handle: &InstanceHandle) {
+        if let Some(inst) = self.instances.write().get_mut(&handle.instance_id) {
+            inst.heartbeat_lost = false;
+        }
+    }
+
+    /// Send a heartbeat at `at` for the instance, unless its heartbeat is lost.
+    pub fn heartbeat(&self, handle: &InstanceHandle, at: Instant) {
+        let alive = self
+            .instances
+            .read()
+            .get(&handle.instance_id)
+            .is_some_and(|i| !i.heartbeat_lost);
+        if alive {
+            self.manager
+                .update_heartbeat(handle.module, &handle.instance_id, at);
+        }
+    }
+
+    /// Send a heartbeat at `at` for every instance whose heartbeat is not lost.
+    pub fn heartbeat_all(&self, at: Instant) {
+        let handles: Vec<_> = self
+            .instances
+            .read()
+            .values()
+            .filter(|i| !i.heartbeat_lost)
+            .map(|i| i.handle.clone())
+            .collect();
+        for h in handles {
+            self.manager.update_heartbeat(h.module, &h.instance_id, at);
+        }
+    }
+
+    /// Events recorded so far, in order.
+    pub fn events(&self) -> Vec<MockEvent> {
+        self.events.lock().clone()
+    }
+
+    fn record(&self, event: MockEvent) {
+        self.events.lock().push(event);
+    }
+}
+
+#[async_trait]
+impl ModuleRuntimeBackend for MockBackend {
+    async fn spawn_instance(&self, cfg: &OopModuleConfig) -> Result<InstanceHandle> {
+        if cfg.backend != BackendKind::Mock {
+            bail!(
+                "MockBackend can only spawn Mock instances, got {:?}",
+                cfg.backend
+            );
+        }
+
+        let start_delay = {
+            let mut behavior = self.behavior.write();
+            let b = behavior.entry(cfg.name).or_default();
+            if b.fail_spawns > 0 {
+                b.fail_spawns -= 1;
+                drop(behavior);
+                self.record(MockEvent::SpawnFailed { module: cfg.name });
+                bail!("mock spawn failure for module '{}'", cfg.name);
+            }
+            b.start_delay
+        };
+
+        let instance_id = Uuid::now_v7().to_string();
+        let control = cfg
+            .control
+            .clone()
+            .unwrap_or_else(|| Endpoint::from_uri(format!("mock://{}/{}", cfg.name, instance_id)));
+        let mut instance = ModuleInstance::new(cfg.name, instance_id.clone())
+            .with_control(control)
+            .with_weight(cfg.weight);
+        if let Some(version) = &cfg.version {
+            instance = instance.with_version(version.clone());
+        }
+        for (service, ep) in &cfg.grpc_services {
+            instance = instance.with_grpc_service(service.clone(), ep.clone());
+        }
+        self.manager.register_instance(Arc::new(instance));
+
+        let handle = InstanceHandle {
+            module: cfg.name,
+            instance_id: instance_id.clone(),
+            backend: BackendKind::Mock,
+            pid: None,
+            created_at: Instant::now(),
+        };
+        self.record(MockEvent::Spawned {
+            module: cfg.name,
+            instance_id: instance_id.clone(),
+        });
+
+        self.instances.write().insert(
+            instance_id.clone(),
+            MockInstance {
+                handle: handle.clone(),
+                heartbeat_lost: false,
+                starting: None,
+            },
+        );
+
+        if start_delay.is_zero() {
+            self.manager.mark_ready(cfg.name, &instance_id);
+            self.record(MockEvent::Ready {
+                module: cfg.name,
+                instance_id,
+            });
+        } else {
+            let manager = self.manager.clone();
+            let instances = self.instances.clone();
+            let events = self.events.clone();
+            let (module, id) = (cfg.name, instance_id.clone());
+            let task = tokio::spawn(async move {
+                tokio::time::sleep(start_delay).await;
+                match instances.write().get_mut(&id) {
+                    Some(inst) => inst.starting = None,
+                    None => return,
+                }
+                manager.mark_ready(module, &id);
+                events.lock().push(MockEvent::Ready {
+                    module,
+                    instance_id: id,
+                });
+            });
+            if let Some(inst) = self.instances.write().get_mut(&instance_id) {
+                inst.starting = Some(task);
+            }
+        }
+
+        Ok(handle)
+    }
+
+    async fn stop_instance(&self, handle: &InstanceHandle) -> Result<()> {
+        let removed = self.instances.write().remove(&handle.instance_id);
+        if let Some(inst) = removed {
+            if let Some(task) = inst.starting {
+                task.abort();
+            }
+            self.manager.deregister(handle.module, &handle.instance_id);
+            self.record(MockEvent::Stopped {
+                module: handle.module,
+                instance_id: handle.instance_id.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn list_instances(&self, module: ModuleName) -> Result<Vec<InstanceHandle>> {
+        Ok(self
+            .instances
+            .read()
+            .values()
+            .filter(|i| i.handle.module == module)
+            .map(|i| i.handle.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::InstanceState;
+
+    fn mock_cfg(name: ModuleName) -> OopModuleConfig {
+        OopModuleConfig::new(name, BackendKind::Mock)
+    }
+
+    #[tokio::test]
+    async fn test_spawn_crash_and_stop() {
+        let manager = Arc::new(ModuleManager::new());
+        let backend = MockBackend::new(manager.clone());
+
+        let a = backend.spawn_instance(&mock_cfg("svc")).await.unwrap();
+        let b = backend.spawn_instance(&mock_cfg("svc")).await.unwrap();
+        assert_eq!(manager.instances_of("svc").len(), 2);
+        assert_eq!(manager.instances_of("svc")[0].state(), InstanceState::Ready);
+
+        backend.crash(&a).unwrap();
+        assert_eq!(backend.list_instances("svc").await.unwrap().len(), 1);
+        assert_eq!(manager.instances_of("svc").len(), 1);
+
+        backend.stop_instance(&b).await.unwrap();
+        assert!(manager.instances_of("svc").is_empty());
+        assert!(matches!(
+            backend.events().last(),
+            Some(MockEvent::Stopped { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_slow_start_and_spawn_failures() {
+        let manager = Arc::new(ModuleManager::new());
+        let backend = MockBackend::new(manager.clone());
+        backend.fail_next_spawns("svc", 1);
+        backend.set_start_delay("svc", Duration::from_millis(50));
+
+        assert!(backend.spawn_instance(&mock_cfg("svc")).await.is_err());
+        let h = backend.spawn_instance(&mock_cfg("svc")).await.unwrap();
+        assert_eq!(
+            manager.instances_of("svc")[0].state(),
+            InstanceState::Registered
+        );
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(manager.instances_of("svc")[0].state(), InstanceState::Ready);
+        assert!(backend.events().contains(&MockEvent::Ready {
+            module: "svc",
+            instance_id: h.instance_id.clone(),
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_loss_quarantines_instance() {
+        let manager = Arc::new(
+            ModuleManager::new()
+                .with_heartbeat_policy(Duration::from_secs(10), Duration::from_secs(10)),
+        );
+        let backend = MockBackend::new(manager.clone());
+        let live = backend.spawn_instance(&mock_cfg("svc")).await.unwrap();
+        let lost = backend.spawn_instance(&mock_cfg("svc")).await.unwrap();
+
+        let start = Instant::now();
+        backend.heartbeat_all(start);
+        backend.lose_heartbeat(&lost).unwrap();
+
+        let later = start + Duration::from_secs(15);
+        backend.heartbeat_all(later);
+        manager.evict_stale(later);
+
+        let state_of = |h: &InstanceHandle| {
+            manager
+                .instances_of("svc")
+                .into_iter()
+                .find(|i| i.instance_id == h.instance_id)
+                .map(|i| i.state())
+        };
+        assert_eq!(state_of(&live), Some(InstanceState::Ready));
+        assert_eq!(state_of(&lost), Some(InstanceState::Quarantined));
+    }
+}
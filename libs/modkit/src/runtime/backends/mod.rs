@@ -16,6 +16,11 @@ pub trait ModuleRuntimeBackend: Send + Sync {
     async fn list_instances(&self, module: ModuleName) -> Result<Vec<InstanceHandle>>;
 }
 
-// Local backend submodule
+// Backend submodules
 pub mod local;
+pub mod mock;
+pub mod static_backend;
+
 pub use local::LocalProcessBackend;
+pub use mock::{MockBackend, MockEvent};
+pub use static_backend::StaticBackend;
@@ -0,0 +1,190 @@
+//! Static backend implementation
+//!
+//! Registers pre-existing, externally managed instances (e.g. sidecars) in the
+//! [`ModuleManager`] without spawning anything.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::super::backend::{BackendKind, OopModuleConfig};
+use super::ModuleRuntimeBackend;
+use crate::runtime::{InstanceHandle, ModuleInstance, ModuleManager, ModuleName};
+
+/// Backend for instances whose endpoints are known from configuration.
+///
+/// Instances are registered as ready and pinned: they are not expected to heartbeat
+/// and are never evicted as stale. Stopping an instance only removes it from the directory.
+pub struct StaticBackend {
+    manager: Arc<ModuleManager>,
+    instances: RwLock<HashMap<String, InstanceHandle>>,
+}
+
+impl StaticBackend {
+    pub fn new(manager: Arc<ModuleManager>) -> Self {
+        Self {
+            manager,
+            instances: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModuleRuntimeBackend for StaticBackend {
+    async fn spawn_instance(&self, cfg: &OopModuleConfig) -> Result<InstanceHandle> {
+        if cfg.backend != BackendKind::Static {
+            bail!(
+                "StaticBackend can only register Static instances, got {:?}",
+                cfg.backend
+            );
+        }
+        if cfg.control.is_none() && cfg.grpc_services.is_empty() {
+            bail!(
+                "Static instance of '{}' needs a control endpoint or at least one gRPC service",
+                cfg.name
+            );
+        }
+
+        let instance_id = Uuid::now_v7().to_string();
+
+        let mut instance = ModuleInstance::new(cfg.name, instance_id.clone())
+            .pinned()
+            .with_weight(cfg.weight);
+        if let Some(control) = &cfg.control {
+            instance = instance.with_control(control.clone());
+        }
+        if let Some(version) = &cfg.version {
+            instance = instance.with_version(version.clone());
+        }
+        for (service, ep) in &cfg.grpc_services {
+            instance = instance.with_grpc_service(service.clone(), ep.clone());
+        }
+
+        self.manager.register_instance(Arc::new(instance));
+        self.manager.mark_ready(cfg.name, &instance_id);
+
+        let handle = InstanceHandle {
+            module: cfg.name,
+            instance_id: instance_id.clone(),
+            backend: BackendKind::Static,
+            pid: None,
+            created_at: std::time::Instant::now(),
+        };
+        self.instances.write().insert(instance_id, handle.clone());
+
+        tracing::info!(
+            module = %cfg.name,
+            instance_id = %handle.instance_id,
+            "Registered static module instance"
+        );
+        Ok(handle)
+    }
+
+    async fn stop_instance(&self, handle: &InstanceHandle) -> Result<()> {
+        if self.instances.write().remove(&handle.instance_id).is_some() {
+            self.manager.deregister(handle.module, &handle.instance_id);
+        } else {
+            tracing::debug!(
+                module = %handle.module,
+                instance_id = %handle.instance_id,
+                "stop_instance called for unknown instance, ignoring"
+            );
+        }
+        Ok(())
+    }
+
+    async fn list_instances(&self, module: ModuleName) -> Result<Vec<InstanceHandle>> {
+        Ok(self
+            .instances
+            .read()
+            .values()
+            .filter(|h| h.module == module)
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{Endpoint, InstanceState};
+    use std::time::{Duration, Instant};
+
+    fn static_cfg(name: ModuleName) -> OopModuleConfig {
+        let mut cfg = OopModuleConfig::new(name, BackendKind::Static);
+        cfg.control = Some(Endpoint::tcp("127.0.0.1", 9000));
+        cfg.grpc_services
+            .insert("svc.Echo".to_string(), Endpoint::tcp("127.0.0.1", 9001));
+        cfg
+    }
+
+    #[tokio::test]
+    async fn test_register_static_instance() {
+        let manager = Arc::new(ModuleManager::new());
+        let backend = StaticBackend::new(manager.clone());
+
+        let handle = backend
+            .spawn_instance(&static_cfg("sidecar"))
+            .await
+            .expect("should register");
+        assert_eq!(handle.backend, BackendKind::Static);
+        assert_eq!(handle.pid, None);
+
+        let instances = manager.instances_of("sidecar");
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].state(), InstanceState::Ready);
+        assert!(manager.pick_service_round_robin("svc.Echo").is_some());
+
+        // Never heartbeats, but stays in the directory
+        manager.evict_stale(Instant::now() + Duration::from_secs(3600));
+        assert_eq!(manager.instances_of("sidecar").len(), 1);
+
+        backend.stop_instance(&handle).await.unwrap();
+        assert!(manager.instances_of("sidecar").is_empty());
+        assert!(backend.list_instances("sidecar").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_static_instance_requires_endpoints() {
+        let backend = StaticBackend::new(Arc::new(ModuleManager::new()));
+        let cfg = OopModuleConfig::new("sidecar", BackendKind::Static);
+        let err = backend.spawn_instance(&cfg).await.unwrap_err();
+        assert!(err.to_string().contains("needs a control endpoint"));
+
+        let cfg = OopModuleConfig::new("sidecar", BackendKind::LocalProcess);
+        let err = backend.spawn_instance(&cfg).await.unwrap_err();
+        assert!(err.to_string().contains("can only register Static"));
+    }
+
+    #[tokio::test]
+    async fn test_configured_weight_drives_weighted_resolution() {
+        use crate::directory::{DirectoryApi, LocalDirectoryApi};
+        use crate::runtime::LbStrategy;
+
+        let manager = Arc::new(ModuleManager::new());
+        let backend = StaticBackend::new(manager.clone());
+        for (port, weight) in [(9001, 3), (9002, 1), (9003, 0)] {
+            let mut cfg = OopModuleConfig::new("sidecar", BackendKind::Static);
+            cfg.grpc_services
+                .insert("svc.Echo".to_string(), Endpoint::tcp("127.0.0.1", port));
+            cfg.weight = weight;
+            backend.spawn_instance(&cfg).await.unwrap();
+        }
+
+        let api = LocalDirectoryApi::new(manager);
+        let mut hits = HashMap::new();
+        for _ in 0..8 {
+            let ep = api
+                .resolve_grpc_service_with("svc.Echo", &LbStrategy::Weighted)
+                .await
+                .unwrap();
+            *hits.entry(ep).or_insert(0) += 1;
+        }
+        assert_eq!(hits.get(&Endpoint::tcp("127.0.0.1", 9001)), Some(&6));
+        assert_eq!(hits.get(&Endpoint::tcp("127.0.0.1", 9002)), Some(&2));
+        assert_eq!(hits.get(&Endpoint::tcp("127.0.0.1", 9003)), None);
+    }
+}
@@ -8,12 +8,15 @@ use crate::contracts::RegisterGrpcServiceFn;
 /// runtime-scoped type that gets injected into the grpc_hub module.
 pub struct GrpcInstallerStore {
     inner: Mutex<Vec<RegisterGrpcServiceFn>>,
+    // Names outlive `take()`, for introspection once the hub installed the services
+    service_names: Mutex<Vec<&'static str>>,
 }
 
 impl GrpcInstallerStore {
     pub fn new() -> Self {
         Self {
             inner: Mutex::new(Vec::new()),
+            service_names: Mutex::new(Vec::new()),
         }
     }
 
@@ -23,10 +26,16 @@ impl GrpcInstallerStore {
         if !guard.is_empty() {
             anyhow::bail!("gRPC installers already initialized");
         }
+        *self.service_names.lock() = installers.iter().map(|i| i.service_name).collect();
         *guard = installers;
         Ok(())
     }
 
+    /// Names of the registered services, also after the hub consumed them.
+    pub fn service_names(&self) -> Vec<&'static str> {
+        self.service_names.lock().clone()
+    }
+
     /// Consume and return all installers.
     pub fn take(&self) -> Vec<RegisterGrpcServiceFn> {
         let mut guard = self.inner.lock();
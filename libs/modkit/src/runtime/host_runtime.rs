@@ -6,14 +6,19 @@
 use axum::Router;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 
 use crate::client_hub::ClientHub;
 use crate::config::ConfigProvider;
 use crate::context::ModuleContextBuilder;
 use crate::contracts::RegisterGrpcServiceFn;
+use crate::event_bus::EventBus;
+use crate::metrics::MetricsRegistry;
 use crate::registry::{ModuleRegistry, RegistryError};
-use crate::runtime::{GrpcInstallerStore, ModuleManager, SystemContext};
+use crate::runtime::introspection::PhaseTimer;
+use crate::runtime::stop::{stop_module, ShutdownReport, StopPolicy, STOP_TIMEOUT_KEY};
+use crate::runtime::{GrpcInstallerStore, ModuleManager, RuntimeIntrospection, SystemContext};
 
 /// How the runtime should provide DBs to modules.
 #[derive(Clone)]
@@ -31,8 +36,11 @@ pub enum DbOptions {
 pub struct HostRuntime {
     registry: ModuleRegistry,
     ctx_builder: ModuleContextBuilder,
+    modules_cfg: Arc<dyn ConfigProvider>,
+    stop_policy: StopPolicy,
     module_manager: Arc<ModuleManager>,
     grpc_installers: Arc<GrpcInstallerStore>,
+    introspection: Arc<RuntimeIntrospection>,
     #[allow(dead_code)]
     client_hub: Arc<ClientHub>,
     cancel: CancellationToken,
@@ -55,36 +63,78 @@ impl HostRuntime {
         let module_manager = Arc::new(ModuleManager::new());
         let grpc_installers = Arc::new(GrpcInstallerStore::new());
 
+        // One event bus per runtime; it stops accepting events once the root token is cancelled
+        client_hub.register::<EventBus>(Arc::new(EventBus::new(cancel.clone())));
+        // One metrics registry per runtime, scraped through api_ingress
+        client_hub.register::<MetricsRegistry>(Arc::new(MetricsRegistry::new()));
+
         // Build the context builder that will resolve per-module DbHandles
         let db_manager = match &db_options {
             DbOptions::Manager(mgr) => Some(mgr.clone()),
             DbOptions::None => None,
         };
 
-        let ctx_builder =
-            ModuleContextBuilder::new(modules_cfg, client_hub.clone(), cancel.clone(), db_manager);
+        let introspection = Arc::new(RuntimeIntrospection::from_registry(
+            &registry,
+            modules_cfg.clone(),
+        ));
+
+        let ctx_builder = ModuleContextBuilder::new(
+            modules_cfg.clone(),
+            client_hub.clone(),
+            cancel.clone(),
+            db_manager,
+        );
 
         Self {
             registry,
             ctx_builder,
+            modules_cfg,
+            stop_policy: StopPolicy::default(),
             module_manager,
             grpc_installers,
+            introspection,
             client_hub,
             cancel,
             db_options,
         }
     }
 
+    #[cfg(feature = "testing")]
+    pub(crate) fn registry(&self) -> &ModuleRegistry {
+        &self.registry
+    }
+
+    /// Override the runtime-wide stop deadlines.
+    pub fn with_stop_policy(mut self, policy: StopPolicy) -> Self {
+        self.stop_policy = policy;
+        self
+    }
+
+    /// Stop deadline for a module: `modules.<name>.stop_timeout_ms` from config, then the
+    /// module's own `stop_timeout()` (e.g. `WithLifecycle::with_stop_timeout`), then the default.
+    fn stop_deadline(&self, name: &str, module: &dyn crate::contracts::StatefulModule) -> Duration {
+        self.modules_cfg
+            .get_module_config(name)
+            .and_then(|raw| raw.get(STOP_TIMEOUT_KEY))
+            .and_then(|v| v.as_u64())
+            .map(Duration::from_millis)
+            .or_else(|| module.stop_timeout())
+            .unwrap_or(self.stop_policy.default_timeout)
+    }
+
     /// SYSTEM WIRING phase: wire runtime internals into system modules.
     ///
     /// This phase runs before init and only for modules with the "system" capability.
     pub async fn wire_system(&self) -> Result<(), RegistryError> {
         tracing::info!("Phase: system_wire");
+        let phase = PhaseTimer::start();
 
         let sys_ctx = SystemContext::new(
             Arc::clone(&self.module_manager),
             Arc::clone(&self.grpc_installers),
-        );
+        )
+        .with_introspection(Arc::clone(&self.introspection));
 
         for entry in self.registry.modules() {
             if entry.is_system {
@@ -95,16 +145,19 @@ impl HostRuntime {
             }
         }
 
+        self.introspection.record("system_wire", None, phase);
         Ok(())
     }
 
     /// DB MIGRATION phase: run migrations for all modules with DB capability.
     ///
     /// Runs before init, with system modules processed first.
-    async fn run_db_phase(&self) -> Result<(), RegistryError> {
+    pub(crate) async fn run_db_phase(&self) -> Result<(), RegistryError> {
         tracing::info!("Phase: db (before init)");
+        let phase = PhaseTimer::start();
 
         for entry in self.registry.modules_by_system_priority() {
+            let timer = PhaseTimer::start();
             let ctx = self.ctx_builder.for_module(entry.name).await.map_err(|e| {
                 RegistryError::DbMigrate {
                     module: entry.name,
@@ -126,18 +179,24 @@ impl HostRuntime {
                     "Module has DbModule trait but no DB handle (no config)"
                 );
             }
+            if entry.db.is_some() {
+                self.introspection.record("db", Some(entry.name), timer);
+            }
         }
 
+        self.introspection.record("db", None, phase);
         Ok(())
     }
 
     /// INIT phase: initialize all modules in topological order.
     ///
     /// System modules initialize first, followed by user modules.
-    async fn run_init_phase(&self) -> Result<(), RegistryError> {
+    pub(crate) async fn run_init_phase(&self) -> Result<(), RegistryError> {
         tracing::info!("Phase: init");
+        let phase = PhaseTimer::start();
 
         for entry in self.registry.modules_by_system_priority() {
+            let timer = PhaseTimer::start();
             let ctx =
                 self.ctx_builder
                     .for_module(entry.name)
@@ -154,8 +213,10 @@ impl HostRuntime {
                     module: entry.name,
                     source: e,
                 })?;
+            self.introspection.record("init", Some(entry.name), timer);
         }
 
+        self.introspection.record("init", None, phase);
         Ok(())
     }
 
@@ -165,8 +226,9 @@ impl HostRuntime {
     /// 1. Preparing the host module
     /// 2. Registering all REST providers
     /// 3. Finalizing with OpenAPI endpoints
-    async fn run_rest_phase(&self) -> Result<Router, RegistryError> {
+    pub(crate) async fn run_rest_phase(&self) -> Result<Router, RegistryError> {
         tracing::info!("Phase: rest (sync)");
+        let phase = PhaseTimer::start();
 
         let mut router = Router::new();
 
@@ -222,6 +284,26 @@ impl HostRuntime {
                 })?;
 
         // 2) Register all REST providers (in the current discovery order)
+        router = self.register_rest_providers(router, registry).await?;
+
+        // 3) Host finalize: attach /openapi.json and /docs, persist Router if needed (no server start)
+        router = host.rest_finalize(&host_ctx, router).map_err(|source| {
+            RegistryError::RestFinalize {
+                module: host_entry.name,
+                source,
+            }
+        })?;
+
+        self.introspection.record("rest", None, phase);
+        Ok(router)
+    }
+
+    /// Register every REST provider against `registry`, in discovery order.
+    pub(crate) async fn register_rest_providers(
+        &self,
+        mut router: Router,
+        registry: &dyn crate::contracts::OpenApiRegistry,
+    ) -> Result<Router, RegistryError> {
         for e in self.registry.modules() {
             if let Some(rest) = &e.rest {
                 let ctx = self.ctx_builder.for_module(e.name).await.map_err(|err| {
@@ -239,22 +321,15 @@ impl HostRuntime {
             }
         }
 
-        // 3) Host finalize: attach /openapi.json and /docs, persist Router if needed (no server start)
-        router = host.rest_finalize(&host_ctx, router).map_err(|source| {
-            RegistryError::RestFinalize {
-                module: host_entry.name,
-                source,
-            }
-        })?;
-
         Ok(router)
     }
 
     /// gRPC registration phase: collect services from all grpc modules.
     ///
     /// Services are stored in the installer store for the grpc_hub to consume during start.
-    async fn run_grpc_phase(&self) -> Result<(), RegistryError> {
+    pub(crate) async fn run_grpc_phase(&self) -> Result<(), RegistryError> {
         tracing::info!("Phase: grpc (registration)");
+        let phase = PhaseTimer::start();
 
         // If no grpc_hub and no grpc_services, skip the phase
         if self.registry.grpc_hub.is_none() && self.registry.grpc_services.is_empty() {
@@ -313,6 +388,7 @@ impl HostRuntime {
             })?;
         }
 
+        self.introspection.record("grpc", None, phase);
         Ok(())
     }
 
@@ -321,9 +397,11 @@ impl HostRuntime {
     /// System modules start first, followed by user modules.
     async fn run_start_phase(&self) -> Result<(), RegistryError> {
         tracing::info!("Phase: start");
+        let phase = PhaseTimer::start();
 
         for e in self.registry.modules_by_system_priority() {
             if let Some(s) = &e.stateful {
+                let timer = PhaseTimer::start();
                 tracing::debug!(
                     module = e.name,
                     is_system = e.is_system,
@@ -336,28 +414,35 @@ impl HostRuntime {
                         source,
                     })?;
                 tracing::info!(module = e.name, "Started module");
+                self.introspection.record("start", Some(e.name), timer);
             }
         }
 
+        self.introspection.record("start", None, phase);
         Ok(())
     }
 
     /// STOP phase: stop all stateful modules in reverse order.
     ///
-    /// Errors are logged but do not fail the shutdown process.
-    async fn run_stop_phase(&self) -> Result<(), RegistryError> {
+    /// Each module gets its own deadline; modules that overrun it are cancelled and then
+    /// aborted. Failures never fail the shutdown process, they end up in the report.
+    pub(crate) async fn run_stop_phase(&self) -> ShutdownReport {
         tracing::info!("Phase: stop");
 
+        let started = Instant::now();
+        let mut report = ShutdownReport::default();
         for e in self.registry.modules().iter().rev() {
             if let Some(s) = &e.stateful {
-                if let Err(err) = s.stop(self.cancel.clone()).await {
-                    tracing::warn!(module = e.name, error = %err, "Failed to stop module");
-                }
-                tracing::info!(module = e.name, "Stopped module");
+                let deadline = self.stop_deadline(e.name, s.as_ref());
+                let outcome =
+                    stop_module(e.name, s.clone(), deadline, self.stop_policy.abort_grace).await;
+                tracing::info!(module = e.name, reason = ?outcome.reason, "Stopped module");
+                report.modules.push(outcome);
             }
         }
+        report.total = started.elapsed();
 
-        Ok(())
+        report
     }
 
     /// Run the full lifecycle: system_wire → DB → init → REST → gRPC → start → wait → stop.
@@ -386,7 +471,7 @@ impl HostRuntime {
         self.cancel.cancelled().await;
 
         // 8. Stop phase
-        self.run_stop_phase().await?;
+        self.run_stop_phase().await.log();
 
         Ok(())
     }
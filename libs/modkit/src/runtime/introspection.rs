@@ -0,0 +1,159 @@
+//! Runtime Introspection - live view of the modules and lifecycle phases
+//!
+//! The runtime records how long each phase (and each module within it) took and keeps
+//! handles to the stateful modules, so system modules can report their current `Status`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::config::ConfigProvider;
+use crate::contracts::StatefulModule;
+use crate::lifecycle::Status;
+use crate::registry::ModuleRegistry;
+
+/// Time one phase, or one module's part of it, took.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub phase: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<&'static str>,
+    pub started_at: DateTime<Utc>,
+    #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+    pub duration: Duration,
+}
+
+fn as_millis<S: serde::Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(d.as_secs_f64() * 1000.0)
+}
+
+/// Start of a timed phase, handed back to [`RuntimeIntrospection::record`].
+pub(crate) struct PhaseTimer {
+    started_at: DateTime<Utc>,
+    started: Instant,
+}
+
+impl PhaseTimer {
+    pub(crate) fn start() -> Self {
+        Self {
+            started_at: Utc::now(),
+            started: Instant::now(),
+        }
+    }
+}
+
+/// A module as declared, with its lifecycle status right now.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleSnapshot {
+    pub name: &'static str,
+    pub deps: Vec<&'static str>,
+    pub capabilities: Vec<&'static str>,
+    /// `None` for modules without a lifecycle
+    pub status: Option<Status>,
+    /// This module's share of the db, init and start phases
+    pub timings: Vec<PhaseTiming>,
+}
+
+struct TrackedModule {
+    name: &'static str,
+    deps: &'static [&'static str],
+    capabilities: Vec<&'static str>,
+    stateful: Option<Arc<dyn StatefulModule>>,
+}
+
+/// Modules, phase timings and module configuration of the running host.
+pub struct RuntimeIntrospection {
+    modules: Vec<TrackedModule>,
+    config: Option<Arc<dyn ConfigProvider>>,
+    timings: Mutex<Vec<PhaseTiming>>,
+}
+
+impl Default for RuntimeIntrospection {
+    fn default() -> Self {
+        Self {
+            modules: Vec::new(),
+            config: None,
+            timings: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl RuntimeIntrospection {
+    pub(crate) fn from_registry(
+        registry: &ModuleRegistry,
+        config: Arc<dyn ConfigProvider>,
+    ) -> Self {
+        let modules = registry
+            .modules()
+            .iter()
+            .map(|e| TrackedModule {
+                name: e.name,
+                deps: e.deps,
+                capabilities: e.capabilities(),
+                stateful: e.stateful.clone(),
+            })
+            .collect();
+        Self {
+            modules,
+            config: Some(config),
+            timings: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a finished phase (`module: None`) or one module's part of it.
+    pub(crate) fn record(
+        &self,
+        phase: &'static str,
+        module: Option<&'static str>,
+        timer: PhaseTimer,
+    ) {
+        self.timings.lock().push(PhaseTiming {
+            phase,
+            module,
+            started_at: timer.started_at,
+            duration: timer.started.elapsed(),
+        });
+    }
+
+    /// Modules in topological order with their current status.
+    pub fn modules(&self) -> Vec<ModuleSnapshot> {
+        let timings = self.timings.lock();
+        self.modules
+            .iter()
+            .map(|m| ModuleSnapshot {
+                name: m.name,
+                deps: m.deps.to_vec(),
+                capabilities: m.capabilities.clone(),
+                status: m.stateful.as_ref().and_then(|s| s.status()),
+                timings: timings
+                    .iter()
+                    .filter(|t| t.module == Some(m.name))
+                    .cloned()
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Completed phases, in the order they ran.
+    pub fn phases(&self) -> Vec<PhaseTiming> {
+        self.timings
+            .lock()
+            .iter()
+            .filter(|t| t.module.is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Raw configuration section of `module`, secrets included.
+    pub fn module_config(&self, module: &str) -> Option<serde_json::Value> {
+        self.config.as_ref()?.get_module_config(module).cloned()
+    }
+
+    /// Names of the modules, in topological order.
+    pub fn module_names(&self) -> Vec<&'static str> {
+        self.modules.iter().map(|m| m.name).collect()
+    }
+}
@@ -0,0 +1,242 @@
+//! Load-balancing strategies for picking among live module instances
+
+use dashmap::DashMap;
+use std::sync::Arc;
+
+use crate::runtime::ModuleInstance;
+
+/// Strategy for choosing one instance among routable candidates.
+///
+/// Candidates handed to [`LoadBalancer::pick`] are already filtered to healthy/ready
+/// instances (quarantined and draining ones are skipped) and are never empty.
+pub trait LoadBalancer: Send + Sync {
+    /// Return the index of the chosen candidate, or `None` if none is acceptable.
+    /// `target` is the service or module name being resolved.
+    fn pick(&self, target: &str, candidates: &[Arc<ModuleInstance>]) -> Option<usize>;
+}
+
+/// Strategy selector for callers that cannot hold a [`LoadBalancer`] (e.g. remote directory clients).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LbStrategy {
+    #[default]
+    RoundRobin,
+    LeastOutstanding,
+    Weighted,
+    /// Only instances reporting exactly this version.
+    VersionPinned(String),
+    /// Stable instance per key (e.g. tenant id).
+    ConsistentHash(String),
+}
+
+fn next_counter(counters: &DashMap<String, usize>, target: &str) -> usize {
+    let mut counter = counters.entry(target.to_string()).or_insert(0);
+    let current = *counter;
+    *counter = counter.wrapping_add(1);
+    current
+}
+
+/// Rotate through candidates per target.
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    counters: DashMap<String, usize>,
+}
+
+impl RoundRobin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LoadBalancer for RoundRobin {
+    fn pick(&self, target: &str, candidates: &[Arc<ModuleInstance>]) -> Option<usize> {
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(next_counter(&self.counters, target) % candidates.len())
+    }
+}
+
+/// Pick the instance with the fewest in-flight requests.
+///
+/// Requests are counted through [`ModuleInstance::track_request`]; ties are broken by rotation.
+#[derive(Debug, Default)]
+pub struct LeastOutstanding {
+    counters: DashMap<String, usize>,
+}
+
+impl LeastOutstanding {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LoadBalancer for LeastOutstanding {
+    fn pick(&self, target: &str, candidates: &[Arc<ModuleInstance>]) -> Option<usize> {
+        let len = candidates.len();
+        if len == 0 {
+            return None;
+        }
+        let start = next_counter(&self.counters, target) % len;
+        (0..len)
+            .map(|i| (start + i) % len)
+            .min_by_key(|&i| candidates[i].outstanding())
+    }
+}
+
+/// Distribute picks proportionally to [`ModuleInstance::weight`]; zero-weight instances are skipped.
+#[derive(Debug, Default)]
+pub struct Weighted {
+    counters: DashMap<String, usize>,
+}
+
+impl Weighted {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LoadBalancer for Weighted {
+    fn pick(&self, target: &str, candidates: &[Arc<ModuleInstance>]) -> Option<usize> {
+        let total: u64 = candidates.iter().map(|c| u64::from(c.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut slot = next_counter(&self.counters, target) as u64 % total;
+        for (i, c) in candidates.iter().enumerate() {
+            let w = u64::from(c.weight);
+            if slot < w {
+                return Some(i);
+            }
+            slot -= w;
+        }
+        None
+    }
+}
+
+/// Restrict candidates to one version, then delegate to another strategy.
+pub struct VersionPinned {
+    version: String,
+    inner: Arc<dyn LoadBalancer>,
+}
+
+impl VersionPinned {
+    pub fn new(version: impl Into<String>, inner: Arc<dyn LoadBalancer>) -> Self {
+        Self {
+            version: version.into(),
+            inner,
+        }
+    }
+}
+
+impl LoadBalancer for VersionPinned {
+    fn pick(&self, target: &str, candidates: &[Arc<ModuleInstance>]) -> Option<usize> {
+        let (indices, pinned): (Vec<usize>, Vec<Arc<ModuleInstance>>) = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.version.as_deref() == Some(self.version.as_str()))
+            .map(|(i, c)| (i, c.clone()))
+            .unzip();
+        if pinned.is_empty() {
+            return None;
+        }
+        self.inner
+            .pick(target, &pinned)
+            .and_then(|i| indices.get(i).copied())
+    }
+}
+
+/// Map a key to a stable instance using rendezvous hashing, so membership changes only
+/// move the keys owned by the instances that joined or left.
+#[derive(Debug, Clone)]
+pub struct ConsistentHash {
+    key: String,
+}
+
+impl ConsistentHash {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl LoadBalancer for ConsistentHash {
+    fn pick(&self, _target: &str, candidates: &[Arc<ModuleInstance>]) -> Option<usize> {
+        candidates
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, c)| fnv1a(&[self.key.as_bytes(), b"\0", c.instance_id.as_bytes()]))
+            .map(|(i, _)| i)
+    }
+}
+
+/// FNV-1a: stable across processes and builds, unlike `DefaultHasher`.
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in parts.iter().flat_map(|p| p.iter()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inst(id: &str) -> Arc<ModuleInstance> {
+        Arc::new(ModuleInstance::new("m", id))
+    }
+
+    #[test]
+    fn test_least_outstanding_prefers_idle_instance() {
+        let lb = LeastOutstanding::new();
+        let candidates = vec![inst("a"), inst("b")];
+        let _busy = candidates[0].track_request();
+        for _ in 0..4 {
+            assert_eq!(lb.pick("svc", &candidates), Some(1));
+        }
+    }
+
+    #[test]
+    fn test_weighted_distribution() {
+        let lb = Weighted::new();
+        let a = Arc::new(ModuleInstance::new("m", "a").with_weight(3));
+        let b = Arc::new(ModuleInstance::new("m", "b").with_weight(1));
+        let off = Arc::new(ModuleInstance::new("m", "off").with_weight(0));
+        let candidates = vec![a, b, off];
+        let picks: Vec<_> = (0..8).filter_map(|_| lb.pick("svc", &candidates)).collect();
+        assert_eq!(picks.iter().filter(|&&i| i == 0).count(), 6);
+        assert_eq!(picks.iter().filter(|&&i| i == 1).count(), 2);
+        assert!(!picks.contains(&2));
+    }
+
+    #[test]
+    fn test_version_pinned() {
+        let lb = VersionPinned::new("2.0", Arc::new(RoundRobin::new()));
+        let candidates = vec![
+            Arc::new(ModuleInstance::new("m", "old").with_version("1.0")),
+            Arc::new(ModuleInstance::new("m", "new").with_version("2.0")),
+        ];
+        assert_eq!(lb.pick("svc", &candidates), Some(1));
+        assert_eq!(lb.pick("svc", &candidates), Some(1));
+        assert_eq!(lb.pick("svc", &candidates[..1]), None);
+    }
+
+    #[test]
+    fn test_consistent_hash_is_stable() {
+        let candidates = vec![inst("a"), inst("b"), inst("c"), inst("d")];
+        let lb = ConsistentHash::new("tenant-42");
+        let first = lb.pick("svc", &candidates).unwrap();
+        assert_eq!(lb.pick("svc", &candidates), Some(first));
+
+        // Removing another instance does not move the key
+        let other = (first + 1) % candidates.len();
+        let remaining: Vec<_> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != other)
+            .map(|(_, c)| c.clone())
+            .collect();
+        let picked = lb.pick("svc", &remaining).unwrap();
+        assert_eq!(remaining[picked].instance_id, candidates[first].instance_id);
+    }
+}
@@ -1,9 +1,12 @@
 mod backend;
 mod grpc_installers;
 mod host_runtime;
+mod introspection;
+mod load_balancer;
 mod module_manager;
 mod runner;
 mod shutdown;
+mod stop;
 mod system_context;
 
 #[cfg(test)]
@@ -16,10 +19,19 @@ pub mod backends;
 pub use backend::{BackendKind, InstanceHandle, OopModuleConfig};
 
 // Re-export backend trait and implementations for convenience
-pub use backends::{LocalProcessBackend, ModuleRuntimeBackend};
+pub use backends::{
+    LocalProcessBackend, MockBackend, MockEvent, ModuleRuntimeBackend, StaticBackend,
+};
 
 pub use grpc_installers::GrpcInstallerStore;
 pub use host_runtime::{DbOptions, HostRuntime};
-pub use module_manager::{Endpoint, InstanceState, ModuleInstance, ModuleManager, ModuleName};
+pub use introspection::{ModuleSnapshot, PhaseTiming, RuntimeIntrospection};
+pub use load_balancer::{
+    ConsistentHash, LbStrategy, LeastOutstanding, LoadBalancer, RoundRobin, VersionPinned, Weighted,
+};
+pub use module_manager::{
+    Endpoint, InstanceState, ModuleInstance, ModuleManager, ModuleName, RequestGuard,
+};
 pub use runner::{run, RunOptions, ShutdownOptions};
+pub use stop::{ModuleStopReport, ShutdownReport, StopPolicy, STOP_TIMEOUT_KEY};
 pub use system_context::SystemContext;
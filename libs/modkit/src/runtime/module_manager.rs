@@ -2,9 +2,12 @@
 
 use dashmap::DashMap;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use crate::runtime::load_balancer::LoadBalancer;
+
 /// Common module identifier
 pub type ModuleName = &'static str;
 
@@ -80,7 +83,25 @@ pub struct ModuleInstance {
     pub control: Option<Endpoint>,
     pub grpc_services: HashMap<String, Endpoint>,
     pub version: Option<String>,
+    /// Pinned instances never heartbeat and are exempt from stale eviction
+    /// (e.g. statically configured sidecars).
+    pub pinned: bool,
+    /// Relative share of traffic for weighted load balancing (default 1).
+    pub weight: u32,
     inner: Arc<parking_lot::RwLock<InstanceRuntimeState>>,
+    outstanding: Arc<AtomicUsize>,
+}
+
+/// In-flight request marker returned by [`ModuleInstance::track_request`].
+#[derive(Debug)]
+pub struct RequestGuard {
+    outstanding: Arc<AtomicUsize>,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.outstanding.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl Clone for ModuleInstance {
@@ -91,7 +112,10 @@ impl Clone for ModuleInstance {
             control: self.control.clone(),
             grpc_services: self.grpc_services.clone(),
             version: self.version.clone(),
+            pinned: self.pinned,
+            weight: self.weight,
             inner: Arc::clone(&self.inner),
+            outstanding: Arc::clone(&self.outstanding),
         }
     }
 }
@@ -104,10 +128,13 @@ impl ModuleInstance {
             control: None,
             grpc_services: HashMap::new(),
             version: None,
+            pinned: false,
+            weight: 1,
             inner: Arc::new(parking_lot::RwLock::new(InstanceRuntimeState {
                 last_heartbeat: Instant::now(),
                 state: InstanceState::Registered,
             })),
+            outstanding: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -126,6 +153,16 @@ impl ModuleInstance {
         self
     }
 
+    pub fn pinned(mut self) -> Self {
+        self.pinned = true;
+        self
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
     /// Get the current state of this instance
     pub fn state(&self) -> InstanceState {
         self.inner.read().state
@@ -135,6 +172,24 @@ impl ModuleInstance {
     pub fn last_heartbeat(&self) -> Instant {
         self.inner.read().last_heartbeat
     }
+
+    /// Count a request as in flight until the returned guard is dropped
+    pub fn track_request(&self) -> RequestGuard {
+        self.outstanding.fetch_add(1, Ordering::Relaxed);
+        RequestGuard {
+            outstanding: Arc::clone(&self.outstanding),
+        }
+    }
+
+    /// Number of requests currently in flight
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.load(Ordering::Relaxed)
+    }
+
+    /// Whether the instance may receive traffic (ready or healthy)
+    pub fn is_routable(&self) -> bool {
+        matches!(self.state(), InstanceState::Healthy | InstanceState::Ready)
+    }
 }
 
 /// Central registry that tracks all running module instances in the system.
@@ -275,6 +330,9 @@ impl ModuleManager {
             let module = *entry.key();
             let vec = entry.value_mut();
             vec.retain(|inst| {
+                if inst.pinned {
+                    return true;
+                }
                 let state = inst.inner.read();
                 let age = now.saturating_duration_since(state.last_heartbeat);
 
@@ -372,6 +430,51 @@ impl ModuleManager {
 
         candidates.get(idx).cloned()
     }
+
+    /// Pick a routable instance of a module with the given strategy.
+    pub fn pick_instance(
+        &self,
+        module: &str,
+        lb: &dyn LoadBalancer,
+    ) -> Option<Arc<ModuleInstance>> {
+        let candidates: Vec<_> = self
+            .instances_of_static(module)
+            .into_iter()
+            .filter(|inst| inst.is_routable())
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let idx = lb.pick(module, &candidates)?;
+        candidates.get(idx).cloned()
+    }
+
+    /// Pick a service endpoint among routable instances with the given strategy,
+    /// returning (module, instance, endpoint).
+    pub fn pick_service(
+        &self,
+        service_name: &str,
+        lb: &dyn LoadBalancer,
+    ) -> Option<(ModuleName, Arc<ModuleInstance>, Endpoint)> {
+        let mut candidates = Vec::new();
+        for entry in self.inner.iter() {
+            let module = *entry.key();
+            for inst in entry.value().iter() {
+                if let Some(ep) = inst.grpc_services.get(service_name) {
+                    if inst.is_routable() {
+                        candidates.push((module, inst.clone(), ep.clone()));
+                    }
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let instances: Vec<_> = candidates.iter().map(|(_, inst, _)| inst.clone()).collect();
+        let idx = lb.pick(service_name, &instances)?;
+        candidates.into_iter().nth(idx)
+    }
 }
 
 impl Default for ModuleManager {
@@ -0,0 +1,140 @@
+//! Stop deadlines and the shutdown report
+//!
+//! Each stateful module is stopped with a deadline. When it is exceeded, the runtime
+//! escalates: first it cancels the token passed to `StatefulModule::stop`, then, after a
+//! short grace period, it aborts the stop task so one stuck module cannot block exit.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::contracts::StatefulModule;
+use crate::lifecycle::StopReason;
+
+/// Config key (in `modules.<name>`) overriding a module's stop deadline, in milliseconds.
+pub const STOP_TIMEOUT_KEY: &str = "stop_timeout_ms";
+
+/// Runtime-wide stop deadlines.
+#[derive(Clone, Copy, Debug)]
+pub struct StopPolicy {
+    /// Deadline for modules that neither configure one nor report `StatefulModule::stop_timeout`.
+    pub default_timeout: Duration,
+    /// How long a module may keep stopping after its cancel token fired before being aborted.
+    pub abort_grace: Duration,
+}
+
+impl Default for StopPolicy {
+    fn default() -> Self {
+        Self {
+            default_timeout: Duration::from_secs(30),
+            abort_grace: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Outcome of stopping a single module.
+#[derive(Clone, Debug)]
+pub struct ModuleStopReport {
+    pub module: &'static str,
+    /// `Finished` within the deadline, `Cancelled` after the cancel escalation,
+    /// `Timeout` when the stop task had to be aborted.
+    pub reason: StopReason,
+    pub elapsed: Duration,
+    /// Error returned by `stop`, if any.
+    pub error: Option<String>,
+}
+
+/// Per-module stop outcomes, in stop order.
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownReport {
+    pub modules: Vec<ModuleStopReport>,
+    pub total: Duration,
+}
+
+impl ShutdownReport {
+    /// True when every module finished within its deadline without error.
+    pub fn is_clean(&self) -> bool {
+        self.modules
+            .iter()
+            .all(|m| m.reason == StopReason::Finished && m.error.is_none())
+    }
+
+    /// Emit one line per module plus a summary.
+    pub fn log(&self) {
+        for m in &self.modules {
+            let elapsed_ms = m.elapsed.as_millis() as u64;
+            match (&m.reason, &m.error) {
+                (StopReason::Finished, None) => tracing::info!(
+                    module = m.module,
+                    reason = ?m.reason,
+                    elapsed_ms,
+                    "shutdown report"
+                ),
+                (_, error) => tracing::warn!(
+                    module = m.module,
+                    reason = ?m.reason,
+                    elapsed_ms,
+                    error = error.as_deref().unwrap_or(""),
+                    "shutdown report"
+                ),
+            }
+        }
+        tracing::info!(
+            modules = self.modules.len(),
+            total_ms = self.total.as_millis() as u64,
+            clean = self.is_clean(),
+            "Shutdown complete"
+        );
+    }
+}
+
+/// Stop one module, escalating from cooperative stop to cancel to abort.
+pub(crate) async fn stop_module(
+    name: &'static str,
+    module: Arc<dyn StatefulModule>,
+    deadline: Duration,
+    abort_grace: Duration,
+) -> ModuleStopReport {
+    let started = Instant::now();
+    let cancel = CancellationToken::new();
+    let token = cancel.clone();
+    let mut task = tokio::spawn(async move { module.stop(token).await });
+
+    let (reason, error) = match tokio::time::timeout(deadline, &mut task).await {
+        Ok(res) => (StopReason::Finished, join_error(res)),
+        Err(_) => {
+            tracing::warn!(
+                module = name,
+                deadline_ms = deadline.as_millis() as u64,
+                "Stop deadline exceeded; cancelling"
+            );
+            cancel.cancel();
+            match tokio::time::timeout(abort_grace, &mut task).await {
+                Ok(res) => (StopReason::Cancelled, join_error(res)),
+                Err(_) => {
+                    tracing::error!(module = name, "Module ignored stop cancellation; aborting");
+                    task.abort();
+                    (
+                        StopReason::Timeout,
+                        Some(format!("aborted after {:?}", started.elapsed())),
+                    )
+                }
+            }
+        }
+    };
+
+    ModuleStopReport {
+        module: name,
+        reason,
+        elapsed: started.elapsed(),
+        error,
+    }
+}
+
+fn join_error(res: Result<anyhow::Result<()>, tokio::task::JoinError>) -> Option<String> {
+    match res {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(format!("{e:#}")),
+        Err(e) => Some(format!("stop task failed: {e}")),
+    }
+}
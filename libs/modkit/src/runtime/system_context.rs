@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 
-use crate::runtime::{GrpcInstallerStore, ModuleManager};
+use crate::runtime::{GrpcInstallerStore, ModuleManager, RuntimeIntrospection};
 
 /// System-level context provided to system modules during the wiring phase.
 ///
@@ -16,6 +16,9 @@ pub struct SystemContext {
 
     /// gRPC service installer store
     pub grpc_installers: Arc<GrpcInstallerStore>,
+
+    /// Modules, lifecycle phase timings and configuration of the running host
+    pub introspection: Arc<RuntimeIntrospection>,
 }
 
 impl SystemContext {
@@ -27,6 +30,13 @@ impl SystemContext {
         Self {
             module_manager,
             grpc_installers,
+            introspection: Arc::new(RuntimeIntrospection::default()),
         }
     }
+
+    /// Replace the (empty) introspection view with the runtime's.
+    pub fn with_introspection(mut self, introspection: Arc<RuntimeIntrospection>) -> Self {
+        self.introspection = introspection;
+        self
+    }
 }
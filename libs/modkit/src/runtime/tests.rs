@@ -261,3 +261,120 @@ async fn test_task_panic_handling() {
     assert!(matches!(reason, StopReason::Finished | StopReason::Timeout));
     assert_eq!(lc.status(), Status::Stopped);
 }
+
+/// Stateful module whose stop takes `delay`, optionally ignoring the cancel token.
+struct SlowStop {
+    delay: Duration,
+    honour_cancel: bool,
+}
+
+#[async_trait::async_trait]
+impl crate::contracts::StatefulModule for SlowStop {
+    async fn start(&self, _cancel: tokio_util::sync::CancellationToken) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&self, cancel: tokio_util::sync::CancellationToken) -> anyhow::Result<()> {
+        if self.honour_cancel {
+            tokio::select! {
+                _ = tokio::time::sleep(self.delay) => Ok(()),
+                _ = cancel.cancelled() => anyhow::bail!("stop cancelled"),
+            }
+        } else {
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_stop_module_escalation() {
+    use crate::runtime::stop::stop_module;
+
+    let fast = Arc::new(SlowStop {
+        delay: Duration::from_millis(5),
+        honour_cancel: true,
+    });
+    let report = stop_module("fast", fast, Duration::from_secs(1), Duration::from_secs(1)).await;
+    assert_eq!(report.reason, StopReason::Finished);
+    assert!(report.error.is_none());
+
+    let cooperative = Arc::new(SlowStop {
+        delay: Duration::from_secs(60),
+        honour_cancel: true,
+    });
+    let report = stop_module(
+        "cooperative",
+        cooperative,
+        Duration::from_millis(20),
+        Duration::from_secs(1),
+    )
+    .await;
+    assert_eq!(report.reason, StopReason::Cancelled);
+    assert!(report.error.unwrap().contains("stop cancelled"));
+
+    let stuck = Arc::new(SlowStop {
+        delay: Duration::from_secs(60),
+        honour_cancel: false,
+    });
+    let report = stop_module(
+        "stuck",
+        stuck,
+        Duration::from_millis(20),
+        Duration::from_millis(20),
+    )
+    .await;
+    assert_eq!(report.reason, StopReason::Timeout);
+    assert!(report.elapsed < Duration::from_secs(5));
+}
+
+#[test]
+fn test_with_lifecycle_reports_stop_timeout() {
+    use crate::contracts::StatefulModule;
+
+    struct Noop;
+    #[async_trait::async_trait]
+    impl crate::lifecycle::Runnable for Noop {
+        async fn run(
+            self: Arc<Self>,
+            cancel: tokio_util::sync::CancellationToken,
+        ) -> anyhow::Result<()> {
+            cancel.cancelled().await;
+            Ok(())
+        }
+    }
+
+    let module =
+        crate::lifecycle::WithLifecycle::new(Noop).with_stop_timeout(Duration::from_secs(7));
+    assert_eq!(module.stop_timeout(), Some(Duration::from_secs(7)));
+}
+
+#[tokio::test]
+async fn test_with_lifecycle_reports_status() {
+    use crate::contracts::StatefulModule;
+
+    struct Noop;
+    #[async_trait::async_trait]
+    impl crate::lifecycle::Runnable for Noop {
+        async fn run(
+            self: Arc<Self>,
+            cancel: tokio_util::sync::CancellationToken,
+        ) -> anyhow::Result<()> {
+            cancel.cancelled().await;
+            Ok(())
+        }
+    }
+
+    let module = crate::lifecycle::WithLifecycle::new(Noop);
+    assert_eq!(StatefulModule::status(&module), Some(Status::Stopped));
+    module
+        .start(tokio_util::sync::CancellationToken::new())
+        .await
+        .unwrap();
+    assert_ne!(StatefulModule::status(&module), Some(Status::Stopped));
+    module
+        .stop(tokio_util::sync::CancellationToken::new())
+        .await
+        .unwrap();
+    assert_eq!(StatefulModule::status(&module), Some(Status::Stopped));
+}
@@ -0,0 +1,311 @@
+//! In-process test harness for booting a subset of modules.
+//!
+//! [`TestRuntime`] picks modules from the inventory registry by name (plus their dependencies),
+//! feeds them config, gives every DB module its own in-memory SQLite database and runs the
+//! system_wire → DB → init → REST → gRPC phases. Stateful modules are not started; teardown
+//! goes through the normal stop phase.
+//!
+//! ```rust,ignore
+//! let rt = TestRuntime::builder()
+//!     .module("users_info")
+//!     .module_config("users_info", json!({ "default_page_size": 5 }))
+//!     .build()
+//!     .await?;
+//!
+//! let resp = rt.get(&SecurityCtx::root_ctx(), "/users").await;
+//! assert_eq!(resp.status(), StatusCode::OK);
+//! rt.shutdown().await;
+//! ```
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use axum::response::Response;
+use axum::Router;
+use figment::{providers::Serialized, Figment};
+use modkit_security::SecurityCtx;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+use tokio_util::sync::CancellationToken;
+use tower::ServiceExt;
+
+use crate::api::OpenApiRegistryImpl;
+use crate::client_hub::ClientHub;
+use crate::config::ConfigProvider;
+use crate::registry::ModuleRegistry;
+use crate::runtime::{DbOptions, HostRuntime, ShutdownReport};
+
+/// Config provider over an in-memory `modules` map.
+struct StaticConfig {
+    modules: Map<String, Value>,
+}
+
+impl ConfigProvider for StaticConfig {
+    fn get_module_config(&self, module_name: &str) -> Option<&Value> {
+        self.modules.get(module_name)
+    }
+}
+
+/// Builder for [`TestRuntime`].
+#[derive(Default)]
+pub struct TestRuntimeBuilder {
+    modules: Vec<&'static str>,
+    yaml: Vec<String>,
+    json: Vec<Value>,
+    module_configs: Vec<(String, Value)>,
+    in_memory_db: bool,
+}
+
+impl TestRuntimeBuilder {
+    /// Select a module by name; its dependencies are pulled in automatically.
+    pub fn module(mut self, name: &'static str) -> Self {
+        self.modules.push(name);
+        self
+    }
+
+    /// Select several modules by name.
+    pub fn modules(mut self, names: impl IntoIterator<Item = &'static str>) -> Self {
+        self.modules.extend(names);
+        self
+    }
+
+    /// Merge a YAML document shaped like the app's `modules:` map
+    /// (`<name>: { config: ..., database: ... }`). Parse errors surface from `build()`.
+    pub fn config_yaml(mut self, yaml: impl Into<String>) -> Self {
+        self.yaml.push(yaml.into());
+        self
+    }
+
+    /// Merge a JSON value shaped like the app's `modules:` map.
+    pub fn config_json(mut self, modules: Value) -> Self {
+        self.json.push(modules);
+        self
+    }
+
+    /// Set `modules.<name>.config`.
+    pub fn module_config(mut self, name: impl Into<String>, config: Value) -> Self {
+        self.module_configs.push((name.into(), config));
+        self
+    }
+
+    /// Give DB modules without an explicit `database` section an in-memory SQLite
+    /// database (default: on).
+    pub fn in_memory_db(mut self, enabled: bool) -> Self {
+        self.in_memory_db = enabled;
+        self
+    }
+
+    /// Discover the selected modules and run them up to (and including) the gRPC phase.
+    pub async fn build(self) -> anyhow::Result<TestRuntime> {
+        let mut modules = Map::new();
+        for raw in &self.yaml {
+            let value: Value = serde_yaml::from_str(raw)
+                .map_err(|e| anyhow::anyhow!("invalid test config YAML: {e}"))?;
+            merge(&mut modules, value);
+        }
+        for value in self.json {
+            merge(&mut modules, value);
+        }
+        for (name, config) in self.module_configs {
+            merge(&mut modules, json!({ name: { "config": config } }));
+        }
+
+        let registry = ModuleRegistry::discover_and_build_only(&self.modules)?;
+
+        // One private in-memory database per DB module and per runtime. Every connection to
+        // `sqlite::memory:` opens a database of its own, so the pool is held to one.
+        if self.in_memory_db {
+            let db_modules: HashSet<_> = registry
+                .modules()
+                .iter()
+                .filter(|e| e.db.is_some())
+                .map(|e| e.name)
+                .collect();
+            for name in db_modules {
+                let entry = modules
+                    .entry(name.to_string())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Some(obj) = entry.as_object_mut() {
+                    obj.entry("database").or_insert_with(
+                        || json!({ "dsn": "sqlite::memory:", "pool": { "max_conns": 1 } }),
+                    );
+                }
+            }
+        }
+
+        let figment = Figment::new().merge(Serialized::defaults(json!({ "modules": modules })));
+        let run_id = uuid::Uuid::new_v4().simple().to_string();
+        let home_dir: PathBuf = std::env::temp_dir().join(format!("modkit-test-{run_id}"));
+        let db_manager = Arc::new(modkit_db::DbManager::from_figment(figment, home_dir)?);
+
+        let hub = Arc::new(ClientHub::default());
+        let cancel = CancellationToken::new();
+        let host = HostRuntime::new(
+            registry,
+            Arc::new(StaticConfig { modules }),
+            DbOptions::Manager(db_manager),
+            hub.clone(),
+            cancel.clone(),
+        );
+
+        host.wire_system().await?;
+        host.run_db_phase().await?;
+        host.run_init_phase().await?;
+        let router = if host
+            .registry()
+            .modules()
+            .iter()
+            .any(|e| e.rest_host.is_some())
+        {
+            host.run_rest_phase().await?
+        } else {
+            // No REST host selected: mount the providers on a bare router
+            let openapi = OpenApiRegistryImpl::new();
+            host.register_rest_providers(Router::new(), &openapi)
+                .await?
+        };
+        host.run_grpc_phase().await?;
+
+        Ok(TestRuntime {
+            host,
+            router,
+            hub,
+            cancel,
+        })
+    }
+}
+
+/// Deep-merge `value` into `target`; objects merge recursively, anything else replaces.
+fn merge(target: &mut Map<String, Value>, value: Value) {
+    let Value::Object(src) = value else {
+        return;
+    };
+    for (k, v) in src {
+        match (target.get_mut(&k), v) {
+            (Some(Value::Object(dst)), Value::Object(src)) => merge(dst, Value::Object(src)),
+            (_, v) => {
+                target.insert(k, v);
+            }
+        }
+    }
+}
+
+/// A booted subset of modules with a ready router.
+///
+/// Requests issued through the helpers carry the given [`SecurityCtx`] as a request extension,
+/// which is what `Authz` extracts. A selected REST host may replace it with its own auth layer.
+pub struct TestRuntime {
+    host: HostRuntime,
+    router: Router,
+    hub: Arc<ClientHub>,
+    cancel: CancellationToken,
+}
+
+impl TestRuntime {
+    pub fn builder() -> TestRuntimeBuilder {
+        TestRuntimeBuilder {
+            in_memory_db: true,
+            ..Default::default()
+        }
+    }
+
+    /// The composed router of all selected REST modules.
+    pub fn router(&self) -> Router {
+        self.router.clone()
+    }
+
+    /// Client hub the modules exposed their clients to.
+    pub fn client_hub(&self) -> Arc<ClientHub> {
+        self.hub.clone()
+    }
+
+    /// Send `req` through the router as `ctx`.
+    pub async fn request(&self, ctx: &SecurityCtx, mut req: Request<Body>) -> Response {
+        req.extensions_mut().insert(ctx.clone());
+        match self.router.clone().oneshot(req).await {
+            Ok(resp) => resp,
+            Err(never) => match never {},
+        }
+    }
+
+    pub async fn get(&self, ctx: &SecurityCtx, uri: &str) -> Response {
+        self.send(ctx, Method::GET, uri, Body::empty(), None).await
+    }
+
+    pub async fn delete(&self, ctx: &SecurityCtx, uri: &str) -> Response {
+        self.send(ctx, Method::DELETE, uri, Body::empty(), None)
+            .await
+    }
+
+    pub async fn post_json<B: Serialize>(
+        &self,
+        ctx: &SecurityCtx,
+        uri: &str,
+        body: &B,
+    ) -> Response {
+        self.send_json(ctx, Method::POST, uri, body).await
+    }
+
+    pub async fn put_json<B: Serialize>(&self, ctx: &SecurityCtx, uri: &str, body: &B) -> Response {
+        self.send_json(ctx, Method::PUT, uri, body).await
+    }
+
+    pub async fn patch_json<B: Serialize>(
+        &self,
+        ctx: &SecurityCtx,
+        uri: &str,
+        body: &B,
+    ) -> Response {
+        self.send_json(ctx, Method::PATCH, uri, body).await
+    }
+
+    /// Read a response body as JSON.
+    pub async fn read_json<T: DeserializeOwned>(resp: Response) -> anyhow::Result<T> {
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Cancel the root token and run the stop phase.
+    pub async fn shutdown(self) -> ShutdownReport {
+        self.cancel.cancel();
+        self.host.run_stop_phase().await
+    }
+
+    async fn send_json<B: Serialize>(
+        &self,
+        ctx: &SecurityCtx,
+        method: Method,
+        uri: &str,
+        body: &B,
+    ) -> Response {
+        let body = serde_json::to_vec(body).expect("request body must serialize to JSON");
+        self.send(ctx, method, uri, Body::from(body), Some("application/json"))
+            .await
+    }
+
+    async fn send(
+        &self,
+        ctx: &SecurityCtx,
+        method: Method,
+        uri: &str,
+        body: Body,
+        content_type: Option<&str>,
+    ) -> Response {
+        let mut req = Request::builder().method(method).uri(uri);
+        if let Some(ct) = content_type {
+            req = req.header(header::CONTENT_TYPE, ct);
+        }
+        let req = req.body(body).expect("valid test request");
+        self.request(ctx, req).await
+    }
+}
+
+impl Drop for TestRuntime {
+    fn drop(&mut self) {
+        // Background tasks tied to the root token must not outlive a test that skipped shutdown()
+        self.cancel.cancel();
+    }
+}
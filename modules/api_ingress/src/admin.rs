@@ -0,0 +1,383 @@
+//! Runtime admin API: read-only introspection of the running host
+//!
+//! Served under `admin.path` (default `/admin`), either on the main listener or on a separate
+//! `admin.bind_addr`, and always behind the `admin.role` role. With `auth_disabled` nothing
+//! checks that role, so the API is only mounted on a loopback `admin.bind_addr`:
+//!
+//! - `GET {path}/modules`   modules with their lifecycle status and phase timings
+//! - `GET {path}/instances` instances known to the `ModuleManager` and their states
+//! - `GET {path}/config`    per-module configuration, secrets redacted
+//! - `GET {path}/routes`    registered operations
+//! - `GET {path}/grpc`      gRPC services registered with the hub
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::routing::get;
+use axum::{Json, Router};
+use modkit::api::{OpenApiRegistryImpl, OperationSpec};
+use modkit::runtime::{
+    GrpcInstallerStore, InstanceState, ModuleManager, RuntimeIntrospection, SystemContext,
+};
+use serde_json::{json, Map, Value};
+
+/// Endpoints below the admin prefix.
+pub const ENDPOINTS: [&str; 5] = ["/modules", "/instances", "/config", "/routes", "/grpc"];
+
+/// Keys whose values are never shown (matched case-insensitively as substrings).
+const SECRET_KEYS: [&str; 7] = [
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "private_key",
+    "credential",
+];
+const REDACTED: &str = "***";
+
+/// Runtime internals the admin API reads, wired in by the runtime.
+#[derive(Clone)]
+pub struct AdminSources {
+    pub module_manager: Arc<ModuleManager>,
+    pub grpc_installers: Arc<GrpcInstallerStore>,
+    pub introspection: Arc<RuntimeIntrospection>,
+}
+
+impl AdminSources {
+    pub fn from_system(sys: &SystemContext) -> Self {
+        Self {
+            module_manager: sys.module_manager.clone(),
+            grpc_installers: sys.grpc_installers.clone(),
+            introspection: sys.introspection.clone(),
+        }
+    }
+}
+
+impl Default for AdminSources {
+    fn default() -> Self {
+        Self {
+            module_manager: Arc::new(ModuleManager::new()),
+            grpc_installers: Arc::new(GrpcInstallerStore::new()),
+            introspection: Arc::new(RuntimeIntrospection::default()),
+        }
+    }
+}
+
+/// Admin routes under `prefix`; the caller adds the auth requirement for them.
+pub fn router(prefix: &str, sources: AdminSources, registry: Arc<OpenApiRegistryImpl>) -> Router {
+    let prefix = prefix.trim_end_matches('/');
+    let at = |endpoint: &str| format!("{prefix}{endpoint}");
+
+    let s = sources.clone();
+    let modules = move || {
+        let s = s.clone();
+        async move {
+            Json(json!({
+                "phases": s.introspection.phases(),
+                "modules": s.introspection.modules(),
+            }))
+        }
+    };
+    let s = sources.clone();
+    let instances = move || {
+        let s = s.clone();
+        async move { Json(instances_view(&s.module_manager)) }
+    };
+    let s = sources.clone();
+    let config = move || {
+        let s = s.clone();
+        async move { Json(config_view(&s.introspection)) }
+    };
+    let routes = move || {
+        let registry = registry.clone();
+        async move {
+            let mut specs: Vec<OperationSpec> = registry
+                .operation_specs
+                .iter()
+                .map(|e| e.value().clone())
+                .collect();
+            specs.sort_by(|a, b| {
+                a.path
+                    .cmp(&b.path)
+                    .then(a.method.as_str().cmp(b.method.as_str()))
+            });
+            Json(Value::Array(specs.iter().map(route_view).collect()))
+        }
+    };
+    let s = sources;
+    let grpc = move || {
+        let s = s.clone();
+        async move { Json(json!({ "services": s.grpc_installers.service_names() })) }
+    };
+
+    Router::new()
+        .route(&at("/modules"), get(modules))
+        .route(&at("/instances"), get(instances))
+        .route(&at("/config"), get(config))
+        .route(&at("/routes"), get(routes))
+        .route(&at("/grpc"), get(grpc))
+}
+
+fn instances_view(manager: &ModuleManager) -> Value {
+    let now = Instant::now();
+    let mut instances = manager.all_instances();
+    instances.sort_by(|a, b| {
+        a.module
+            .cmp(b.module)
+            .then(a.instance_id.cmp(&b.instance_id))
+    });
+    Value::Array(
+        instances
+            .iter()
+            .map(|i| {
+                let grpc: Map<String, Value> = i
+                    .grpc_services
+                    .iter()
+                    .map(|(name, ep)| (name.clone(), json!(ep.uri)))
+                    .collect();
+                json!({
+                    "module": i.module,
+                    "instance_id": i.instance_id,
+                    "state": state_name(i.state()),
+                    "version": i.version,
+                    "control": i.control.as_ref().map(|ep| ep.uri.clone()),
+                    "grpc_services": grpc,
+                    "pinned": i.pinned,
+                    "weight": i.weight,
+                    "outstanding": i.outstanding(),
+                    "last_heartbeat_secs_ago": now
+                        .saturating_duration_since(i.last_heartbeat())
+                        .as_secs_f64(),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn state_name(state: InstanceState) -> &'static str {
+    match state {
+        InstanceState::Registered => "registered",
+        InstanceState::Ready => "ready",
+        InstanceState::Healthy => "healthy",
+        InstanceState::Quarantined => "quarantined",
+        InstanceState::Draining => "draining",
+    }
+}
+
+fn config_view(introspection: &RuntimeIntrospection) -> Value {
+    let modules: Map<String, Value> = introspection
+        .module_names()
+        .into_iter()
+        .filter_map(|name| {
+            let mut section = introspection.module_config(name)?;
+            redact(&mut section);
+            Some((name.to_string(), section))
+        })
+        .collect();
+    Value::Object(modules)
+}
+
+/// Blank out secret-looking keys, and passwords or tokens embedded in DSNs, URLs and libpq
+/// `key=value` connection strings.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if !v.is_null() && SECRET_KEYS.iter().any(|s| key.contains(s)) {
+                    *v = json!(REDACTED);
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        Value::String(s) if s.contains("://") => {
+            if let Some(redacted) = redact_url(s) {
+                *s = redacted;
+            }
+        }
+        Value::String(s) if s.contains('=') => {
+            if let Some(redacted) = redact_key_values(s) {
+                *s = redacted;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|s| key.contains(s))
+}
+
+/// The URL with its password and secret query parameters blanked out; `None` if it has none.
+fn redact_url(s: &str) -> Option<String> {
+    let Ok(mut url) = url::Url::parse(s) else {
+        // Unparsable, but may still carry credentials
+        return s.contains('@').then(|| REDACTED.to_string());
+    };
+    let mut changed = false;
+    if url.password().is_some() {
+        let _ = url.set_password(Some(REDACTED));
+        changed = true;
+    }
+    let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    if pairs.iter().any(|(k, _)| is_secret(k)) {
+        url.query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs.iter().map(|(k, v)| {
+                let v = if is_secret(k) { REDACTED } else { v.as_str() };
+                (k.as_str(), v)
+            }));
+        changed = true;
+    }
+    changed.then(|| url.to_string())
+}
+
+/// A libpq-style `host=db password='p w'` string with secret values blanked out; `None` if
+/// the string is not made of `key=value` pairs or has no secret in it.
+fn redact_key_values(s: &str) -> Option<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut changed = false;
+    let mut rest = s;
+    loop {
+        let trimmed = rest.trim_start();
+        out.push_str(&rest[..rest.len() - trimmed.len()]);
+        rest = trimmed;
+        if rest.is_empty() {
+            break;
+        }
+        // libpq allows whitespace around `=`
+        let (key_part, after) = rest.split_once('=')?;
+        let key = key_part.trim_end();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        let value = after.trim_start();
+        let (spacing, after) = after.split_at(after.len() - value.len());
+        // Single-quoted values may contain spaces and backslash-escaped quotes
+        let value_len = if let Some(quoted) = after.strip_prefix('\'') {
+            let mut escaped = false;
+            let close = quoted.char_indices().find(|&(_, c)| {
+                let end = c == '\'' && !escaped;
+                escaped = c == '\\' && !escaped;
+                end
+            })?;
+            close.0 + 2
+        } else {
+            after.find(char::is_whitespace).unwrap_or(after.len())
+        };
+        out.push_str(key_part);
+        out.push('=');
+        out.push_str(spacing);
+        if is_secret(key) {
+            out.push_str(REDACTED);
+            changed = true;
+        } else {
+            out.push_str(&after[..value_len]);
+        }
+        rest = &after[value_len..];
+    }
+    changed.then_some(out)
+}
+
+fn route_view(spec: &OperationSpec) -> Value {
+    json!({
+        "method": spec.method.as_str(),
+        "path": spec.path,
+        "handler_id": spec.handler_id,
+        "operation_id": spec.operation_id,
+        "summary": spec.summary,
+        "tags": spec.tags,
+        "public": spec.is_public,
+        "required_role": spec
+            .sec_requirement
+            .as_ref()
+            .map(|r| format!("{}:{}", r.resource, r.action)),
+        "request_content_type": spec.request_body.as_ref().map(|b| b.content_type),
+        "responses": spec
+            .responses
+            .iter()
+            .map(|r| json!({ "status": r.status, "content_type": r.content_type }))
+            .collect::<Vec<_>>(),
+        "version": spec.version.as_ref().map(|v| v.name.clone()),
+        "deprecated": spec.deprecation.is_some(),
+        "timeout_ms": spec.timeout.map(|t| t.as_millis() as u64),
+        "rate_limit": spec.rate_limit.as_ref().map(|r| json!({
+            "rps": r.rps,
+            "burst": r.burst,
+            "in_flight": r.in_flight,
+        })),
+        "idempotent": spec.idempotent,
+        "batch_atomic": spec.batch_atomic,
+        "websocket": spec.websocket.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_hides_secrets_and_dsn_passwords() {
+        let mut config = json!({
+            "database": {
+                "dsn": "postgres://app:hunter2@db:5432/app",
+                "password": "hunter2",
+                "pool": { "max_conns": 10 }
+            },
+            "config": {
+                "jwks_uri": "https://idp.example.com/jwks",
+                "client_secret": "s3cr3t",
+                "tokens": ["a", "b"],
+                "api_key": null
+            }
+        });
+        redact(&mut config);
+
+        assert_eq!(config["database"]["dsn"], "postgres://app:***@db:5432/app");
+        assert_eq!(config["database"]["password"], REDACTED);
+        assert_eq!(config["database"]["pool"]["max_conns"], 10);
+        assert_eq!(config["config"]["jwks_uri"], "https://idp.example.com/jwks");
+        assert_eq!(config["config"]["client_secret"], REDACTED);
+        assert_eq!(config["config"]["tokens"], REDACTED);
+        assert!(config["config"]["api_key"].is_null());
+    }
+
+    #[test]
+    fn test_redact_hides_tokens_in_url_queries() {
+        let mut config = json!({
+            "webhook": "https://hooks.example.com/notify?channel=ops&access_token=abc123",
+            "plain": "https://idp.example.com",
+            "broken": "not a url://user:pw@host",
+        });
+        redact(&mut config);
+
+        assert_eq!(
+            config["webhook"],
+            "https://hooks.example.com/notify?channel=ops&access_token=***"
+        );
+        assert_eq!(config["plain"], "https://idp.example.com");
+        assert_eq!(config["broken"], REDACTED);
+    }
+
+    #[test]
+    fn test_redact_hides_passwords_in_key_value_dsns() {
+        let mut config = json!({
+            "dsn": "host=db port=5432 user=app password=hunter2 dbname=app",
+            "quoted": r"host=db password='hunter 2 \' x' sslmode=require",
+            "spaced": "password = hunter2",
+            "prose": "a=b is not a secret",
+        });
+        redact(&mut config);
+
+        assert_eq!(
+            config["dsn"],
+            "host=db port=5432 user=app password=*** dbname=app"
+        );
+        assert_eq!(config["quoted"], "host=db password=*** sslmode=require");
+        assert_eq!(config["spaced"], "password = ***");
+        assert_eq!(config["prose"], "a=b is not a secret");
+    }
+}
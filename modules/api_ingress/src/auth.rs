@@ -41,7 +41,7 @@ impl RouteMatcher {
     }
 }
 
-/// Public route matcher for explicitly public routes
+/// Path matcher for explicitly public routes, and for routes that need authentication only
 #[derive(Clone)]
 pub(crate) struct PublicRouteMatcher {
     matcher: matchit::Router<()>,
@@ -105,6 +105,8 @@ pub struct AuthState {
 pub struct IngressRoutePolicy {
     route_matchers: Arc<HashMap<Method, RouteMatcher>>,
     public_matchers: Arc<HashMap<Method, PublicRouteMatcher>>,
+    // Routes that need a valid token whatever `require_auth_by_default` says
+    authenticated_matchers: Arc<HashMap<Method, PublicRouteMatcher>>,
     require_auth_by_default: bool,
 }
 
@@ -117,9 +119,19 @@ impl IngressRoutePolicy {
         Self {
             route_matchers,
             public_matchers,
+            authenticated_matchers: Arc::default(),
             require_auth_by_default,
         }
     }
+
+    /// Require authentication, without a role, on these routes.
+    pub(crate) fn with_authenticated(
+        mut self,
+        authenticated_matchers: Arc<HashMap<Method, PublicRouteMatcher>>,
+    ) -> Self {
+        self.authenticated_matchers = authenticated_matchers;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -139,8 +151,16 @@ impl modkit_auth::RoutePolicy for IngressRoutePolicy {
             .map(|matcher| matcher.find(path))
             .unwrap_or(false);
 
+        let is_authenticated = self
+            .authenticated_matchers
+            .get(method)
+            .map(|matcher| matcher.find(path))
+            .unwrap_or(false);
+
         // Public routes should not be forced to auth by default
-        let needs_authn = requirement.is_some() || (self.require_auth_by_default && !is_public);
+        let needs_authn = requirement.is_some()
+            || is_authenticated
+            || (self.require_auth_by_default && !is_public);
 
         if !needs_authn {
             AuthRequirement::None
@@ -173,6 +193,7 @@ pub fn build_auth_state(
     cfg: &crate::config::ApiIngressConfig,
     requirements: HashMap<(Method, String), Requirement>,
     public_routes: std::collections::HashSet<(Method, String)>,
+    authenticated_routes: std::collections::HashSet<(Method, String)>,
 ) -> Result<(AuthState, IngressRoutePolicy), anyhow::Error> {
     // Build validator (TokenValidator trait implementation)
     let validator: Arc<dyn TokenValidator> = if cfg.auth_disabled {
@@ -244,19 +265,9 @@ pub fn build_auth_state(
             .map_err(|e| anyhow::anyhow!("Failed to insert route pattern '{}': {}", path, e))?;
     }
 
-    // Build public matchers per HTTP method
-    let mut public_matchers_map: HashMap<Method, PublicRouteMatcher> = HashMap::new();
-
-    for (method, path) in public_routes {
-        let matcher = public_matchers_map
-            .entry(method)
-            .or_insert_with(PublicRouteMatcher::new);
-        // Convert Axum path syntax (:param) to matchit syntax ({param})
-        let matchit_path = convert_axum_path_to_matchit(&path);
-        matcher.insert(&matchit_path).map_err(|e| {
-            anyhow::anyhow!("Failed to insert public route pattern '{}': {}", path, e)
-        })?;
-    }
+    // Build public and authentication-only matchers per HTTP method
+    let public_matchers_map = build_path_matchers(public_routes, "public")?;
+    let authenticated_matchers_map = build_path_matchers(authenticated_routes, "authenticated")?;
 
     let auth_state = AuthState {
         validator,
@@ -268,11 +279,32 @@ pub fn build_auth_state(
         Arc::new(route_matchers_map),
         Arc::new(public_matchers_map),
         cfg.require_auth_by_default,
-    );
+    )
+    .with_authenticated(Arc::new(authenticated_matchers_map));
 
     Ok((auth_state, route_policy))
 }
 
+fn build_path_matchers(
+    routes: std::collections::HashSet<(Method, String)>,
+    kind: &str,
+) -> Result<HashMap<Method, PublicRouteMatcher>, anyhow::Error> {
+    let mut matchers: HashMap<Method, PublicRouteMatcher> = HashMap::new();
+
+    for (method, path) in routes {
+        let matcher = matchers
+            .entry(method)
+            .or_insert_with(PublicRouteMatcher::new);
+        // Convert Axum path syntax (:param) to matchit syntax ({param})
+        let matchit_path = convert_axum_path_to_matchit(&path);
+        matcher.insert(&matchit_path).map_err(|e| {
+            anyhow::anyhow!("Failed to insert {} route pattern '{}': {}", kind, path, e)
+        })?;
+    }
+
+    Ok(matchers)
+}
+
 /// No-op validator for auth_disabled mode (should never be called)
 struct NoopValidator;
 
@@ -384,6 +416,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_authenticated_route_ignores_require_auth_by_default() {
+        let mut authenticated_matchers = HashMap::new();
+        let mut matcher = PublicRouteMatcher::new();
+        matcher.insert("/metrics").unwrap();
+        authenticated_matchers.insert(Method::GET, matcher);
+
+        let policy = build_test_policy(HashMap::new(), HashMap::new(), false)
+            .with_authenticated(Arc::new(authenticated_matchers));
+
+        let result = policy.resolve(&Method::GET, "/metrics").await;
+        assert_eq!(result, AuthRequirement::Required(None));
+        let result = policy.resolve(&Method::GET, "/other").await;
+        assert_eq!(result, AuthRequirement::None);
+    }
+
     #[tokio::test]
     async fn route_without_requirement_with_require_auth_by_default_returns_required_none() {
         let policy = build_test_policy(HashMap::new(), HashMap::new(), true);
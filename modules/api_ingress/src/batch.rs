@@ -0,0 +1,617 @@
+//! `$batch` endpoint: several sub-requests in one call
+//!
+//! The body is a JSON array of `{id?, method, path, headers?, body?, atomicity_group?}` items.
+//! Each item is dispatched, in order, through the cached router with the whole middleware
+//! stack, so auth, rate limits and validation apply per item with the caller's own headers
+//! (minus the ones describing the batch body). The response is a JSON array of
+//! `{id, status, headers, body}` in the same order.
+//!
+//! Streaming (SSE, octet-stream) and WebSocket operations cannot be batched, and the response
+//! bodies of all items together are buffered up to `max_response_bytes`; an item whose body
+//! does not fit is answered with `502` instead.
+//!
+//! Items sharing an `atomicity_group` run on one [`BatchTransaction`] per database. Only
+//! operations marked `.atomic_in_batch()` are accepted in a group. Once its last item ran, the
+//! group commits when every item succeeded; otherwise it rolls back and its successful items
+//! are reported as `424 Failed Dependency`.
+//!
+//! A group's items must be consecutive. Its transaction holds a pool connection until the
+//! group settles, so an item from outside the group running in between could wait for that
+//! connection forever (always, on a one-connection SQLite pool); such batches are rejected.
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, Weak};
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{ConnectInfo, Request};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use modkit::api::{OperationSpec, Problem, APPLICATION_PROBLEM_JSON};
+use modkit_db::batch::BatchTransaction;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::config::BatchConfig;
+use crate::errors::ErrorCode;
+use crate::router_cache::RouterCache;
+
+/// Headers of the batch request that describe its own body or identity, not the items'.
+const NOT_INHERITED: [HeaderName; 7] = [
+    header::CONTENT_TYPE,
+    header::CONTENT_LENGTH,
+    header::CONTENT_ENCODING,
+    header::TRANSFER_ENCODING,
+    header::ACCEPT_ENCODING,
+    HeaderName::from_static("idempotency-key"),
+    HeaderName::from_static("x-request-id"),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchItem {
+    #[serde(default)]
+    id: Option<String>,
+    method: String,
+    path: String,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: Option<Value>,
+    #[serde(default)]
+    atomicity_group: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchItemResponse {
+    id: Option<String>,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    body: Value,
+}
+
+impl BatchItemResponse {
+    fn problem(id: Option<String>, problem: &Problem) -> Self {
+        Self {
+            id,
+            status: problem.status.as_u16(),
+            headers: BTreeMap::from([(
+                header::CONTENT_TYPE.as_str().to_string(),
+                APPLICATION_PROBLEM_JSON.to_string(),
+            )]),
+            body: serde_json::to_value(problem).unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// Handler state: the router sub-requests are dispatched through and the batch limits.
+#[derive(Clone)]
+pub struct BatchState {
+    // Weak: the cached router contains this handler
+    router: Weak<RouterCache<Router>>,
+    config: BatchConfig,
+    /// Paths of the operations allowed in atomic groups, per method
+    atomic: Arc<HashMap<Method, matchit::Router<()>>>,
+    /// Paths of the streaming and WebSocket operations, per method
+    unbatchable: Arc<HashMap<Method, matchit::Router<()>>>,
+}
+
+fn paths_by_method<'a>(
+    specs: impl Iterator<Item = &'a OperationSpec>,
+) -> HashMap<Method, matchit::Router<()>> {
+    let mut paths: HashMap<Method, matchit::Router<()>> = HashMap::new();
+    for spec in specs {
+        let _ = paths
+            .entry(spec.method.clone())
+            .or_default()
+            .insert(spec.path.clone(), ());
+    }
+    paths
+}
+
+fn matches(paths: &HashMap<Method, matchit::Router<()>>, method: &Method, path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or(path);
+    paths.get(method).is_some_and(|m| m.at(path).is_ok())
+}
+
+impl BatchState {
+    pub fn new(
+        router: &Arc<RouterCache<Router>>,
+        config: BatchConfig,
+        specs: &[OperationSpec],
+    ) -> Self {
+        let atomic = paths_by_method(specs.iter().filter(|s| s.batch_atomic));
+        let unbatchable = paths_by_method(
+            specs
+                .iter()
+                .filter(|s| s.websocket.is_some() || crate::middleware::timeout::is_streaming(s)),
+        );
+        Self {
+            router: Arc::downgrade(router),
+            config,
+            atomic: Arc::new(atomic),
+            unbatchable: Arc::new(unbatchable),
+        }
+    }
+
+    fn allows_atomic(&self, method: &Method, path: &str) -> bool {
+        matches(&self.atomic, method, path)
+    }
+
+    /// Streams and WebSockets never end on their own, so their responses cannot be buffered.
+    fn allows_batching(&self, method: &Method, path: &str) -> bool {
+        !matches(&self.unbatchable, method, path)
+    }
+}
+
+fn invalid(detail: impl Into<String>) -> Response {
+    ErrorCode::api_ingress_errors_invalid_batch_v1()
+        .to_problem(detail)
+        .into_response()
+}
+
+pub async fn handle_batch(state: BatchState, req: Request) -> Response {
+    let Some(cache) = state.router.upgrade() else {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+    let (parts, body) = req.into_parts();
+    let body = match to_bytes(body, state.config.max_body_bytes).await {
+        Ok(body) => body,
+        Err(_) => {
+            return ErrorCode::api_ingress_errors_batch_too_large_v1()
+                .to_problem(format!(
+                    "Batch body exceeds {} bytes",
+                    state.config.max_body_bytes
+                ))
+                .into_response()
+        }
+    };
+    let items: Vec<BatchItem> = match serde_json::from_slice(&body) {
+        Ok(items) => items,
+        Err(e) => return invalid(format!("Batch body is not an array of requests: {e}")),
+    };
+    if items.len() > state.config.max_requests {
+        return ErrorCode::api_ingress_errors_batch_too_large_v1()
+            .to_problem(format!(
+                "Batch has {} requests; at most {} are accepted",
+                items.len(),
+                state.config.max_requests
+            ))
+            .into_response();
+    }
+
+    // Validate the whole batch before running any item
+    let mut methods = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        let Ok(method) = Method::from_bytes(item.method.to_ascii_uppercase().as_bytes()) else {
+            return invalid(format!("Item {i}: invalid method '{}'", item.method));
+        };
+        if !item.path.starts_with('/') {
+            return invalid(format!("Item {i}: path must start with '/'"));
+        }
+        if item.path.split('?').next() == Some(state.config.path.as_str()) {
+            return invalid(format!("Item {i}: batches cannot be nested"));
+        }
+        if !state.allows_batching(&method, &item.path) {
+            return invalid(format!(
+                "Item {i}: {} {} is a streaming or WebSocket operation and cannot be batched",
+                method, item.path
+            ));
+        }
+        if item.atomicity_group.is_some() && !state.allows_atomic(&method, &item.path) {
+            return invalid(format!(
+                "Item {i}: {} {} cannot run in an atomicity group",
+                method, item.path
+            ));
+        }
+        methods.push(method);
+    }
+
+    // Index of each group's last item: the group is settled right after it
+    let mut group_ends: HashMap<&str, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        if let Some(group) = &item.atomicity_group {
+            group_ends.insert(group, i);
+        }
+    }
+    // No other item may run while a group's transaction is open
+    let mut open: Option<&str> = None;
+    for (i, item) in items.iter().enumerate() {
+        let group = item.atomicity_group.as_deref();
+        if let Some(current) = open {
+            if group != Some(current) {
+                return invalid(format!(
+                    "Item {i}: the items of atomicity group '{current}' must be consecutive"
+                ));
+            }
+        }
+        open = group.filter(|g| group_ends.get(g) != Some(&i));
+    }
+
+    let inherited = inherited_headers(&parts.headers);
+    let request_id = parts
+        .headers
+        .get(crate::middleware::request_id::header())
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let connect_info = parts.extensions.get::<ConnectInfo<SocketAddr>>().cloned();
+    let router = cache.load();
+    drop(cache);
+
+    let mut groups: HashMap<String, BatchTransaction> = HashMap::new();
+    let mut response_budget = state.config.max_response_bytes;
+    let mut responses: Vec<BatchItemResponse> = Vec::with_capacity(items.len());
+    for (i, (item, method)) in items.iter().zip(methods).enumerate() {
+        let resp = match sub_request(item, method, &inherited) {
+            Ok(mut sub) => {
+                if let Some(rid) = &request_id {
+                    if let Ok(v) = HeaderValue::from_str(&format!("{rid}.{i}")) {
+                        sub.headers_mut()
+                            .insert(crate::middleware::request_id::header(), v);
+                    }
+                }
+                if let Some(ci) = &connect_info {
+                    sub.extensions_mut().insert(*ci);
+                }
+                if let Some(group) = &item.atomicity_group {
+                    let txn = groups.entry(group.clone()).or_default().clone();
+                    sub.extensions_mut().insert(txn);
+                }
+                match (*router).clone().oneshot(sub).await {
+                    Ok(resp) => resp,
+                    Err(never) => match never {},
+                }
+            }
+            // A malformed item fails on its own, like a request the router rejects
+            Err(detail) => invalid(detail),
+        };
+        responses.push(into_item(item.id.clone(), resp, &mut response_budget).await);
+
+        if let Some(group) = &item.atomicity_group {
+            if group_ends.get(group.as_str()) == Some(&i) {
+                let txn = groups.remove(group).unwrap_or_default();
+                settle_group(group, txn, &items, &mut responses).await;
+            }
+        }
+    }
+
+    Json(responses).into_response()
+}
+
+/// The item as a request: inherited headers of the batch request, overridden by its own.
+fn sub_request(item: &BatchItem, method: Method, inherited: &HeaderMap) -> Result<Request, String> {
+    let mut headers = inherited.clone();
+    for (name, value) in &item.headers {
+        let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) else {
+            return Err(format!("Invalid header '{name}'"));
+        };
+        headers.insert(name, value);
+    }
+    let body = match &item.body {
+        Some(body) => {
+            headers
+                .entry(header::CONTENT_TYPE)
+                .or_insert(HeaderValue::from_static("application/json"));
+            match body {
+                // Non-JSON payloads (e.g. text/plain) travel as strings
+                Value::String(s) if !is_json(&headers) => Body::from(s.clone()),
+                other => Body::from(other.to_string()),
+            }
+        }
+        None => Body::empty(),
+    };
+
+    let mut req = Request::builder()
+        .method(method)
+        .uri(item.path.as_str())
+        .body(body)
+        .map_err(|e| format!("Invalid request '{}': {e}", item.path))?;
+    *req.headers_mut() = headers;
+    Ok(req)
+}
+
+fn inherited_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut inherited = headers.clone();
+    for name in &NOT_INHERITED {
+        inherited.remove(name);
+    }
+    inherited
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|ct| ct.split(';').next().unwrap_or("").trim())
+        .is_some_and(|ct| ct.eq_ignore_ascii_case("application/json") || ct.ends_with("+json"))
+}
+
+/// Buffer the item's response, drawing its body size from the batch-wide `budget`.
+async fn into_item(id: Option<String>, resp: Response, budget: &mut usize) -> BatchItemResponse {
+    let (parts, body) = resp.into_parts();
+    let bytes = match to_bytes(body, *budget).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            let problem = ErrorCode::api_ingress_errors_batch_response_too_large_v1().to_problem(
+                "The response does not fit into what is left of the batch response limit",
+            );
+            return BatchItemResponse::problem(id, &problem);
+        }
+    };
+    *budget -= bytes.len();
+
+    let mut headers: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in &parts.headers {
+        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+        headers
+            .entry(name.as_str().to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    let body = if bytes.is_empty() {
+        Value::Null
+    } else if is_json(&parts.headers) {
+        serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+    } else {
+        Value::String(String::from_utf8_lossy(&bytes).into_owned())
+    };
+    BatchItemResponse {
+        id,
+        status: parts.status.as_u16(),
+        headers,
+        body,
+    }
+}
+
+/// Commit the group if all its items succeeded; otherwise roll it back and mark its
+/// successful items as failed dependencies.
+async fn settle_group(
+    group: &str,
+    txn: BatchTransaction,
+    items: &[BatchItem],
+    responses: &mut [BatchItemResponse],
+) {
+    let members: Vec<usize> = (0..responses.len())
+        .filter(|&i| items[i].atomicity_group.as_deref() == Some(group))
+        .collect();
+    let succeeded = members
+        .iter()
+        .all(|&i| StatusCode::from_u16(responses[i].status).is_ok_and(|s| s.is_success()));
+
+    let detail = if succeeded {
+        match txn.commit().await {
+            Ok(()) => return,
+            Err(e) => {
+                tracing::warn!(group = %group, error = %e, "Batch group commit failed");
+                format!("Atomicity group '{group}' could not be committed")
+            }
+        }
+    } else {
+        if let Err(e) = txn.rollback().await {
+            tracing::warn!(group = %group, error = %e, "Batch group rollback failed");
+        }
+        format!("Rolled back: another request of atomicity group '{group}' failed")
+    };
+
+    let problem = ErrorCode::api_ingress_errors_batch_rolled_back_v1().to_problem(detail);
+    for i in members {
+        let failed = StatusCode::from_u16(responses[i].status).is_ok_and(|s| !s.is_success());
+        if failed {
+            // Items that caused the rollback keep their own response
+            continue;
+        }
+        responses[i] = BatchItemResponse::problem(responses[i].id.clone(), &problem);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use modkit::api::{OpenApiRegistryImpl, OperationBuilder};
+
+    #[derive(utoipa::ToSchema)]
+    #[allow(dead_code)]
+    struct Tick {
+        n: u32,
+    }
+
+    fn app(cache: &Arc<RouterCache<Router>>) -> Router {
+        let registry = OpenApiRegistryImpl::new();
+        let router = OperationBuilder::post("/notes")
+            .operation_id("test.create_note")
+            .public()
+            .atomic_in_batch()
+            .handler(|req: Request| async move {
+                // Echo whether the item runs in a group and which credentials it carries
+                let in_group = req.extensions().get::<BatchTransaction>().is_some();
+                let auth = req
+                    .headers()
+                    .get(header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("")
+                    .to_string();
+                let body = to_bytes(req.into_body(), usize::MAX).await.unwrap();
+                let note: Value = serde_json::from_slice(&body).unwrap();
+                if note["text"] == "fail" {
+                    return StatusCode::CONFLICT.into_response();
+                }
+                (
+                    StatusCode::CREATED,
+                    Json(serde_json::json!({ "in_group": in_group, "auth": auth })),
+                )
+                    .into_response()
+            })
+            .json_response(StatusCode::CREATED, "created")
+            .register(Router::new(), &registry);
+        let router = OperationBuilder::get("/events")
+            .operation_id("test.events")
+            .public()
+            .handler(|| async { "never dispatched" })
+            .sse_json::<Tick>(&registry, "ticks")
+            .register(router, &registry);
+        let router = router
+            .route("/ping", get(|| async { "pong" }))
+            .route("/big", get(|| async { "x".repeat(1000) }));
+
+        let specs: Vec<_> = registry
+            .operation_specs
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        let config = BatchConfig {
+            max_response_bytes: 1024,
+            ..BatchConfig::default()
+        };
+        let state = BatchState::new(cache, config, &specs);
+        let router = router.route(
+            "/$batch",
+            post(move |req: Request| handle_batch(state.clone(), req)),
+        );
+        cache.store(router.clone());
+        router
+    }
+
+    async fn batch(body: Value) -> (StatusCode, Value) {
+        let cache = Arc::new(RouterCache::new(Router::new()));
+        let resp = app(&cache)
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/$batch")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .header(header::AUTHORIZATION, "Bearer caller")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_items_run_with_caller_credentials() {
+        let (status, items) = batch(serde_json::json!([
+            { "id": "a", "method": "post", "path": "/notes", "body": { "text": "hi" } },
+            { "id": "b", "method": "GET", "path": "/ping" },
+            { "id": "c", "method": "GET", "path": "/missing" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(items[0]["status"], 201);
+        assert_eq!(items[0]["body"]["auth"], "Bearer caller");
+        assert_eq!(items[0]["body"]["in_group"], false);
+        assert_eq!(items[1]["body"], "pong");
+        assert_eq!(items[2]["id"], "c");
+        assert_eq!(items[2]["status"], 404);
+
+        let (status, _) =
+            batch(serde_json::json!([{ "method": "POST", "path": "/$batch", "body": [] }])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let too_many: Vec<Value> = (0..51)
+            .map(|_| serde_json::json!({ "method": "GET", "path": "/ping" }))
+            .collect();
+        assert_eq!(
+            batch(Value::Array(too_many)).await.0,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_group_marks_its_successes_as_rolled_back() {
+        let (status, _) = batch(serde_json::json!([
+            { "method": "GET", "path": "/ping", "atomicity_group": "g" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "operation did not opt in");
+
+        let (_, items) = batch(serde_json::json!([
+            { "method": "POST", "path": "/notes", "body": { "text": "a" }, "atomicity_group": "g" },
+            { "method": "POST", "path": "/notes", "body": { "text": "fail" }, "atomicity_group": "g" },
+            { "method": "POST", "path": "/notes", "body": { "text": "b" } },
+        ]))
+        .await;
+        assert_eq!(items[0]["status"], 424);
+        assert_eq!(
+            items[0]["body"]["code"],
+            "gts.hx.core.errors.err.v1~hx.api_ingress.errors.batch_rolled_back.v1"
+        );
+        assert_eq!(items[1]["status"], 409);
+        assert_eq!(items[2]["status"], 201);
+    }
+
+    #[tokio::test]
+    async fn test_interleaved_groups_are_rejected_before_any_item_runs() {
+        let (status, problem) = batch(serde_json::json!([
+            { "method": "POST", "path": "/notes", "body": { "text": "a" }, "atomicity_group": "g" },
+            { "method": "POST", "path": "/notes", "body": { "text": "b" } },
+            { "method": "POST", "path": "/notes", "body": { "text": "c" }, "atomicity_group": "g" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            problem["detail"],
+            "Item 1: the items of atomicity group 'g' must be consecutive"
+        );
+
+        let (status, _) = batch(serde_json::json!([
+            { "method": "POST", "path": "/notes", "body": { "text": "a" }, "atomicity_group": "g" },
+            { "method": "POST", "path": "/notes", "body": { "text": "b" }, "atomicity_group": "h" },
+            { "method": "POST", "path": "/notes", "body": { "text": "c" }, "atomicity_group": "g" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "another group in between");
+
+        let (status, items) = batch(serde_json::json!([
+            { "method": "POST", "path": "/notes", "body": { "text": "a" }, "atomicity_group": "g" },
+            { "method": "POST", "path": "/notes", "body": { "text": "b" }, "atomicity_group": "g" },
+            { "method": "POST", "path": "/notes", "body": { "text": "c" }, "atomicity_group": "h" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(items[0]["body"]["in_group"], true);
+        assert_eq!(items[2]["body"]["in_group"], true);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_operations_are_rejected_before_any_item_runs() {
+        let (status, problem) = batch(serde_json::json!([
+            { "method": "POST", "path": "/notes", "body": { "text": "hi" } },
+            { "method": "GET", "path": "/events" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(problem["detail"]
+            .as_str()
+            .unwrap()
+            .starts_with("Item 1: GET /events is a streaming"));
+    }
+
+    #[tokio::test]
+    async fn test_responses_past_the_limit_fail_on_their_own() {
+        let (status, items) = batch(serde_json::json!([
+            { "id": "a", "method": "GET", "path": "/big" },
+            { "id": "b", "method": "GET", "path": "/big" },
+            { "id": "c", "method": "GET", "path": "/ping" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(items[0]["status"], 200);
+        assert_eq!(items[0]["body"].as_str().unwrap().len(), 1000);
+        assert_eq!(items[1]["status"], 502);
+        assert_eq!(items[1]["id"], "b");
+        assert_eq!(items[2]["status"], 200);
+        assert_eq!(items[2]["body"], "pong");
+    }
+}
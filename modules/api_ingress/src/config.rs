@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use modkit::api::RateLimitPartition;
 use serde::{Deserialize, Serialize};
 
 fn default_require_auth_by_default() -> bool {
@@ -8,6 +11,10 @@ fn default_body_limit_bytes() -> usize {
     16 * 1024 * 1024
 }
 
+fn default_request_timeout_ms() -> u64 {
+    30_000
+}
+
 /// API ingress configuration - reused from api_ingress module
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 #[serde(deny_unknown_fields)]
@@ -52,6 +59,358 @@ pub struct ApiIngressConfig {
     /// If true, routes without explicit role still require authentication (AuthN-only).
     #[serde(default = "default_require_auth_by_default")]
     pub require_auth_by_default: bool,
+
+    /// Graceful shutdown (connection draining) settings
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+
+    /// Serve HTTPS on `bind_addr` instead of plain HTTP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+
+    /// Rate-limit partitioning, client identification and shared state
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Response compression and request decompression
+    #[serde(default)]
+    pub compression: CompressionConfig,
+
+    /// `Idempotency-Key` handling for operations marked idempotent
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+
+    /// Prometheus scrape endpoint and per-route HTTP metrics
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+
+    /// Per-request access log lines
+    #[serde(default)]
+    pub access_log: AccessLogConfig,
+
+    /// Keepalive and limits of WebSocket operations
+    #[serde(default)]
+    pub websocket: WebSocketConfig,
+
+    /// JSON Schema validation of request bodies
+    #[serde(default)]
+    pub request_validation: RequestValidationConfig,
+
+    /// `$batch` endpoint running several sub-requests in one call
+    #[serde(default)]
+    pub batch: BatchConfig,
+
+    /// Read-only runtime introspection endpoints
+    #[serde(default)]
+    pub admin: AdminConfig,
+}
+
+/// Admin API exposing modules, instances, redacted configuration, routes and gRPC services.
+/// Callers need `role` (`resource:action`), even where other routes are public.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct AdminConfig {
+    pub enabled: bool,
+    /// Prefix of the admin endpoints
+    pub path: String,
+    /// Serve the admin endpoints on this address only, instead of on `bind_addr`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_addr: Option<String>,
+    pub role: String,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "/admin".to_string(),
+            bind_addr: None,
+            role: "runtime:admin".to_string(),
+        }
+    }
+}
+
+impl AdminConfig {
+    /// Whether the endpoints are served on a loopback address only.
+    pub fn is_loopback_only(&self) -> bool {
+        let Some(addr) = &self.bind_addr else {
+            return false;
+        };
+        match addr.parse::<std::net::SocketAddr>() {
+            Ok(addr) => addr.ip().is_loopback(),
+            Err(_) => addr
+                .rsplit_once(':')
+                .is_some_and(|(host, _)| host.eq_ignore_ascii_case("localhost")),
+        }
+    }
+
+    /// `role` split into the (resource, action) pair routes are authorized with.
+    pub fn role_parts(&self) -> Option<(&str, &str)> {
+        self.role
+            .split_once(':')
+            .filter(|(resource, action)| !resource.is_empty() && !action.is_empty())
+    }
+}
+
+/// A JSON array of sub-requests posted to `path` is dispatched item by item through the full
+/// router, with the caller's credentials; the response lists each item's status and body.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct BatchConfig {
+    pub enabled: bool,
+    pub path: String,
+    /// Sub-requests accepted in one batch
+    pub max_requests: usize,
+    /// Size of the whole batch document
+    pub max_body_bytes: usize,
+    /// Response bodies of all items together; items past it get a 502 of their own
+    pub max_response_bytes: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "/$batch".to_string(),
+            max_requests: 50,
+            max_body_bytes: 1024 * 1024,
+            max_response_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+/// Validate JSON request bodies against the schema registered with `json_request::<T>()`
+/// before the handler runs; failures are answered with 422 and the list of violations.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RequestValidationConfig {
+    pub enabled: bool,
+    /// Violations listed in one response at most
+    pub max_errors: usize,
+}
+
+impl Default for RequestValidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_errors: 20,
+        }
+    }
+}
+
+/// Connections are pinged every `ping_interval_secs` and closed after `idle_timeout_secs`
+/// without a frame from the client.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct WebSocketConfig {
+    pub ping_interval_secs: u64,
+    pub idle_timeout_secs: u64,
+    /// Largest message accepted from clients
+    pub max_message_bytes: usize,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval_secs: 30,
+            idle_timeout_secs: 300,
+            max_message_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl WebSocketConfig {
+    pub fn settings(&self) -> modkit::http::ws::WsSettings {
+        modkit::http::ws::WsSettings {
+            ping_interval: std::time::Duration::from_secs(self.ping_interval_secs.max(1)),
+            idle_timeout: std::time::Duration::from_secs(self.idle_timeout_secs),
+            max_message_bytes: self.max_message_bytes,
+        }
+    }
+}
+
+/// Line format of the access log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogFormat {
+    /// One JSON object per line
+    #[default]
+    Json,
+    /// NCSA Common Log Format followed by `key=value` fields
+    Common,
+    /// Combined Log Format (Common plus referer and user agent) followed by `key=value` fields
+    Combined,
+}
+
+/// One line per request on the `access_log` tracing target. Give it a dedicated rotated file
+/// with an `access_log` section under `logging`; without one the lines are dropped.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct AccessLogConfig {
+    pub enabled: bool,
+    pub format: AccessLogFormat,
+    /// Fraction of requests logged (0.0 to 1.0), decided per request id; 5xx responses are
+    /// always logged
+    pub sample_rate: f64,
+    /// Request paths that are never logged (exact match)
+    pub exclude_paths: Vec<String>,
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: AccessLogFormat::Json,
+            sample_rate: 1.0,
+            exclude_paths: vec![
+                "/health".to_string(),
+                "/healthz".to_string(),
+                "/readyz".to_string(),
+                "/metrics".to_string(),
+            ],
+        }
+    }
+}
+
+/// Prometheus text exposition of the runtime metrics registry.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct MetricsConfig {
+    /// Record per-route metrics and serve `path`
+    pub enabled: bool,
+    pub path: String,
+    /// Serve `path` without authentication; otherwise scrapers need a valid token
+    pub public: bool,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "/metrics".to_string(),
+            public: false,
+        }
+    }
+}
+
+/// Replay of responses to retried requests carrying an `Idempotency-Key`.
+/// Keys are stored in the module database, scoped to the caller's tenant and subject.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct IdempotencyConfig {
+    /// How long a completed response is replayed for its key
+    pub ttl_secs: u64,
+    /// How long a running request holds its key; after that a retry may take it over
+    pub lock_timeout_secs: u64,
+    /// Larger responses are not captured; the key is released so a retry runs again
+    pub max_response_bytes: usize,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: 24 * 60 * 60,
+            lock_timeout_secs: 60,
+            max_response_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Content codings the ingress can negotiate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Br,
+    Zstd,
+}
+
+/// Negotiated response compression (`Accept-Encoding`) and request decompression
+/// (`Content-Encoding`). `text/event-stream` is never compressed.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct CompressionConfig {
+    /// Compress responses when the client accepts one of `algorithms`
+    pub enabled: bool,
+    pub algorithms: Vec<CompressionAlgorithm>,
+    /// Responses smaller than this are sent as-is
+    pub min_size_bytes: u16,
+    /// Compressible media types; an entry ending in `/` matches the whole type, e.g. `text/`
+    pub content_types: Vec<String>,
+    /// Accept compressed request bodies; `defaults.body_limit_bytes` applies to the
+    /// decompressed size
+    pub decompress_requests: bool,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            algorithms: vec![
+                CompressionAlgorithm::Gzip,
+                CompressionAlgorithm::Br,
+                CompressionAlgorithm::Zstd,
+            ],
+            min_size_bytes: 1024,
+            content_types: vec![
+                "application/json".to_string(),
+                "application/problem+json".to_string(),
+                "application/xml".to_string(),
+                "application/javascript".to_string(),
+                "text/".to_string(),
+            ],
+            decompress_requests: true,
+        }
+    }
+}
+
+fn default_tls_reload_interval_ms() -> u64 {
+    5_000
+}
+
+/// TLS termination settings. Files are PEM encoded and re-read when they change.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Certificate chain, leaf first
+    pub cert_path: String,
+    /// Private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_path: String,
+    /// When set, clients must present a certificate signed by one of these CAs (mTLS)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_ca_path: Option<String>,
+    /// How often the files are checked for changes
+    #[serde(default = "default_tls_reload_interval_ms")]
+    pub reload_interval_ms: u64,
+    /// Optional plain HTTP listener that redirects every request to HTTPS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_http_addr: Option<String>,
+}
+
+/// Graceful shutdown sequence: mark not-ready, wait `pre_stop_delay_ms` so load balancers
+/// observe it, stop accepting connections, wait up to `drain_timeout_ms` for in-flight
+/// requests, then force-close.
+///
+/// `pre_stop_delay_ms + drain_timeout_ms` must stay below the module stop timeout (30s).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct ShutdownConfig {
+    /// Delay between reporting not-ready on `/readyz` and closing the listener
+    pub pre_stop_delay_ms: u64,
+    /// Upper bound for in-flight requests to complete after the listener is closed
+    pub drain_timeout_ms: u64,
+    /// `retry:` hint sent to SSE clients in the final shutdown event
+    pub sse_retry_ms: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            pre_stop_delay_ms: 5_000,
+            drain_timeout_ms: 20_000,
+            sse_retry_ms: 1_000,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -61,6 +420,8 @@ pub struct Defaults {
     pub rate_limit: RateLimitDefaults,
     /// Global request body size limit in bytes
     pub body_limit_bytes: usize,
+    /// Request timeout for operations that do not declare one
+    pub request_timeout_ms: u64,
 }
 
 impl Default for Defaults {
@@ -68,6 +429,7 @@ impl Default for Defaults {
         Self {
             rate_limit: RateLimitDefaults::default(),
             body_limit_bytes: default_body_limit_bytes(),
+            request_timeout_ms: default_request_timeout_ms(),
         }
     }
 }
@@ -78,6 +440,8 @@ pub struct RateLimitDefaults {
     pub rps: u32,
     pub burst: u32,
     pub in_flight: u32,
+    /// Bucket key for routes that do not choose one (`route` = one bucket per route)
+    pub partition: RateLimitPartition,
 }
 
 impl Default for RateLimitDefaults {
@@ -86,6 +450,58 @@ impl Default for RateLimitDefaults {
             rps: 50,
             burst: 100,
             in_flight: 64,
+            partition: RateLimitPartition::Route,
+        }
+    }
+}
+
+/// Where partitioned token buckets are kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitBackend {
+    /// Per-process buckets
+    #[default]
+    Memory,
+    /// Fixed-window counters in the module database, shared by all replicas
+    Db,
+}
+
+/// Rate-limit steady-state override for one tenant.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantRateLimit {
+    pub rps: u32,
+    pub burst: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RateLimitConfig {
+    /// Per-route partition overrides keyed by `"METHOD /path"`, e.g. `"POST /users": tenant`
+    pub routes: HashMap<String, RateLimitPartition>,
+    /// Peers (IPs or CIDRs) whose `X-Forwarded-For` header is trusted for `client_ip`
+    pub trusted_proxies: Vec<String>,
+    /// Header carrying the API key for the `api_key` partition
+    pub api_key_header: String,
+    /// Upper bound of partitions tracked per route; the least recently used is evicted
+    pub max_partitions: usize,
+    /// Partitions idle for this long are dropped
+    pub idle_ttl_secs: u64,
+    /// Rate overrides per tenant id, applied to tenant-partitioned routes
+    pub tenant_overrides: HashMap<String, TenantRateLimit>,
+    pub backend: RateLimitBackend,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            routes: HashMap::new(),
+            trusted_proxies: Vec::new(),
+            api_key_header: "x-api-key".to_string(),
+            max_partitions: 10_000,
+            idle_ttl_secs: 600,
+            tenant_overrides: HashMap::new(),
+            backend: RateLimitBackend::Memory,
         }
     }
 }
@@ -0,0 +1,10 @@
+//! Generated, strongly-typed error catalog for api_ingress.
+//! Source of truth: gts/errors.json
+
+use modkit_errors_macro::declare_errors;
+
+declare_errors! {
+    path = "gts/errors.json",
+    namespace = "errors",
+    vis = "pub"
+}
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use std::sync::Arc;
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use dashmap::DashMap;
 
 use anyhow::Result;
@@ -10,36 +10,46 @@ use axum::{extract::DefaultBodyLimit, middleware::from_fn, routing::get, Router}
 use modkit::api::{OpenApiRegistry, OpenApiRegistryImpl};
 use modkit::lifecycle::ReadySignal;
 use parking_lot::Mutex;
+use std::future::Future;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 use tower_http::{
     cors::CorsLayer,
     limit::RequestBodyLimitLayer,
     request_id::{PropagateRequestIdLayer, SetRequestIdLayer},
-    timeout::TimeoutLayer,
 };
 use tracing::debug;
 
+mod admin;
 mod assets;
 mod auth;
+mod batch;
 
 mod config;
 mod cors;
 pub mod middleware;
 
 pub mod error;
+mod errors;
 mod router_cache;
+mod server;
+mod storage;
+mod tls;
 mod web;
 
-pub use config::{ApiIngressConfig, CorsConfig};
+pub use config::{
+    AdminConfig, ApiIngressConfig, BatchConfig, CompressionConfig, CorsConfig, IdempotencyConfig,
+    MetricsConfig, RateLimitBackend, RateLimitConfig, ShutdownConfig, TlsConfig,
+};
 use router_cache::RouterCache;
 
 /// Main API Ingress module — owns the HTTP server (rest_host) and collects
 /// typed operation specs to emit a single OpenAPI document.
 #[modkit::module(
 	name = "api_ingress",
-	capabilities = [rest_host, rest, stateful, system],
+	capabilities = [db, rest_host, rest, stateful, system],
 	lifecycle(entry = "serve", stop_timeout = "30s", await_ready)
 )]
 pub struct ApiIngress {
@@ -47,26 +57,51 @@ pub struct ApiIngress {
     config: ArcSwap<ApiIngressConfig>,
     // OpenAPI registry for operations and schemas
     openapi_registry: Arc<OpenApiRegistryImpl>,
-    // Built router cache for zero-lock hot path access
-    router_cache: RouterCache<axum::Router>,
+    // Built router cache for zero-lock hot path access; `$batch` dispatches through it
+    router_cache: Arc<RouterCache<axum::Router>>,
     // Store the finalized router from REST phase for serving
     final_router: Mutex<Option<axum::Router>>,
+    // Admin endpoints for their own listener when `admin.bind_addr` is set
+    admin_router: Mutex<Option<axum::Router>>,
+    // Runtime internals behind the admin API, wired in before init
+    admin_sources: ArcSwap<admin::AdminSources>,
 
     // Duplicate detection (per (method, path) and per handler id)
     registered_routes: DashMap<(Method, String), ()>,
     registered_handlers: DashMap<String, ()>,
+
+    // Shutdown drain state shared by the server loop, /readyz and SSE streams
+    drain: middleware::drain::DrainState,
+
+    // Shared rate-limit buckets when `rate_limit.backend = db`
+    rate_limit_store: ArcSwapOption<storage::RateLimitStore>,
+
+    // Stored responses for `Idempotency-Key` replays; requires the module database
+    idempotency_store: ArcSwapOption<storage::IdempotencyStore>,
+
+    // Registry served on `/metrics`: the runtime's once `init` ran, a private one before
+    metrics: ArcSwap<modkit::MetricsRegistry>,
+    http_metrics: middleware::metrics::HttpMetrics,
 }
 
 impl Default for ApiIngress {
     fn default() -> Self {
         let default_router = Router::new();
+        let (metrics, http_metrics) = Self::private_metrics();
         Self {
             config: ArcSwap::from_pointee(ApiIngressConfig::default()),
             openapi_registry: Arc::new(OpenApiRegistryImpl::new()),
-            router_cache: RouterCache::new(default_router),
+            router_cache: Arc::new(RouterCache::new(default_router)),
             final_router: Mutex::new(None),
+            admin_router: Mutex::new(None),
+            admin_sources: ArcSwap::from_pointee(admin::AdminSources::default()),
             registered_routes: DashMap::new(),
             registered_handlers: DashMap::new(),
+            drain: middleware::drain::DrainState::new(),
+            rate_limit_store: ArcSwapOption::from(None),
+            idempotency_store: ArcSwapOption::from(None),
+            metrics: ArcSwap::from(metrics),
+            http_metrics,
         }
     }
 }
@@ -75,16 +110,37 @@ impl ApiIngress {
     /// Create a new ApiIngress instance with the given configuration
     pub fn new(config: ApiIngressConfig) -> Self {
         let default_router = Router::new();
+        let (metrics, http_metrics) = Self::private_metrics();
         Self {
             config: ArcSwap::from_pointee(config),
             openapi_registry: Arc::new(OpenApiRegistryImpl::new()),
-            router_cache: RouterCache::new(default_router),
+            router_cache: Arc::new(RouterCache::new(default_router)),
             final_router: Mutex::new(None),
+            admin_router: Mutex::new(None),
+            admin_sources: ArcSwap::from_pointee(admin::AdminSources::default()),
             registered_routes: DashMap::new(),
             registered_handlers: DashMap::new(),
+            drain: middleware::drain::DrainState::new(),
+            rate_limit_store: ArcSwapOption::from(None),
+            idempotency_store: ArcSwapOption::from(None),
+            metrics: ArcSwap::from(metrics),
+            http_metrics,
         }
     }
 
+    /// HTTP metrics registered in a registry of their own, until `init` finds the runtime's.
+    fn private_metrics() -> (
+        Arc<modkit::MetricsRegistry>,
+        middleware::metrics::HttpMetrics,
+    ) {
+        let registry = Arc::new(modkit::MetricsRegistry::new());
+        let http_metrics = middleware::metrics::HttpMetrics::new();
+        http_metrics
+            .register(&registry)
+            .expect("fresh registry has no conflicting metrics");
+        (registry, http_metrics)
+    }
+
     /// Get the current configuration (cheap clone from ArcSwap)
     pub fn get_config(&self) -> ApiIngressConfig {
         (**self.config.load()).clone()
@@ -115,8 +171,24 @@ impl ApiIngress {
         // Always mark built-in health check routes as public
         public_routes.insert((Method::GET, "/health".to_string()));
         public_routes.insert((Method::GET, "/healthz".to_string()));
+        public_routes.insert((Method::GET, "/readyz".to_string()));
         public_routes.insert((Method::GET, "/docs".to_string()));
         public_routes.insert((Method::GET, "/openapi.json".to_string()));
+        public_routes.insert((Method::GET, "/asyncapi.json".to_string()));
+        public_routes.insert((Method::GET, "/docs/asyncapi".to_string()));
+        for version in self.openapi_registry.api_versions() {
+            public_routes.insert((Method::GET, format!("/openapi/{version}.json")));
+        }
+        let config = self.get_cached_config();
+        let mut authenticated_routes = std::collections::HashSet::new();
+        if config.metrics.enabled {
+            let route = (Method::GET, config.metrics.path.clone());
+            if config.metrics.public {
+                public_routes.insert(route);
+            } else {
+                authenticated_routes.insert(route);
+            }
+        }
 
         for spec in self.openapi_registry.operation_specs.iter() {
             let spec = spec.value();
@@ -137,11 +209,30 @@ impl ApiIngress {
             }
         }
 
-        let config = self.get_cached_config();
+        if config.admin.enabled {
+            let (resource, action) = config.admin.role_parts().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "admin.role must be 'resource:action', got '{}'",
+                    config.admin.role
+                )
+            })?;
+            let prefix = config.admin.path.trim_end_matches('/');
+            for endpoint in admin::ENDPOINTS {
+                req_map.insert(
+                    (Method::GET, format!("{prefix}{endpoint}")),
+                    auth::Requirement {
+                        resource: resource.to_string(),
+                        action: action.to_string(),
+                    },
+                );
+            }
+        }
+
         let requirements_count = req_map.len();
         let public_routes_count = public_routes.len();
 
-        let (auth_state, route_policy) = auth::build_auth_state(&config, req_map, public_routes)?;
+        let (auth_state, route_policy) =
+            auth::build_auth_state(&config, req_map, public_routes, authenticated_routes)?;
 
         tracing::info!(
             auth_disabled = config.auth_disabled,
@@ -154,13 +245,13 @@ impl ApiIngress {
         Ok((auth_state, route_policy))
     }
 
-    /// Apply all middleware layers to a router (request ID, tracing, timeout, body limit, CORS, rate limiting, error mapping, auth)
+    /// Apply all middleware layers to a router (request ID, tracing, timeout, body limit, compression, CORS, rate limiting, error mapping, auth)
     fn apply_middleware_stack(&self, mut router: Router) -> Result<Router> {
         // Build auth state and route policy once
         let (auth_state, route_policy) = self.build_auth_state_from_specs()?;
 
         // Correct middleware order (outermost to innermost):
-        // RequestId(Propagate -> Set) -> Trace -> push_req_id_to_extensions -> Timeout -> BodyLimit -> CORS -> RateLimit -> ErrorMapping -> Auth -> Router
+        // RequestId(Propagate -> Set) -> Trace -> push_req_id_to_extensions -> Timeout -> BodyLimit -> Decompression -> Compression -> CORS -> Idempotency -> RateLimit -> ErrorMapping -> Auth -> Router
         // Note: CORS must short-circuit OPTIONS before Auth/limits; tower-http does this when the layer is present above them.
         let x_request_id = crate::middleware::request_id::header();
 
@@ -233,17 +324,41 @@ impl ApiIngress {
         // 4. Record request_id into span + extensions (span must exist first)
         router = router.layer(from_fn(middleware::request_id::push_req_id_to_extensions));
 
-        // 5. Timeout layer - 30 second timeout for handlers
-        router = router.layer(TimeoutLayer::with_status_code(
-            axum::http::StatusCode::GATEWAY_TIMEOUT,
-            Duration::from_secs(30),
+        // 5. Per-operation timeouts (problem+json 504); streaming operations are exempt
+        let config = self.get_cached_config();
+        let specs: Vec<_> = self
+            .openapi_registry
+            .operation_specs
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        let default_timeout = Duration::from_millis(config.defaults.request_timeout_ms);
+        let timeout_map = middleware::timeout::build_timeout_map(&specs, default_timeout);
+        router = router.layer(from_fn(
+            move |req: axum::extract::Request, next: axum::middleware::Next| {
+                let map = timeout_map.clone();
+                middleware::timeout::timeout_middleware(map, default_timeout, req, next)
+            },
         ));
 
         // 6. Body limit layer - from config default
-        let config = self.get_cached_config();
         router = router.layer(RequestBodyLimitLayer::new(config.defaults.body_limit_bytes));
         router = router.layer(DefaultBodyLimit::max(config.defaults.body_limit_bytes));
 
+        // 6a. Request decompression wraps the body limit, so the limit counts decoded bytes
+        if config.compression.decompress_requests {
+            router = router.layer(middleware::compression::decompression_layer(
+                &config.compression,
+            ));
+        }
+
+        // 6b. Negotiated response compression (allow-listed types, never SSE)
+        if config.compression.enabled {
+            router = router.layer(middleware::compression::compression_layer(
+                &config.compression,
+            ));
+        }
+
         // 7. CORS layer (if enabled). Place after BodyLimit so preflight returns early.
         if config.cors_enabled {
             if let Some(layer) = crate::cors::build_cors_layer(&config) {
@@ -253,13 +368,26 @@ impl ApiIngress {
             }
         }
 
+        // 7a. JSON Schema validation of request bodies; inside MIME validation and auth, so
+        // only accepted content types from authenticated callers are parsed
+        if config.request_validation.enabled {
+            let validators = middleware::validation::RequestValidators::from_specs(
+                &specs,
+                &self.openapi_registry.components_registry.load(),
+                &config.request_validation,
+            );
+            if !validators.is_empty() {
+                let validators = Arc::new(validators);
+                router = router.layer(from_fn(
+                    move |req: axum::extract::Request, next: axum::middleware::Next| {
+                        let validators = validators.clone();
+                        middleware::validation::request_validation_middleware(validators, req, next)
+                    },
+                ));
+            }
+        }
+
         // 8. MIME type validation (after CORS, before rate limiting)
-        let specs: Vec<_> = self
-            .openapi_registry
-            .operation_specs
-            .iter()
-            .map(|e| e.value().clone())
-            .collect();
         let mime_map = middleware::mime_validation::build_mime_validation_map(&specs);
         router = router.layer(from_fn(
             move |req: axum::extract::Request, next: axum::middleware::Next| {
@@ -268,8 +396,38 @@ impl ApiIngress {
             },
         ));
 
+        // 8a. Idempotency-Key replay for idempotent operations. Runs inside auth (keys are
+        // scoped to the caller) and inside rate limiting (replays still count).
+        if specs.iter().any(|s| s.idempotent) {
+            match self.idempotency_store.load_full() {
+                Some(store) => {
+                    let idem_map = middleware::idempotency::IdempotencyMap::from_specs(
+                        &specs,
+                        &config.idempotency,
+                        store,
+                    );
+                    router = router.layer(from_fn(
+                        move |req: axum::extract::Request, next: axum::middleware::Next| {
+                            let map = idem_map.clone();
+                            middleware::idempotency::idempotency_middleware(map, req, next)
+                        },
+                    ));
+                }
+                None => tracing::warn!(
+                    "Idempotent operations are registered but api_ingress has no database; \
+                     Idempotency-Key headers will be ignored"
+                ),
+            }
+        }
+
         // 9. Per-route rate limiting & in-flight limits (after MIME validation, before auth)
-        let rate_map = middleware::rate_limit::RateLimiterMap::from_specs(&specs, &config);
+        let mut rate_map = middleware::rate_limit::RateLimiterMap::from_specs(&specs, &config)?;
+        if let Some(store) = self.rate_limit_store.load_full() {
+            rate_map = rate_map.with_store(store);
+        }
+        if config.metrics.enabled {
+            rate_map = rate_map.with_metrics(self.http_metrics.clone());
+        }
         router = router.layer(from_fn(
             move |req: axum::extract::Request, next: axum::middleware::Next| {
                 let map = rate_map.clone();
@@ -280,6 +438,11 @@ impl ApiIngress {
         // 10. Error mapping layer (no-op converter for now; keeps order explicit)
         router = router.layer(from_fn(modkit::api::error_layer::error_mapping_middleware));
 
+        // 10a. Access log: note the authenticated caller for the line written further out
+        if config.access_log.enabled {
+            router = router.layer(from_fn(middleware::access_log::record_caller));
+        }
+
         // 11. Auth middleware - MUST be after CORS; preflight short-circuits before this.
         let config = self.get_cached_config();
         if config.auth_disabled {
@@ -322,6 +485,56 @@ impl ApiIngress {
             ));
         }
 
+        // 12. Shutdown drain: end SSE streams with a final event once draining starts
+        let drain = self.drain.clone();
+        let sse_retry_ms = config.shutdown.sse_retry_ms;
+        router = router.layer(from_fn(
+            move |req: axum::extract::Request, next: axum::middleware::Next| {
+                middleware::drain::sse_drain_middleware(drain.clone(), sse_retry_ms, req, next)
+            },
+        ));
+
+        // 12a. Deprecation notices, outside auth so rejected calls are told as well
+        let deprecation_map = middleware::versioning::build_deprecation_map(&specs);
+        if !deprecation_map.is_empty() {
+            router = router.layer(from_fn(
+                move |req: axum::extract::Request, next: axum::middleware::Next| {
+                    let map = deprecation_map.clone();
+                    middleware::versioning::deprecation_middleware(map, req, next)
+                },
+            ));
+        }
+
+        // 12b. Keepalive settings for WebSocket upgrade handlers
+        router = router.layer(axum::Extension(config.websocket.settings()));
+
+        // 12c. Per-route RED metrics, outside auth, rate limiting and timeouts so
+        // 401/403/429/503/504 responses are counted too
+        if config.metrics.enabled {
+            let http_metrics = self.http_metrics.clone();
+            router = router.layer(from_fn(
+                move |req: axum::extract::Request, next: axum::middleware::Next| {
+                    middleware::metrics::metrics_middleware(http_metrics.clone(), req, next)
+                },
+            ));
+        }
+
+        // 13. Access log, outermost so rejected and drained requests are logged too
+        if config.access_log.enabled {
+            let access_log = middleware::access_log::AccessLog::from_config(&config.access_log);
+            router = router.layer(from_fn(
+                move |req: axum::extract::Request, next: axum::middleware::Next| {
+                    middleware::access_log::access_log_middleware(access_log.clone(), req, next)
+                },
+            ));
+        }
+
+        // 14. Media-type version selection rewrites the path, so it wraps the routing itself
+        let media_type_versions = middleware::versioning::MediaTypeVersions::from_specs(&specs);
+        if !media_type_versions.is_empty() {
+            router = middleware::versioning::route_by_media_type(router, media_type_versions);
+        }
+
         Ok(router)
     }
 
@@ -358,6 +571,80 @@ impl ApiIngress {
         self.openapi_registry.build_openapi(&info)
     }
 
+    /// Build the AsyncAPI document of the streaming (SSE and WebSocket) operations.
+    pub fn build_asyncapi(&self) -> serde_json::Value {
+        let config = self.get_cached_config();
+        let info = modkit::api::OpenApiInfo {
+            title: config.openapi.title.clone(),
+            version: config.openapi.version.clone(),
+            description: config.openapi.description.clone(),
+        };
+        self.openapi_registry.build_asyncapi(&info)
+    }
+
+    /// Build the OpenAPI document of one API version declared by the operations.
+    pub fn build_openapi_for_version(&self, version: &str) -> Result<utoipa::openapi::OpenApi> {
+        let config = self.get_cached_config();
+        let info = modkit::api::OpenApiInfo {
+            title: config.openapi.title.clone(),
+            version: config.openapi.version.clone(),
+            description: config.openapi.description.clone(),
+        };
+        self.openapi_registry
+            .build_openapi_for_version(&info, version)
+    }
+
+    /// Plain HTTP listener answering every request with a redirect to the HTTPS port.
+    async fn spawn_https_redirect(
+        &self,
+        redirect_addr: &str,
+        https_port: u16,
+        stop_accepting: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        let addr: SocketAddr = redirect_addr
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid redirect address '{}': {}", redirect_addr, e))?;
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!("HTTP -> HTTPS redirect bound on {}", addr);
+        let stop = stop_accepting.clone();
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, tls::redirect_router(https_port))
+                .with_graceful_shutdown(stop.cancelled_owned())
+                .await
+            {
+                tracing::warn!(error = %e, "HTTP redirect listener failed");
+            }
+        });
+        Ok(())
+    }
+
+    /// Separate plain HTTP listener for the admin endpoints.
+    async fn spawn_admin_listener(
+        &self,
+        admin_addr: &str,
+        router: Router,
+        stop_accepting: &CancellationToken,
+    ) -> anyhow::Result<()> {
+        let addr: SocketAddr = admin_addr
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid admin address '{}': {}", admin_addr, e))?;
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!("Admin API bound on {}", addr);
+        let stop = stop_accepting.clone();
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(stop.cancelled_owned())
+            .await
+            {
+                tracing::warn!(error = %e, "Admin listener failed");
+            }
+        });
+        Ok(())
+    }
+
     /// Background HTTP server: bind, notify ready, serve until cancelled.
     ///
     /// This method is the lifecycle entry-point generated by the macro
@@ -385,22 +672,75 @@ impl ApiIngress {
 
         // Bind the socket, only now consider the service "ready"
         let listener = tokio::net::TcpListener::bind(addr).await?;
-        tracing::info!("HTTP server bound on {}", addr);
-        ready.notify(); // Starting -> Running
 
-        // Graceful shutdown on cancel
-        let shutdown = {
-            let cancel = cancel.clone();
-            async move {
-                cancel.cancelled().await;
-                tracing::info!("HTTP server shutting down gracefully (cancellation)");
+        // The listener closes only when `stop_accepting` fires, after the pre-stop delay.
+        let stop_accepting = CancellationToken::new();
+        let admin_router = { self.admin_router.lock().take() };
+        if let (Some(admin_router), Some(admin_addr)) = (admin_router, &cfg.admin.bind_addr) {
+            self.spawn_admin_listener(admin_addr, admin_router, &stop_accepting)
+                .await?;
+        }
+        let mut server: Pin<Box<dyn Future<Output = ()> + Send>> = match &cfg.tls {
+            Some(tls_cfg) => {
+                let tls = tls::ReloadableTls::new(tls_cfg)?;
+                tls.spawn_reloader(tls_cfg.clone(), cancel.child_token());
+                if let Some(redirect_addr) = &tls_cfg.redirect_http_addr {
+                    self.spawn_https_redirect(redirect_addr, addr.port(), &stop_accepting)
+                        .await?;
+                }
+                tracing::info!("HTTPS server bound on {}", addr);
+                let listener = tls::TlsListener::new(listener, tls)?;
+                Box::pin(server::serve(listener, router, stop_accepting.clone()))
+            }
+            None => {
+                tracing::info!("HTTP server bound on {}", addr);
+                Box::pin(server::serve(listener, router, stop_accepting.clone()))
             }
         };
+        ready.notify(); // Starting -> Running
 
-        axum::serve(listener, router)
-            .with_graceful_shutdown(shutdown)
-            .await
-            .map_err(|e| anyhow::anyhow!(e))
+        tokio::select! {
+            () = &mut server => return Ok(()),
+            _ = cancel.cancelled() => {}
+        }
+
+        // 1. Report not-ready so load balancers stop routing new traffic here
+        let shutdown = cfg.shutdown;
+        tracing::info!(
+            pre_stop_delay_ms = shutdown.pre_stop_delay_ms,
+            drain_timeout_ms = shutdown.drain_timeout_ms,
+            "HTTP server draining"
+        );
+        self.drain.mark_draining();
+
+        // 2. Keep serving during the pre-stop delay; SSE clients reconnect elsewhere
+        self.drain.close_streams();
+        tokio::select! {
+            () = &mut server => return Ok(()),
+            _ = tokio::time::sleep(Duration::from_millis(shutdown.pre_stop_delay_ms)) => {}
+        }
+
+        // 3. Stop accepting connections and wait for in-flight requests up to the deadline
+        stop_accepting.cancel();
+        match tokio::time::timeout(
+            Duration::from_millis(shutdown.drain_timeout_ms),
+            &mut server,
+        )
+        .await
+        {
+            Ok(()) => {
+                tracing::info!("HTTP server drained");
+                Ok(())
+            }
+            Err(_) => {
+                // 4. Dropping the server future aborts the connection tasks still running
+                tracing::warn!(
+                    drain_timeout_ms = shutdown.drain_timeout_ms,
+                    "HTTP server drain deadline exceeded; force-closing connections"
+                );
+                Ok(())
+            }
+        }
     }
 }
 
@@ -410,6 +750,27 @@ impl modkit::Module for ApiIngress {
     async fn init(&self, ctx: &modkit::context::ModuleCtx) -> anyhow::Result<()> {
         debug!("Module initialized with context");
         let cfg = ctx.config::<crate::config::ApiIngressConfig>()?;
+        if cfg.rate_limit.backend == config::RateLimitBackend::Db {
+            let db = ctx.db_required()?;
+            self.rate_limit_store
+                .store(Some(Arc::new(storage::RateLimitStore::new(
+                    db.sea_secure().conn().clone(),
+                ))));
+            debug!("Rate limit buckets are shared through the database");
+        }
+        if let Ok(registry) = ctx.metrics() {
+            // Re-registering after a repeated init is harmless; the series already exist
+            if let Err(e) = self.http_metrics.register(&registry) {
+                debug!(error = %e, "HTTP metrics not registered with the runtime registry");
+            }
+            self.metrics.store(registry);
+        }
+        if let Some(db) = ctx.db_optional() {
+            self.idempotency_store
+                .store(Some(Arc::new(storage::IdempotencyStore::new(
+                    db.sea_secure().conn().clone(),
+                ))));
+        }
         self.config.store(Arc::new(cfg));
 
         debug!(
@@ -422,6 +783,29 @@ impl modkit::Module for ApiIngress {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
+
+    fn as_system_module(&self) -> Option<&dyn modkit::contracts::SystemModule> {
+        Some(self)
+    }
+}
+
+impl modkit::contracts::SystemModule for ApiIngress {
+    fn wire_system(&self, sys: &modkit::runtime::SystemContext) {
+        self.admin_sources
+            .store(Arc::new(admin::AdminSources::from_system(sys)));
+    }
+}
+
+#[async_trait]
+impl modkit::contracts::DbModule for ApiIngress {
+    async fn migrate(&self, db: &modkit_db::DbHandle) -> anyhow::Result<()> {
+        use sea_orm_migration::MigratorTrait;
+        // api_ingress_rate_limits and api_ingress_idempotency_keys are system tables,
+        // not tenant-scoped
+        let sec = db.sea_secure();
+        storage::migrations::Migrator::up(sec.conn(), None).await?;
+        Ok(())
+    }
 }
 
 // Test that the module is properly registered via inventory
@@ -488,6 +872,92 @@ mod tests {
         assert_eq!(info.get("version").unwrap(), "1.0.0");
         assert_eq!(info.get("description").unwrap(), "Test Description");
     }
+
+    #[tokio::test]
+    async fn test_metrics_need_a_token_unless_made_public() {
+        use modkit_auth::types::{AuthRequirement, RoutePolicy};
+
+        let resolve = |public: bool| async move {
+            let config = ApiIngressConfig {
+                auth_disabled: true,
+                require_auth_by_default: false,
+                metrics: config::MetricsConfig {
+                    public,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let (_, policy) = ApiIngress::new(config)
+                .build_auth_state_from_specs()
+                .unwrap();
+            policy.resolve(&Method::GET, "/metrics").await
+        };
+
+        assert_eq!(resolve(false).await, AuthRequirement::Required(None));
+        assert_eq!(resolve(true).await, AuthRequirement::None);
+    }
+
+    #[tokio::test]
+    async fn test_requests_outliving_the_drain_timeout_are_cut_off() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = ApiIngressConfig {
+            bind_addr: format!("127.0.0.1:{port}"),
+            shutdown: config::ShutdownConfig {
+                pre_stop_delay_ms: 0,
+                drain_timeout_ms: 100,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let api = Arc::new(ApiIngress::new(config));
+        *api.final_router.lock() = Some(Router::new().route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                "late"
+            }),
+        ));
+
+        let cancel = CancellationToken::new();
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(
+            api.clone()
+                .serve(cancel.clone(), ReadySignal::from_sender(ready_tx)),
+        );
+        ready_rx.await.unwrap();
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET /slow HTTP/1.1\r\nhost: test\r\n\r\n")
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        cancel.cancel();
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("serve did not return after the drain timeout")
+            .unwrap()
+            .unwrap();
+
+        // The in-flight request got no response; its connection was closed
+        let mut buf = [0u8; 64];
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .expect("connection outlived the drain timeout");
+        assert!(
+            matches!(read, Ok(0) | Err(_)),
+            "unexpected response: {read:?}"
+        );
+    }
 }
 
 // REST host role: prepare/finalize the router, but do not start the server here.
@@ -497,8 +967,30 @@ impl modkit::contracts::RestHostModule for ApiIngress {
         _ctx: &modkit::context::ModuleCtx,
         router: axum::Router,
     ) -> anyhow::Result<axum::Router> {
-        // Add basic health check endpoint and any global middlewares
-        let router = router.route("/healthz", get(|| async { "ok" }));
+        // Add basic health check (liveness) and readiness endpoints and any global middlewares
+        let drain = self.drain.clone();
+        let mut router = router.route("/healthz", get(|| async { "ok" })).route(
+            "/readyz",
+            get(move || middleware::drain::readiness(drain.clone())),
+        );
+
+        let config = self.get_cached_config();
+        if config.metrics.enabled {
+            let registry = self.metrics.load_full();
+            router = router.route(
+                &config.metrics.path,
+                get(move || {
+                    let registry = registry.clone();
+                    async move {
+                        use axum::http::header;
+                        (
+                            [(header::CONTENT_TYPE, modkit::metrics::TEXT_CONTENT_TYPE)],
+                            registry.render(),
+                        )
+                    }
+                }),
+            );
+        }
 
         // You may attach global middlewares here (trace, compression, cors), but do not start server.
         tracing::debug!("REST host prepared base router with health check");
@@ -536,18 +1028,95 @@ impl modkit::contracts::RestHostModule for ApiIngress {
                 )
                 .route("/docs", get(web::serve_docs));
 
+            // SSE and WebSocket channels, with the same component schemas
+            let asyncapi_doc = Arc::new(self.build_asyncapi());
+            router = router
+                .route(
+                    "/asyncapi.json",
+                    get({
+                        use axum::{http::header, response::IntoResponse, Json};
+                        move || async move {
+                            (
+                                [(header::CACHE_CONTROL, "no-store")],
+                                Json(asyncapi_doc.as_ref()),
+                            )
+                                .into_response()
+                        }
+                    }),
+                )
+                .route("/docs/asyncapi", get(web::serve_asyncapi_docs));
+
+            // One document per API version declared by the operations
+            for version in self.openapi_registry.api_versions() {
+                let doc = Arc::new(self.build_openapi_for_version(&version)?);
+                router = router.route(
+                    &format!("/openapi/{version}.json"),
+                    get({
+                        use axum::{http::header, response::IntoResponse, Json};
+                        move || async move {
+                            ([(header::CACHE_CONTROL, "no-store")], Json(doc.as_ref()))
+                                .into_response()
+                        }
+                    }),
+                );
+            }
+
             #[cfg(feature = "embed_elements")]
             {
                 router = router.route("/docs/assets/{*file}", get(assets::serve_elements_asset));
             }
         }
 
+        // Sub-requests go through the finished router, so the endpoint reads it from the cache
+        if config.batch.enabled {
+            let specs: Vec<_> = self
+                .openapi_registry
+                .operation_specs
+                .iter()
+                .map(|e| e.value().clone())
+                .collect();
+            let state = batch::BatchState::new(&self.router_cache, config.batch.clone(), &specs);
+            router = router.route(
+                &config.batch.path,
+                axum::routing::post(move |req: axum::extract::Request| {
+                    batch::handle_batch(state.clone(), req)
+                }),
+            );
+        }
+
+        // Runtime introspection, on the main listener or on its own
+        if config.admin.enabled {
+            if config.auth_disabled {
+                // Without auth the role check is gone; only the network can keep callers out
+                if !config.admin.is_loopback_only() {
+                    anyhow::bail!(
+                        "admin API needs auth, or admin.bind_addr on a loopback address, \
+                         when auth_disabled is set"
+                    );
+                }
+                tracing::warn!(
+                    "Admin API is enabled while auth is disabled: it is open to local callers"
+                );
+            }
+            let admin_router = admin::router(
+                &config.admin.path,
+                (**self.admin_sources.load()).clone(),
+                self.openapi_registry.clone(),
+            );
+            if config.admin.bind_addr.is_some() {
+                *self.admin_router.lock() = Some(self.apply_middleware_stack(admin_router)?);
+            } else {
+                router = router.merge(admin_router);
+            }
+        }
+
         // Apply middleware stack (including auth) to the final router
         tracing::debug!("Applying middleware stack to finalized router");
         router = self.apply_middleware_stack(router)?;
 
         // Keep the finalized router to be used by `serve()`
         *self.final_router.lock() = Some(router.clone());
+        self.router_cache.store(router.clone());
 
         tracing::info!("REST host finalized router with OpenAPI endpoints and auth middleware");
         Ok(router)
@@ -0,0 +1,341 @@
+//! Access log: one line per request on the [`ACCESS_LOG_TARGET`] tracing target
+//!
+//! The line is written when the response body has been sent (or the client went away), so
+//! `bytes` and `latency_ms` cover the whole exchange. The caller's identity is only known
+//! inside the auth layer; [`record_caller`] runs there and fills a slot the outer
+//! [`access_log_middleware`] reads when it writes the line.
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::body::Body;
+use axum::extract::{ConnectInfo, MatchedPath, Request};
+use axum::http::{header, HeaderMap};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::StreamExt;
+use modkit::http::otel;
+use modkit_bootstrap::logging::ACCESS_LOG_TARGET;
+use modkit_security::SecurityCtx;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+
+use crate::config::{AccessLogConfig, AccessLogFormat};
+use crate::middleware::request_id;
+
+#[derive(Clone)]
+pub struct AccessLog {
+    format: AccessLogFormat,
+    sample_rate: f64,
+    exclude_paths: Arc<HashSet<String>>,
+}
+
+impl AccessLog {
+    pub fn from_config(cfg: &AccessLogConfig) -> Self {
+        Self {
+            format: cfg.format,
+            sample_rate: cfg.sample_rate.clamp(0.0, 1.0),
+            exclude_paths: Arc::new(cfg.exclude_paths.iter().cloned().collect()),
+        }
+    }
+
+    /// Server errors are always logged; other requests by a SHA-256 of their request id, so
+    /// every replica and build makes the same choice for a given request. Requests without an
+    /// id get a random draw.
+    fn sampled(&self, status: u16, request_id: Option<&str>) -> bool {
+        if status >= 500 || self.sample_rate >= 1.0 {
+            return true;
+        }
+        if self.sample_rate <= 0.0 {
+            return false;
+        }
+        let draw = match request_id {
+            Some(rid) => {
+                let digest = Sha256::digest(rid.as_bytes());
+                let mut prefix = [0u8; 8];
+                prefix.copy_from_slice(&digest[..8]);
+                u64::from_be_bytes(prefix) % 10_000
+            }
+            None => rand::random_range(0..10_000),
+        };
+        draw < (self.sample_rate * 10_000.0) as u64
+    }
+}
+
+/// Subject and tenant of the request, filled in by [`record_caller`].
+#[derive(Clone, Default)]
+struct CallerSlot(Arc<Mutex<Option<Caller>>>);
+
+#[derive(Clone, Debug, Default)]
+struct Caller {
+    subject_id: String,
+    tenant_id: Option<String>,
+}
+
+/// Runs inside the auth layer and records who the caller turned out to be.
+pub async fn record_caller(req: Request, next: Next) -> Response {
+    if let (Some(slot), Some(ctx)) = (
+        req.extensions().get::<CallerSlot>(),
+        req.extensions().get::<SecurityCtx>(),
+    ) {
+        *slot.0.lock() = Some(Caller {
+            subject_id: ctx.subject_id().to_string(),
+            tenant_id: ctx.scope().tenant_ids().first().map(|t| t.to_string()),
+        });
+    }
+    next.run(req).await
+}
+
+pub async fn access_log_middleware(log: AccessLog, mut req: Request, next: Next) -> Response {
+    if log.exclude_paths.contains(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let slot = CallerSlot::default();
+    req.extensions_mut().insert(slot.clone());
+    let mut entry = Entry::from_request(&req);
+    let start = Instant::now();
+
+    let resp = next.run(req).await;
+
+    entry.status = resp.status().as_u16();
+    // The request id is assigned inside this layer and echoed on the response
+    if let Some(rid) = header_str(resp.headers(), &request_id::header()) {
+        entry.request_id = Some(rid.to_string());
+    }
+    if !log.sampled(entry.status, entry.request_id.as_deref()) {
+        return resp;
+    }
+
+    let (parts, body) = resp.into_parts();
+    let mut pending = PendingLine {
+        format: log.format,
+        entry,
+        slot,
+        start,
+    };
+    let body = body.into_data_stream().inspect(move |chunk| {
+        // Borrow the whole line so the closure owns it, not just the `bytes` field
+        let pending = &mut pending;
+        if let Ok(chunk) = chunk {
+            pending.entry.bytes += chunk.len() as u64;
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(body))
+}
+
+/// Writes the line when dropped together with the response body.
+struct PendingLine {
+    format: AccessLogFormat,
+    entry: Entry,
+    slot: CallerSlot,
+    start: Instant,
+}
+
+impl Drop for PendingLine {
+    fn drop(&mut self) {
+        self.entry.latency_ms = self.start.elapsed().as_secs_f64() * 1000.0;
+        self.entry.caller = self.slot.0.lock().take();
+        let line = self.entry.render(self.format);
+        tracing::info!(target: ACCESS_LOG_TARGET, "{line}");
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    started_at: DateTime<Utc>,
+    remote_addr: Option<String>,
+    method: String,
+    target: String,
+    version: String,
+    route: Option<String>,
+    user_agent: Option<String>,
+    referer: Option<String>,
+    request_id: Option<String>,
+    trace_id: Option<String>,
+    caller: Option<Caller>,
+    status: u16,
+    bytes: u64,
+    latency_ms: f64,
+}
+
+impl Entry {
+    fn from_request(req: &Request) -> Self {
+        let headers = req.headers();
+        Self {
+            started_at: Utc::now(),
+            remote_addr: req
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|c| c.0.ip().to_string()),
+            method: req.method().to_string(),
+            target: req
+                .uri()
+                .path_and_query()
+                .map(|pq| pq.to_string())
+                .unwrap_or_else(|| "/".to_string()),
+            version: format!("{:?}", req.version()),
+            route: req
+                .extensions()
+                .get::<MatchedPath>()
+                .map(|p| p.as_str().to_string()),
+            user_agent: header_str(headers, &header::USER_AGENT).map(str::to_string),
+            referer: header_str(headers, &header::REFERER).map(str::to_string),
+            request_id: header_str(headers, &request_id::header()).map(str::to_string),
+            trace_id: otel::get_traceparent(headers).and_then(otel::parse_trace_id),
+            ..Default::default()
+        }
+    }
+
+    fn render(&self, format: AccessLogFormat) -> String {
+        match format {
+            AccessLogFormat::Json => self.render_json(),
+            AccessLogFormat::Common => self.render_clf(false),
+            AccessLogFormat::Combined => self.render_clf(true),
+        }
+    }
+
+    fn render_json(&self) -> String {
+        let caller = self.caller.as_ref();
+        serde_json::json!({
+            "ts": self.started_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "request_id": self.request_id,
+            "trace_id": self.trace_id,
+            "subject_id": caller.map(|c| &c.subject_id),
+            "tenant_id": caller.and_then(|c| c.tenant_id.as_ref()),
+            "remote_addr": self.remote_addr,
+            "method": self.method,
+            "path": self.target,
+            "route": self.route,
+            "status": self.status,
+            "bytes": self.bytes,
+            "latency_ms": (self.latency_ms * 1000.0).round() / 1000.0,
+            "user_agent": self.user_agent,
+            "referer": self.referer,
+        })
+        .to_string()
+    }
+
+    /// `host ident user [time] "request" status bytes` (+ `"referer" "agent"` when combined),
+    /// then the fields CLF has no column for.
+    fn render_clf(&self, combined: bool) -> String {
+        let dash = |v: Option<&str>| v.unwrap_or("-").to_string();
+        let caller = self.caller.as_ref();
+        let mut line = format!(
+            "{} - {} [{}] {} {} {}",
+            dash(self.remote_addr.as_deref()),
+            dash(caller.map(|c| c.subject_id.as_str())),
+            self.started_at.format("%d/%b/%Y:%H:%M:%S %z"),
+            quoted(&format!("{} {} {}", self.method, self.target, self.version)),
+            self.status,
+            if self.bytes == 0 {
+                "-".to_string()
+            } else {
+                self.bytes.to_string()
+            },
+        );
+        if combined {
+            let _ = write!(
+                line,
+                " {} {}",
+                quoted(self.referer.as_deref().unwrap_or("-")),
+                quoted(self.user_agent.as_deref().unwrap_or("-")),
+            );
+        }
+        let _ = write!(
+            line,
+            " request_id={} trace_id={} tenant_id={} route={} latency_ms={:.3}",
+            dash(self.request_id.as_deref()),
+            dash(self.trace_id.as_deref()),
+            dash(caller.and_then(|c| c.tenant_id.as_deref())),
+            quoted(self.route.as_deref().unwrap_or("-")),
+            self.latency_ms,
+        );
+        line
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &header::HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Double-quoted with `"` and `\` escaped, as access log parsers expect.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry() -> Entry {
+        Entry {
+            started_at: Utc.with_ymd_and_hms(2025, 3, 1, 12, 30, 5).unwrap(),
+            remote_addr: Some("203.0.113.9".into()),
+            method: "GET".into(),
+            target: "/users/42?x=1".into(),
+            version: "HTTP/1.1".into(),
+            route: Some("/users/{id}".into()),
+            user_agent: Some("curl/8.5 \"test\"".into()),
+            referer: None,
+            request_id: Some("rid-1".into()),
+            trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".into()),
+            caller: Some(Caller {
+                subject_id: "sub-1".into(),
+                tenant_id: Some("ten-1".into()),
+            }),
+            status: 200,
+            bytes: 512,
+            latency_ms: 12.5,
+        }
+    }
+
+    #[test]
+    fn test_combined_line() {
+        assert_eq!(
+            entry().render(AccessLogFormat::Combined),
+            "203.0.113.9 - sub-1 [01/Mar/2025:12:30:05 +0000] \"GET /users/42?x=1 HTTP/1.1\" 200 512 \
+             \"-\" \"curl/8.5 \\\"test\\\"\" request_id=rid-1 \
+             trace_id=4bf92f3577b34da6a3ce929d0e0e4736 tenant_id=ten-1 route=\"/users/{id}\" \
+             latency_ms=12.500"
+        );
+    }
+
+    #[test]
+    fn test_json_line_carries_identity_and_route() {
+        let line: serde_json::Value =
+            serde_json::from_str(&entry().render(AccessLogFormat::Json)).unwrap();
+        assert_eq!(line["request_id"], "rid-1");
+        assert_eq!(line["subject_id"], "sub-1");
+        assert_eq!(line["tenant_id"], "ten-1");
+        assert_eq!(line["route"], "/users/{id}");
+        assert_eq!(line["status"], 200);
+        assert_eq!(line["bytes"], 512);
+        assert_eq!(line["ts"], "2025-03-01T12:30:05.000Z");
+    }
+
+    #[test]
+    fn test_sampling_keeps_server_errors() {
+        let log = AccessLog::from_config(&AccessLogConfig {
+            sample_rate: 0.0,
+            ..Default::default()
+        });
+        assert!(!log.sampled(200, Some("rid-1")));
+        assert!(log.sampled(503, Some("rid-1")));
+
+        let half = AccessLog::from_config(&AccessLogConfig {
+            sample_rate: 0.5,
+            ..Default::default()
+        });
+        // Fixed per request id: SHA-256 buckets 3947 and 8285 of 10 000
+        assert!(half.sampled(200, Some("rid-1")));
+        assert!(!half.sampled(200, Some("rid-3")));
+
+        // Without an id, each request is a fresh draw
+        let kept = (0..1_000).filter(|_| half.sampled(200, None)).count();
+        assert!((300..700).contains(&kept), "kept {kept} of 1000");
+    }
+}
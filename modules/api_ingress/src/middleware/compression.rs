@@ -0,0 +1,174 @@
+//! Response compression and request decompression
+//!
+//! Responses are compressed only when the client accepts a configured coding, the body is large
+//! enough and its media type is on the `compression.content_types` allow-list. Event streams are
+//! always sent uncompressed so events are not held back by the encoder.
+use std::sync::Arc;
+
+use axum::body::HttpBody;
+use axum::http::{header, Response};
+use tower_http::compression::predicate::{And, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::decompression::RequestDecompressionLayer;
+
+use crate::config::{CompressionAlgorithm, CompressionConfig};
+
+/// Media type allow-list predicate; see [`CompressionConfig::content_types`].
+#[derive(Clone)]
+pub struct CompressibleContent {
+    types: Arc<[String]>,
+}
+
+impl CompressibleContent {
+    pub fn new(types: &[String]) -> Self {
+        Self {
+            types: types.iter().map(|t| t.to_ascii_lowercase()).collect(),
+        }
+    }
+}
+
+impl Predicate for CompressibleContent {
+    fn should_compress<B>(&self, response: &Response<B>) -> bool
+    where
+        B: HttpBody,
+    {
+        let Some(content_type) = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+        else {
+            return false;
+        };
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        if essence == "text/event-stream" {
+            return false;
+        }
+        self.types.iter().any(|t| {
+            if t.ends_with('/') {
+                essence.starts_with(t.as_str())
+            } else {
+                essence == *t
+            }
+        })
+    }
+}
+
+pub fn compression_layer(
+    cfg: &CompressionConfig,
+) -> CompressionLayer<And<SizeAbove, CompressibleContent>> {
+    let has = |a| cfg.algorithms.contains(&a);
+    CompressionLayer::new()
+        .gzip(has(CompressionAlgorithm::Gzip))
+        .br(has(CompressionAlgorithm::Br))
+        .zstd(has(CompressionAlgorithm::Zstd))
+        .compress_when(
+            SizeAbove::new(cfg.min_size_bytes).and(CompressibleContent::new(&cfg.content_types)),
+        )
+}
+
+/// Decodes `Content-Encoding` request bodies; unsupported codings are rejected with 415.
+pub fn decompression_layer(cfg: &CompressionConfig) -> RequestDecompressionLayer {
+    let has = |a| cfg.algorithms.contains(&a);
+    RequestDecompressionLayer::new()
+        .gzip(has(CompressionAlgorithm::Gzip))
+        .br(has(CompressionAlgorithm::Br))
+        .zstd(has(CompressionAlgorithm::Zstd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::{get, post};
+    use axum::Router;
+    use std::io::Write;
+    use tower::ServiceExt;
+    use tower_http::limit::RequestBodyLimitLayer;
+
+    fn response(content_type: &str) -> Response<Body> {
+        Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_allow_list_matches_media_type() {
+        let p = CompressibleContent::new(&["application/json".to_string(), "text/".to_string()]);
+        assert!(p.should_compress(&response("application/json; charset=utf-8")));
+        assert!(p.should_compress(&response("text/markdown")));
+        assert!(!p.should_compress(&response("text/event-stream")));
+        assert!(!p.should_compress(&response("image/png")));
+        assert!(!p.should_compress(&Response::new(Body::empty())));
+    }
+
+    #[tokio::test]
+    async fn test_large_json_is_compressed_small_is_not() {
+        let router = Router::new()
+            .route("/big", get(|| async { axum::Json(vec!["hyperspot"; 500]) }))
+            .route("/small", get(|| async { axum::Json("ok") }))
+            .layer(compression_layer(&CompressionConfig::default()));
+
+        let call = |uri: &'static str| {
+            router.clone().oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header(header::ACCEPT_ENCODING, "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let resp = call("/big").await.unwrap();
+        assert_eq!(resp.headers()[header::CONTENT_ENCODING], "gzip");
+
+        let resp = call("/small").await.unwrap();
+        assert!(resp.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decoded_body_is_held_to_body_limit() {
+        const LIMIT: usize = 1024;
+
+        // Same order as the ingress stack: decompression wraps the body limit
+        let router = Router::new()
+            .route(
+                "/echo",
+                post(|body: axum::body::Bytes| async move { body.len().to_string() }),
+            )
+            .layer(RequestBodyLimitLayer::new(LIMIT))
+            .layer(decompression_layer(&CompressionConfig::default()));
+
+        let gzip = |len: usize| {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder.write_all(&vec![b'a'; len]).unwrap();
+            encoder.finish().unwrap()
+        };
+        let call = |body: Vec<u8>| {
+            router.clone().oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/echo")
+                    .header(header::CONTENT_ENCODING, "gzip")
+                    .header(header::CONTENT_LENGTH, body.len())
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        };
+
+        let resp = call(gzip(LIMIT / 2)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let bomb = gzip(64 * LIMIT);
+        assert!(bomb.len() < LIMIT);
+        let resp = call(bomb).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,170 @@
+//! Shutdown draining: readiness flip and termination of long-lived SSE streams and WebSockets.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::body::{Body, Bytes};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::{extract::Request, middleware::Next};
+use futures::{stream, StreamExt};
+use modkit::http::ws::WsShutdown;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+/// SSE event name sent to clients right before their stream is closed for shutdown.
+pub const SSE_SHUTDOWN_EVENT: &str = "shutdown";
+
+/// Shared drain state between the server loop, the readiness probe and the SSE middleware.
+#[derive(Clone, Default)]
+pub struct DrainState {
+    draining: Arc<AtomicBool>,
+    streams_close: CancellationToken,
+}
+
+impl DrainState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Report not-ready from now on.
+    pub fn mark_draining(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    /// Send the shutdown event on every open SSE stream and end them; close WebSockets.
+    pub fn close_streams(&self) {
+        self.streams_close.cancel();
+    }
+}
+
+/// Final SSE frame; `retry` tells clients how soon to reconnect (to another instance).
+fn shutdown_frame(retry_ms: u64) -> Bytes {
+    Bytes::from(format!(
+        "event: {SSE_SHUTDOWN_EVENT}\nretry: {retry_ms}\ndata: {}\n\n",
+        json!({ "reason": "server_shutdown" })
+    ))
+}
+
+/// Readiness probe: 200 while serving, 503 once draining started.
+pub async fn readiness(state: DrainState) -> Response {
+    if state.is_draining() {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "draining" })),
+        )
+            .into_response()
+    } else {
+        Json(json!({ "status": "ready" })).into_response()
+    }
+}
+
+/// Wrap `text/event-stream` responses so they end with a shutdown event when draining, and
+/// hand WebSocket handlers the token that closes them.
+pub async fn sse_drain_middleware(
+    state: DrainState,
+    retry_ms: u64,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    req.extensions_mut()
+        .insert(WsShutdown(state.streams_close.clone()));
+    let resp = next.run(req).await;
+
+    let is_sse = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    if !is_sse {
+        return resp;
+    }
+
+    let (parts, body) = resp.into_parts();
+    let closed = state.streams_close.clone();
+    let frame = shutdown_frame(retry_ms);
+
+    let data = body
+        .into_data_stream()
+        .take_until(state.streams_close.clone().cancelled_owned());
+    // Evaluated only once the inner stream ended, so natural completion sends nothing.
+    let tail = stream::once(async move { closed.is_cancelled() }).filter_map(move |closed| {
+        let frame = frame.clone();
+        async move { closed.then(|| Ok::<_, axum::Error>(frame)) }
+    });
+
+    Response::from_parts(parts, Body::from_stream(data.chain(tail)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware::from_fn, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(state: DrainState) -> Router {
+        let sse = Router::new().route(
+            "/events",
+            get(|| async {
+                let pending = stream::pending::<Result<Bytes, std::convert::Infallible>>();
+                (
+                    [(header::CONTENT_TYPE, "text/event-stream")],
+                    Body::from_stream(pending),
+                )
+            }),
+        );
+        let probe = state.clone();
+        sse.route("/readyz", get(move || readiness(probe.clone())))
+            .layer(from_fn(move |req: Request, next: Next| {
+                sse_drain_middleware(state.clone(), 1000, req, next)
+            }))
+    }
+
+    #[tokio::test]
+    async fn test_readiness_flips_when_draining() {
+        let state = DrainState::new();
+        let app = app(state.clone());
+        let req = || {
+            Request::builder()
+                .uri("/readyz")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(req()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        state.mark_draining();
+        let resp = app.oneshot(req()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_ends_with_shutdown_event() {
+        let state = DrainState::new();
+        let resp = app(state.clone())
+            .oneshot(
+                Request::builder()
+                    .uri("/events")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        state.close_streams();
+        let body = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            axum::body::to_bytes(resp.into_body(), usize::MAX),
+        )
+        .await
+        .expect("stream should end after drain")
+        .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.starts_with("event: shutdown\nretry: 1000\n"));
+    }
+}
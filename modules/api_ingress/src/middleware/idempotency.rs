@@ -0,0 +1,554 @@
+//! `Idempotency-Key` support for operations marked with `OperationBuilder::idempotent()`
+//!
+//! The first request with a key runs normally and its response is stored; retries with the
+//! same key and payload get that response back with `Idempotent-Replayed: true`. A retry that
+//! arrives while the first request is still running gets 409, and reusing a key for a
+//! different payload gets 422. Keys are scoped to the caller's tenants and subject, so
+//! anonymous requests are passed through untouched.
+//!
+//! Server errors, event streams and oversized responses are not stored; the key is released
+//! so the client can simply retry. So is a key whose request was cancelled (client gone,
+//! timeout). The database being unavailable must not take the API down, so store errors let
+//! the request through without idempotency.
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::{Body, Bytes, HttpBody};
+use axum::http::header::{CONTENT_TYPE, RETRY_AFTER};
+use axum::http::{request::Parts, HeaderName, HeaderValue, Method, StatusCode};
+use axum::{
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures::StreamExt;
+use modkit_security::SecurityCtx;
+use sha2::{Digest, Sha256};
+
+use crate::config::IdempotencyConfig;
+use crate::errors::ErrorCode;
+use crate::storage::{Claim, ClaimToken, IdempotencyStore, StoredResponse};
+
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Longest accepted key, in bytes
+const MAX_KEY_LEN: usize = 255;
+
+/// Response headers that describe the live exchange rather than the resource, and so are
+/// never replayed.
+const VOLATILE_HEADERS: &[&str] = &[
+    "content-length",
+    "date",
+    "retry-after",
+    "ratelimit-limit",
+    "ratelimit-remaining",
+    "ratelimit-reset",
+    "x-request-id",
+];
+
+#[derive(Clone)]
+pub struct IdempotencyMap {
+    routes: Arc<HashSet<(Method, String)>>,
+    store: Arc<IdempotencyStore>,
+    ttl_secs: i64,
+    lock_timeout_secs: i64,
+    max_response_bytes: usize,
+}
+
+impl IdempotencyMap {
+    pub fn from_specs(
+        specs: &[modkit::api::OperationSpec],
+        cfg: &IdempotencyConfig,
+        store: Arc<IdempotencyStore>,
+    ) -> Self {
+        let routes = specs
+            .iter()
+            .filter(|s| s.idempotent)
+            .map(|s| (s.method.clone(), s.path.clone()))
+            .collect();
+        Self {
+            routes: Arc::new(routes),
+            store,
+            ttl_secs: i64::try_from(cfg.ttl_secs).unwrap_or(i64::MAX),
+            lock_timeout_secs: i64::try_from(cfg.lock_timeout_secs).unwrap_or(i64::MAX),
+            max_response_bytes: cfg.max_response_bytes,
+        }
+    }
+}
+
+pub async fn idempotency_middleware(map: IdempotencyMap, req: Request, next: Next) -> Response {
+    if req.method().is_safe() {
+        return next.run(req).await;
+    }
+    let path = req
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    if !map.routes.contains(&(req.method().clone(), path)) {
+        return next.run(req).await;
+    }
+    let Some(raw_key) = req.headers().get(IDEMPOTENCY_KEY) else {
+        return next.run(req).await;
+    };
+    let Some(key) = parse_key(raw_key) else {
+        return ErrorCode::api_ingress_errors_invalid_idempotency_key_v1()
+            .to_problem(format!(
+                "Idempotency-Key must be 1 to {MAX_KEY_LEN} visible ASCII characters"
+            ))
+            .into_response();
+    };
+    let Some(owner) = owner(&req) else {
+        return next.run(req).await;
+    };
+    let id = digest(&[owner.as_bytes(), key.as_bytes()]);
+
+    let (parts, body) = req.into_parts();
+    let body = match super::buffer_request_body(body).await {
+        Ok(body) => body,
+        Err(resp) => return resp,
+    };
+    let fingerprint = fingerprint(&parts, &body);
+
+    let now = unix_now();
+    let claim = map
+        .store
+        .claim(
+            &id,
+            &fingerprint,
+            now,
+            now.saturating_add(map.lock_timeout_secs),
+        )
+        .await;
+    let req = Request::from_parts(parts, Body::from(body));
+    let token = match claim {
+        Ok(Claim::Acquired(token)) => token,
+        Ok(Claim::Held {
+            fingerprint: held, ..
+        }) if held != fingerprint => {
+            return ErrorCode::api_ingress_errors_idempotency_key_reused_v1()
+                .to_problem("Idempotency-Key was already used for a different request")
+                .into_response();
+        }
+        Ok(Claim::Held { response: None, .. }) => {
+            let mut resp = ErrorCode::api_ingress_errors_idempotency_in_progress_v1()
+                .to_problem("A request with this Idempotency-Key is still in progress")
+                .into_response();
+            resp.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from_static("1"));
+            return resp;
+        }
+        Ok(Claim::Held {
+            response: Some(stored),
+            ..
+        }) => return replay(stored),
+        Err(e) => {
+            tracing::warn!(error = %e, "Idempotency store unavailable; running request without it");
+            return next.run(req).await;
+        }
+    };
+
+    let mut guard = KeyGuard {
+        store: map.store.clone(),
+        token: Some(token),
+    };
+    let resp = next.run(req).await;
+
+    let is_stream = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    if resp.status().is_server_error() || is_stream {
+        return resp; // the guard releases the key
+    }
+
+    let (parts, body) = resp.into_parts();
+    let body = match buffer_within(body, map.max_response_bytes).await {
+        Ok(Ok(body)) => body,
+        // Too large to store: stream it through as-is; the guard releases the key
+        Ok(Err(body)) => return Response::from_parts(parts, body),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to buffer response of idempotent request");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let stored = capture(&parts, &body);
+    let token = guard.token.take().expect("key is held until completed");
+    let expires_at = unix_now().saturating_add(map.ttl_secs);
+    if let Err(e) = map.store.complete(&token, &stored, expires_at).await {
+        tracing::warn!(error = %e, "Failed to store idempotent response");
+        guard.token = Some(token);
+    }
+    purge_in_background(&map.store);
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Buffer `body` if it is at most `limit` bytes. A larger body is handed back as a stream,
+/// with the part read so far put in front, so it is never held in memory as a whole.
+async fn buffer_within(body: Body, limit: usize) -> Result<Result<Bytes, Body>, axum::Error> {
+    if body.size_hint().lower() > limit as u64 {
+        return Ok(Err(body));
+    }
+    let mut stream = body.into_data_stream();
+    let mut buf = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        buf.extend_from_slice(&chunk?);
+        if buf.len() > limit {
+            let head = futures::stream::once(futures::future::ready(Ok(Bytes::from(buf))));
+            return Ok(Err(Body::from_stream(head.chain(stream))));
+        }
+    }
+    Ok(Ok(Bytes::from(buf)))
+}
+
+/// Releases a claimed key unless the response was stored, including when the request future
+/// is dropped midway.
+struct KeyGuard {
+    store: Arc<IdempotencyStore>,
+    token: Option<ClaimToken>,
+}
+
+impl Drop for KeyGuard {
+    fn drop(&mut self) {
+        let Some(token) = self.token.take() else {
+            return;
+        };
+        let store = self.store.clone();
+        tokio::spawn(async move {
+            if let Err(e) = store.release(&token).await {
+                tracing::debug!(error = %e, "Failed to release idempotency key");
+            }
+        });
+    }
+}
+
+fn purge_in_background(store: &Arc<IdempotencyStore>) {
+    let now = unix_now();
+    if !store.purge_due(now) {
+        return;
+    }
+    let store = store.clone();
+    tokio::spawn(async move {
+        if let Err(e) = store.purge_expired(now).await {
+            tracing::debug!(error = %e, "Failed to purge expired idempotency keys");
+        }
+    });
+}
+
+fn parse_key(value: &HeaderValue) -> Option<&str> {
+    let key = value.to_str().ok()?;
+    let valid =
+        !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic());
+    valid.then_some(key)
+}
+
+/// Caller identity keys are scoped to; `None` for anonymous requests.
+fn owner(req: &Request) -> Option<String> {
+    let ctx = req.extensions().get::<SecurityCtx>()?;
+    let subject = ctx.subject_id();
+    if subject.is_nil() {
+        return None;
+    }
+    let tenants: Vec<String> = ctx
+        .scope()
+        .tenant_ids()
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+    Some(format!("{}|{subject}", tenants.join(",")))
+}
+
+/// What makes two requests "the same": target, media type and payload.
+fn fingerprint(parts: &Parts, body: &Bytes) -> String {
+    let target = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .map(|v| v.as_bytes())
+        .unwrap_or_default();
+    digest(&[
+        parts.method.as_str().as_bytes(),
+        target.as_bytes(),
+        content_type,
+        body,
+    ])
+}
+
+fn capture(parts: &axum::http::response::Parts, body: &Bytes) -> StoredResponse {
+    let headers = parts
+        .headers
+        .iter()
+        .filter(|(name, _)| !VOLATILE_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    StoredResponse {
+        status: parts.status.as_u16(),
+        headers,
+        body: body.to_vec(),
+    }
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut resp = Response::new(Body::from(stored.body));
+    *resp.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let headers = resp.headers_mut();
+    for (name, value) in &stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::try_from(name.as_str()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    resp
+}
+
+/// Hex SHA-256 over length-prefixed fields, so field boundaries cannot be shifted.
+fn digest(fields: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for field in fields {
+        hasher.update((field.len() as u64).to_be_bytes());
+        hasher.update(field);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use uuid::Uuid;
+
+    fn request(method: Method, uri: &str) -> Request {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_key_accepts_visible_ascii_only() {
+        assert_eq!(
+            parse_key(&HeaderValue::from_static("3f7c-a1")),
+            Some("3f7c-a1")
+        );
+        assert!(parse_key(&HeaderValue::from_static("")).is_none());
+        assert!(parse_key(&HeaderValue::from_static("with space")).is_none());
+        let long = "k".repeat(MAX_KEY_LEN + 1);
+        assert!(parse_key(&HeaderValue::from_str(&long).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_owner_requires_an_authenticated_subject() {
+        let mut req = request(Method::POST, "/users");
+        assert!(owner(&req).is_none());
+
+        let tenant = Uuid::new_v4();
+        let subject = Uuid::new_v4();
+        req.extensions_mut()
+            .insert(SecurityCtx::for_tenant(tenant, subject));
+        assert_eq!(owner(&req), Some(format!("{tenant}|{subject}")));
+
+        req.extensions_mut()
+            .insert(SecurityCtx::for_tenant(tenant, Uuid::nil()));
+        assert!(owner(&req).is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_covers_target_and_payload() {
+        let (post, _) = request(Method::POST, "/users").into_parts();
+        let (put, _) = request(Method::PUT, "/users").into_parts();
+        let (query, _) = request(Method::POST, "/users?dry_run=true").into_parts();
+        let body = Bytes::from_static(b"{\"name\":\"a\"}");
+
+        let base = fingerprint(&post, &body);
+        assert_eq!(base, fingerprint(&post, &body));
+        assert_ne!(base, fingerprint(&put, &body));
+        assert_ne!(base, fingerprint(&query, &body));
+        assert_ne!(base, fingerprint(&post, &Bytes::from_static(b"{}")));
+    }
+
+    #[test]
+    fn test_replay_restores_response_without_volatile_headers() {
+        let resp = Response::builder()
+            .status(StatusCode::CREATED)
+            .header(CONTENT_TYPE, "application/json")
+            .header("location", "/users/1")
+            .header("ratelimit-remaining", "9")
+            .header("x-request-id", "abc")
+            .body(Body::empty())
+            .unwrap();
+        let (parts, _) = resp.into_parts();
+        let stored = capture(&parts, &Bytes::from_static(b"{\"id\":1}"));
+        assert_eq!(stored.status, 201);
+        assert_eq!(stored.headers.len(), 2);
+
+        let replayed = replay(stored);
+        assert_eq!(replayed.status(), StatusCode::CREATED);
+        assert_eq!(replayed.headers()["location"], "/users/1");
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED], "true");
+        assert!(replayed.headers().get("x-request-id").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_bodies_stream_through_intact() {
+        let small = buffer_within(Body::from("0123456789"), 10).await.unwrap();
+        assert_eq!(small.unwrap(), Bytes::from_static(b"0123456789"));
+
+        // Unknown length: read past the limit, then hand back everything
+        let chunks = futures::stream::iter(["01234", "56789", "abcde"].map(Ok::<_, axum::Error>));
+        let large = buffer_within(Body::from_stream(chunks), 8).await.unwrap();
+        let body = to_bytes(large.unwrap_err(), usize::MAX).await.unwrap();
+        assert_eq!(body, Bytes::from_static(b"0123456789abcde"));
+
+        // Known length: not read at all
+        let sized = buffer_within(Body::from("0123456789"), 8).await.unwrap();
+        assert!(sized.is_err());
+    }
+
+    mod through_router {
+        use super::*;
+        use axum::routing::post;
+        use axum::Router;
+        use modkit_db::{ConnectOpts, DbHandle};
+        use sea_orm_migration::MigratorTrait;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tempfile::TempDir;
+        use tokio::sync::Notify;
+        use tower::ServiceExt;
+
+        /// Handler state: calls so far, and a gate that holds `"slow"` requests.
+        #[derive(Default)]
+        struct Calls {
+            count: AtomicUsize,
+            entered: Notify,
+            release: Notify,
+        }
+
+        async fn app(dir: &TempDir, calls: Arc<Calls>) -> Router {
+            let dsn = format!("sqlite://{}?mode=rwc", dir.path().join("idem.db").display());
+            let db = DbHandle::connect(&dsn, ConnectOpts::default())
+                .await
+                .unwrap();
+            let conn = db.sea_secure().conn().clone();
+            crate::storage::migrations::Migrator::up(&conn, None)
+                .await
+                .unwrap();
+            let map = IdempotencyMap {
+                routes: Arc::new(HashSet::from([(Method::POST, "/notes".to_string())])),
+                store: Arc::new(IdempotencyStore::new(conn)),
+                ttl_secs: 3_600,
+                lock_timeout_secs: 60,
+                max_response_bytes: 64 * 1024,
+            };
+            let ctx = SecurityCtx::for_tenant(Uuid::new_v4(), Uuid::new_v4());
+
+            Router::new()
+                .route(
+                    "/notes",
+                    post(move |body: String| async move {
+                        let n = calls.count.fetch_add(1, Ordering::SeqCst) + 1;
+                        if body.contains("slow") {
+                            calls.entered.notify_one();
+                            calls.release.notified().await;
+                        }
+                        (StatusCode::CREATED, format!("{{\"call\":{n}}}"))
+                    }),
+                )
+                .layer(axum::middleware::from_fn(move |req, next| {
+                    idempotency_middleware(map.clone(), req, next)
+                }))
+                .layer(axum::Extension(ctx))
+        }
+
+        fn post_note(key: &str, body: &str) -> Request {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/notes")
+                .header(CONTENT_TYPE, "application/json")
+                .header(IDEMPOTENCY_KEY, key)
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        }
+
+        async fn body_of(resp: Response) -> String {
+            let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_retry_is_answered_from_the_stored_response() {
+            let dir = TempDir::new().unwrap();
+            let calls = Arc::new(Calls::default());
+            let app = app(&dir, calls.clone()).await;
+
+            let first = app.clone().oneshot(post_note("k1", "{}")).await.unwrap();
+            assert_eq!(first.status(), StatusCode::CREATED);
+            assert!(first.headers().get(IDEMPOTENT_REPLAYED).is_none());
+            assert_eq!(body_of(first).await, r#"{"call":1}"#);
+
+            let retry = app.oneshot(post_note("k1", "{}")).await.unwrap();
+            assert_eq!(retry.status(), StatusCode::CREATED);
+            assert_eq!(retry.headers()[IDEMPOTENT_REPLAYED], "true");
+            assert_eq!(body_of(retry).await, r#"{"call":1}"#);
+            assert_eq!(calls.count.load(Ordering::SeqCst), 1);
+        }
+
+        #[tokio::test]
+        async fn test_duplicate_of_a_running_request_gets_409() {
+            let dir = TempDir::new().unwrap();
+            let calls = Arc::new(Calls::default());
+            let app = app(&dir, calls.clone()).await;
+
+            let running = tokio::spawn(app.clone().oneshot(post_note("k1", r#""slow""#)));
+            calls.entered.notified().await;
+
+            let duplicate = app.oneshot(post_note("k1", r#""slow""#)).await.unwrap();
+            assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+            assert!(duplicate.headers().contains_key(RETRY_AFTER));
+
+            calls.release.notify_one();
+            let first = running.await.unwrap().unwrap();
+            assert_eq!(first.status(), StatusCode::CREATED);
+            assert_eq!(calls.count.load(Ordering::SeqCst), 1);
+        }
+
+        #[tokio::test]
+        async fn test_key_reused_for_another_payload_gets_422() {
+            let dir = TempDir::new().unwrap();
+            let calls = Arc::new(Calls::default());
+            let app = app(&dir, calls.clone()).await;
+
+            let first = app.clone().oneshot(post_note("k1", "{}")).await.unwrap();
+            assert_eq!(first.status(), StatusCode::CREATED);
+
+            let reused = app
+                .oneshot(post_note("k1", r#"{"other":true}"#))
+                .await
+                .unwrap();
+            assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(calls.count.load(Ordering::SeqCst), 1);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Per-route RED metrics (rate, errors, duration) for the `/metrics` endpoint
+//!
+//! Series are labelled by HTTP method and the route template from `MatchedPath`, never by the
+//! raw path, so cardinality stays bounded; requests no route matched share `route="unmatched"`.
+//! Durations are measured until the response head is produced, so long-lived streams do not
+//! skew the histogram.
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use modkit::metrics::prometheus::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+};
+use modkit::MetricsRegistry;
+
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Why the rate limiter turned a request away.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RejectReason {
+    /// Token bucket or shared window exhausted (429)
+    Rate,
+    /// In-flight limit reached (503)
+    InFlight,
+}
+
+impl RejectReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            RejectReason::Rate => "rate",
+            RejectReason::InFlight => "in_flight",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct HttpMetrics {
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    duration: HistogramVec,
+    in_flight: IntGaugeVec,
+    rate_limited: IntCounterVec,
+}
+
+impl HttpMetrics {
+    pub fn new() -> Self {
+        let labels = &["method", "route"];
+        Self {
+            requests: IntCounterVec::new(
+                Opts::new("http_requests_total", "HTTP requests by route and status"),
+                &["method", "route", "status"],
+            )
+            .expect("valid metric definition"),
+            errors: IntCounterVec::new(
+                Opts::new(
+                    "http_request_errors_total",
+                    "HTTP requests answered with a 5xx status",
+                ),
+                labels,
+            )
+            .expect("valid metric definition"),
+            duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "http_request_duration_seconds",
+                    "Time until the response head was produced",
+                ),
+                labels,
+            )
+            .expect("valid metric definition"),
+            in_flight: IntGaugeVec::new(
+                Opts::new("http_requests_in_flight", "HTTP requests being served"),
+                labels,
+            )
+            .expect("valid metric definition"),
+            rate_limited: IntCounterVec::new(
+                Opts::new(
+                    "http_rate_limit_rejections_total",
+                    "Requests rejected by rate or in-flight limits",
+                ),
+                &["method", "route", "reason"],
+            )
+            .expect("valid metric definition"),
+        }
+    }
+
+    pub fn register(
+        &self,
+        registry: &MetricsRegistry,
+    ) -> Result<(), modkit::metrics::prometheus::Error> {
+        registry.register(self.requests.clone())?;
+        registry.register(self.errors.clone())?;
+        registry.register(self.duration.clone())?;
+        registry.register(self.in_flight.clone())?;
+        registry.register(self.rate_limited.clone())?;
+        Ok(())
+    }
+
+    pub fn rate_limited(&self, method: &Method, route: &str, reason: RejectReason) {
+        self.rate_limited
+            .with_label_values(&[method.as_str(), route, reason.as_str()])
+            .inc();
+    }
+}
+
+impl Default for HttpMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decrements the in-flight gauge even when the request future is dropped.
+struct InFlight(IntGauge);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+pub async fn metrics_middleware(metrics: HttpMetrics, req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let labels = [method.as_str(), route.as_str()];
+
+    let gauge = metrics.in_flight.with_label_values(&labels);
+    gauge.inc();
+    let _in_flight = InFlight(gauge);
+    let start = Instant::now();
+
+    let resp = next.run(req).await;
+
+    let status = resp.status();
+    metrics
+        .duration
+        .with_label_values(&labels)
+        .observe(start.elapsed().as_secs_f64());
+    metrics
+        .requests
+        .with_label_values(&[method.as_str(), route.as_str(), status.as_str()])
+        .inc();
+    if status.is_server_error() {
+        metrics.errors.with_label_values(&labels).inc();
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_requests_are_labelled_by_route_template() {
+        let registry = MetricsRegistry::new();
+        let metrics = HttpMetrics::new();
+        metrics.register(&registry).unwrap();
+
+        let m = metrics.clone();
+        let router = Router::new()
+            .route("/users/{id}", get(|| async { "ok" }))
+            .route("/boom", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .layer(axum::middleware::from_fn(
+                move |req: Request, next: Next| metrics_middleware(m.clone(), req, next),
+            ));
+
+        for uri in ["/users/1", "/users/2", "/boom"] {
+            router
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+        metrics.rate_limited(&Method::GET, "/users/{id}", RejectReason::Rate);
+
+        let text = registry.render();
+        assert!(text
+            .contains(r#"http_requests_total{method="GET",route="/users/{id}",status="200"} 2"#));
+        assert!(text.contains(r#"http_request_errors_total{method="GET",route="/boom"} 1"#));
+        assert!(text.contains(r#"http_requests_in_flight{method="GET",route="/boom"} 0"#));
+        assert!(text.contains(
+            r#"http_rate_limit_rejections_total{method="GET",reason="rate",route="/users/{id}"} 1"#
+        ));
+    }
+}
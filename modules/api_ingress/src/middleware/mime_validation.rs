@@ -147,6 +147,14 @@ mod tests {
             sec_requirement: None,
             is_public: false,
             rate_limit: None,
+            rate_limit_partition: None,
+            idempotent: false,
+            version: None,
+            deprecation: None,
+            websocket: None,
+            sse_event_name: None,
+            batch_atomic: false,
+            timeout: None,
             allowed_request_content_types: Some(vec!["multipart/form-data", "application/pdf"]),
         }];
 
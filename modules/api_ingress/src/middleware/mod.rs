@@ -1,3 +1,24 @@
+pub mod access_log;
+pub mod compression;
+pub mod drain;
+pub mod idempotency;
+pub mod metrics;
 pub mod mime_validation;
 pub mod rate_limit;
 pub mod request_id;
+pub mod timeout;
+pub mod validation;
+pub mod versioning;
+
+use axum::body::{to_bytes, Body, Bytes};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+/// Read a whole request body for a middleware that needs it before the handler does.
+/// `defaults.body_limit_bytes` is enforced by the body limit layers further out; exceeding
+/// it is the only failure a connected client can observe, so every error becomes 413.
+pub(crate) async fn buffer_request_body(body: Body) -> Result<Bytes, Response> {
+    to_bytes(body, usize::MAX)
+        .await
+        .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE.into_response())
+}
@@ -1,33 +1,62 @@
+//! Per-route rate limiting and in-flight limits
+//!
+//! A route's token buckets are partitioned by its [`RateLimitPartition`]: a single shared
+//! bucket, or one bucket per client IP, subject, tenant or API key. Partitioned buckets live in
+//! a bounded map that forgets idle clients. With the `db` backend the buckets become
+//! fixed-window counters in the module database, so a limit holds across replicas.
+//!
+//! In-flight limits always apply per route and per process.
 use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use axum::http::{Method, StatusCode};
+use axum::extract::ConnectInfo;
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
 use axum::{
     extract::Request,
     middleware::Next,
     response::{IntoResponse, Response},
 };
+use ipnet::IpNet;
+use modkit::api::RateLimitPartition;
+use modkit_security::SecurityCtx;
 use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
 use tokio::sync::Semaphore;
 
-use crate::config::ApiIngressConfig;
+use crate::config::{ApiIngressConfig, RateLimitConfig, TenantRateLimit};
+use crate::errors::ErrorCode;
+use crate::middleware::metrics::{HttpMetrics, RejectReason};
+use crate::storage::RateLimitStore;
 
 type RateLimitKey = (Method, String);
-type BucketMap = Arc<HashMap<RateLimitKey, Arc<Mutex<TokenBucket>>>>;
-type InflightMap = Arc<HashMap<RateLimitKey, Arc<Semaphore>>>;
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// `Retry-After` for requests rejected by the in-flight limit; slots free up quickly.
+const OVERLOAD_RETRY_AFTER_SECS: u64 = 1;
 
 #[derive(Default, Clone)]
 pub struct RateLimiterMap {
-    buckets: BucketMap,
-    inflight: InflightMap,
+    routes: Arc<HashMap<RateLimitKey, Arc<RouteLimiter>>>,
+    clients: Arc<ClientIdentity>,
+    tenant_overrides: Arc<HashMap<String, TenantRateLimit>>,
+    store: Option<Arc<RateLimitStore>>,
+    metrics: Option<HttpMetrics>,
 }
 
 impl RateLimiterMap {
-    pub fn from_specs(specs: &Vec<modkit::api::OperationSpec>, cfg: &ApiIngressConfig) -> Self {
-        let mut buckets = HashMap::new();
-        let mut inflight = HashMap::new();
-        // TODO: Add support for per-route rate limiting
+    pub fn from_specs(
+        specs: &Vec<modkit::api::OperationSpec>,
+        cfg: &ApiIngressConfig,
+    ) -> anyhow::Result<Self> {
+        let rl = &cfg.rate_limit;
+        let mut routes = HashMap::new();
         for spec in specs {
             let (rps, burst, in_flight) = spec
                 .rate_limit
@@ -38,18 +67,64 @@ impl RateLimiterMap {
                     cfg.defaults.rate_limit.burst,
                     cfg.defaults.rate_limit.in_flight,
                 ));
-            let key = (spec.method.clone(), spec.path.clone());
-            buckets.insert(
-                key.clone(),
-                Arc::new(Mutex::new(TokenBucket::new(rps, burst))),
+            let id = format!("{} {}", spec.method, spec.path);
+            // Operator config wins over the operation's own choice
+            let partition = rl
+                .routes
+                .get(&id)
+                .copied()
+                .or(spec.rate_limit_partition)
+                .unwrap_or(cfg.defaults.rate_limit.partition);
+            routes.insert(
+                (spec.method.clone(), spec.path.clone()),
+                Arc::new(RouteLimiter {
+                    id,
+                    rps,
+                    burst,
+                    partition,
+                    buckets: PartitionBuckets::new(
+                        rl.max_partitions,
+                        Duration::from_secs(rl.idle_ttl_secs),
+                    ),
+                    inflight: Arc::new(Semaphore::new(in_flight as usize)),
+                }),
             );
-            inflight.insert(key, Arc::new(Semaphore::new(in_flight as usize)));
         }
-        Self {
-            buckets: Arc::new(buckets),
-            inflight: Arc::new(inflight),
+        Ok(Self {
+            routes: Arc::new(routes),
+            clients: Arc::new(ClientIdentity::from_config(rl)?),
+            tenant_overrides: Arc::new(rl.tenant_overrides.clone()),
+            store: None,
+            metrics: None,
+        })
+    }
+
+    /// Keep buckets in the shared database instead of process memory.
+    pub fn with_store(mut self, store: Arc<RateLimitStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Count rejections in `http_rate_limit_rejections_total`.
+    pub fn with_metrics(mut self, metrics: HttpMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn record_rejection(&self, key: &RateLimitKey, reason: RejectReason) {
+        if let Some(metrics) = &self.metrics {
+            metrics.rate_limited(&key.0, &key.1, reason);
         }
     }
+
+    /// Steady-state rate for `partition_key`, honouring tenant overrides.
+    fn limits(&self, route: &RouteLimiter, partition_key: &str) -> (u32, u32) {
+        partition_key
+            .strip_prefix("tenant:")
+            .and_then(|tenant| self.tenant_overrides.get(tenant))
+            .map(|o| (o.rps, o.burst))
+            .unwrap_or((route.rps, route.burst))
+    }
 }
 
 // TODO: Use tower-governor instead of own implementation
@@ -63,26 +138,283 @@ pub async fn rate_limit_middleware(map: RateLimiterMap, req: Request, next: Next
         .unwrap_or_else(|| req.uri().path().to_string());
     let key = (method, path);
 
-    if let Some(bucket) = map.buckets.get(&key) {
-        let mut b = bucket.lock();
-        if !b.allow_now() {
-            return StatusCode::TOO_MANY_REQUESTS.into_response();
+    let Some(route) = map.routes.get(&key).cloned() else {
+        return next.run(req).await;
+    };
+
+    let partition_key = map.clients.partition_key(route.partition, &req);
+    let (rps, burst) = map.limits(&route, &partition_key);
+    let quota = match &map.store {
+        Some(store) => allow_shared(store, &route.id, &partition_key, rps, burst).await,
+        None => Some(route.buckets.allow(&partition_key, rps, burst)),
+    };
+    if let Some(quota) = quota.filter(|q| !q.allowed) {
+        map.record_rejection(&key, RejectReason::Rate);
+        let mut resp = ErrorCode::api_ingress_errors_rate_limited_v1()
+            .to_problem(format!(
+                "Rate limit of {} requests exceeded; retry in {} s",
+                quota.limit, quota.retry_after_secs
+            ))
+            .into_response();
+        quota.apply(resp.headers_mut());
+        insert_secs(resp.headers_mut(), RETRY_AFTER, quota.retry_after_secs);
+        return resp;
+    }
+
+    match route.inflight.clone().try_acquire_owned() {
+        Ok(_permit) => {
+            // Allow request; permit is dropped when response future completes
+            let mut resp = next.run(req).await;
+            if let Some(quota) = quota {
+                quota.apply(resp.headers_mut());
+            }
+            resp
+        }
+        Err(_) => {
+            map.record_rejection(&key, RejectReason::InFlight);
+            let mut resp = ErrorCode::api_ingress_errors_overloaded_v1()
+                .to_problem("Too many requests in progress for this route")
+                .into_response();
+            insert_secs(resp.headers_mut(), RETRY_AFTER, OVERLOAD_RETRY_AFTER_SECS);
+            resp
         }
     }
+}
+
+/// Outcome of taking one request from a bucket, reported as `RateLimit-*` headers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Quota {
+    allowed: bool,
+    /// Bucket capacity
+    limit: u32,
+    remaining: u32,
+    /// Seconds until the bucket is full again
+    reset_secs: u64,
+    /// Seconds until the next request would be admitted
+    retry_after_secs: u64,
+}
+
+impl Quota {
+    fn apply(&self, headers: &mut HeaderMap) {
+        insert_secs(headers, RATELIMIT_LIMIT, u64::from(self.limit));
+        insert_secs(headers, RATELIMIT_REMAINING, u64::from(self.remaining));
+        insert_secs(headers, RATELIMIT_RESET, self.reset_secs);
+    }
+}
 
-    if let Some(sem) = map.inflight.get(&key) {
-        match sem.clone().try_acquire_owned() {
-            Ok(_permit) => {
-                // Allow request; permit is dropped when response future completes
-                return next.run(req).await;
+fn insert_secs(headers: &mut HeaderMap, name: HeaderName, value: u64) {
+    headers.insert(name, HeaderValue::from(value));
+}
+
+/// Fixed-window approximation of the token bucket: `burst` hits per `burst / rps` seconds.
+///
+/// The database being unavailable must not take the API down, so errors let the request
+/// through without a quota.
+async fn allow_shared(
+    store: &Arc<RateLimitStore>,
+    route_id: &str,
+    partition_key: &str,
+    rps: u32,
+    burst: u32,
+) -> Option<Quota> {
+    let rps = rps.max(1);
+    let limit = burst.max(rps);
+    let window_secs = i64::from(limit.div_ceil(rps));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let window_start = now - now.rem_euclid(window_secs);
+
+    let bucket = digest(&format!("{route_id}|{partition_key}"));
+    let quota = match store.hit(&bucket, window_start).await {
+        Ok(hits) => {
+            // Both the next request and a fresh quota arrive with the next window
+            let window_left = (window_start + window_secs - now).max(1) as u64;
+            Some(Quota {
+                allowed: hits <= i64::from(limit),
+                limit,
+                remaining: u32::try_from(i64::from(limit) - hits).unwrap_or(0),
+                reset_secs: window_left,
+                retry_after_secs: window_left,
+            })
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, route = route_id, "Shared rate limit unavailable; allowing request");
+            None
+        }
+    };
+
+    if store.purge_due(now) {
+        let store = store.clone();
+        tokio::spawn(async move {
+            if let Err(e) = store.purge_stale(now).await {
+                tracing::debug!(error = %e, "Failed to purge expired rate limit windows");
+            }
+        });
+    }
+    quota
+}
+
+struct RouteLimiter {
+    /// `"METHOD /path"`, also used to namespace shared buckets
+    id: String,
+    rps: u32,
+    burst: u32,
+    partition: RateLimitPartition,
+    buckets: PartitionBuckets,
+    inflight: Arc<Semaphore>,
+}
+
+/// Token buckets keyed by partition, bounded in size and dropping idle entries.
+struct PartitionBuckets {
+    inner: Mutex<BucketTable>,
+    max: usize,
+    idle_ttl: Duration,
+}
+
+struct BucketTable {
+    buckets: HashMap<String, TokenBucket>,
+    last_sweep: Instant,
+}
+
+impl PartitionBuckets {
+    fn new(max: usize, idle_ttl: Duration) -> Self {
+        Self {
+            inner: Mutex::new(BucketTable {
+                buckets: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+            max: max.max(1),
+            idle_ttl,
+        }
+    }
+
+    fn allow(&self, key: &str, rps: u32, burst: u32) -> Quota {
+        let mut table = self.inner.lock();
+        if table.last_sweep.elapsed() >= self.idle_ttl {
+            self.sweep(&mut table);
+        }
+        if !table.buckets.contains_key(key) && table.buckets.len() >= self.max {
+            self.sweep(&mut table);
+            if table.buckets.len() >= self.max {
+                // Still full of active clients: forget the one seen least recently
+                let oldest = table
+                    .buckets
+                    .iter()
+                    .min_by_key(|(_, b)| b.last)
+                    .map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    table.buckets.remove(&oldest);
+                }
             }
-            Err(_) => {
-                return StatusCode::SERVICE_UNAVAILABLE.into_response();
+        }
+        table
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(rps, burst))
+            .acquire()
+    }
+
+    fn sweep(&self, table: &mut BucketTable) {
+        let ttl = self.idle_ttl;
+        table.buckets.retain(|_, b| b.last.elapsed() < ttl);
+        table.last_sweep = Instant::now();
+    }
+}
+
+/// Derives partition keys from a request.
+#[derive(Default)]
+struct ClientIdentity {
+    trusted_proxies: Vec<IpNet>,
+    api_key_header: Option<HeaderName>,
+}
+
+impl ClientIdentity {
+    fn from_config(cfg: &RateLimitConfig) -> anyhow::Result<Self> {
+        let trusted_proxies = cfg
+            .trusted_proxies
+            .iter()
+            .map(|p| {
+                p.parse::<IpNet>()
+                    .or_else(|_| p.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| anyhow::anyhow!("invalid trusted proxy '{p}'"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        let api_key_header = HeaderName::try_from(cfg.api_key_header.as_str())
+            .map_err(|e| anyhow::anyhow!("invalid api_key_header '{}': {e}", cfg.api_key_header))?;
+        Ok(Self {
+            trusted_proxies,
+            api_key_header: Some(api_key_header),
+        })
+    }
+
+    /// Bucket key for `partition`. Identity-based partitions fall back to the client IP for
+    /// callers that do not carry that identity (anonymous routes, missing API key).
+    fn partition_key(&self, partition: RateLimitPartition, req: &Request) -> String {
+        let ctx = req.extensions().get::<SecurityCtx>();
+        let key = match partition {
+            RateLimitPartition::Route => return String::new(),
+            RateLimitPartition::ClientIp => None,
+            RateLimitPartition::Subject => ctx
+                .map(|c| c.subject_id())
+                .filter(|id| !id.is_nil())
+                .map(|id| format!("sub:{id}")),
+            RateLimitPartition::Tenant => ctx
+                .and_then(|c| c.scope().tenant_ids().first().copied())
+                .map(|id| format!("tenant:{id}")),
+            RateLimitPartition::ApiKey => self
+                .api_key_header
+                .as_ref()
+                .and_then(|h| req.headers().get(h))
+                .map(|v| format!("key:{}", digest_bytes(v.as_bytes()))),
+        };
+        key.unwrap_or_else(|| match self.client_ip(req) {
+            Some(ip) => format!("ip:{ip}"),
+            None => "ip:unknown".to_string(),
+        })
+    }
+
+    /// Client address: the TCP peer, or — when the peer is a trusted proxy — the right-most
+    /// `X-Forwarded-For` hop that is not itself a trusted proxy.
+    fn client_ip(&self, req: &Request) -> Option<IpAddr> {
+        let peer = req.extensions().get::<ConnectInfo<SocketAddr>>()?.0.ip();
+        if !self.is_trusted(peer) {
+            return Some(peer);
+        }
+        let forwarded: Vec<IpAddr> = req
+            .headers()
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|hop| hop.trim().parse().ok())
+            .collect();
+        let mut client = peer;
+        for hop in forwarded.into_iter().rev() {
+            client = hop;
+            if !self.is_trusted(hop) {
+                break;
             }
         }
+        Some(client)
     }
 
-    next.run(req).await
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+}
+
+fn digest(value: &str) -> String {
+    digest_bytes(value.as_bytes())
+}
+
+/// Hex SHA-256, so raw API keys are never kept as map or table keys.
+fn digest_bytes(value: &[u8]) -> String {
+    Sha256::digest(value)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
 }
 
 struct TokenBucket {
@@ -103,16 +435,126 @@ impl TokenBucket {
         }
     }
 
-    fn allow_now(&mut self) -> bool {
+    fn acquire(&mut self) -> Quota {
         let now = Instant::now();
         let elapsed = now.duration_since(self.last).as_secs_f64();
         self.last = now;
         self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity as f64);
-        if self.tokens >= 1.0 {
+        let allowed = self.tokens >= 1.0;
+        if allowed {
             self.tokens -= 1.0;
-            true
-        } else {
-            false
         }
+        let secs_until = |tokens: f64| (tokens.max(0.0) / self.refill_per_sec).ceil() as u64;
+        Quota {
+            allowed,
+            limit: self.capacity,
+            remaining: self.tokens.floor() as u32,
+            reset_secs: secs_until(self.capacity as f64 - self.tokens),
+            // Retry-After must be at least one second to be meaningful
+            retry_after_secs: secs_until(1.0 - self.tokens).max(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use uuid::Uuid;
+
+    fn request(peer: &str, xff: Option<&str>) -> Request {
+        let mut req = Request::builder().uri("/x");
+        if let Some(xff) = xff {
+            req = req.header(X_FORWARDED_FOR, xff);
+        }
+        let mut req = req.body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        req
+    }
+
+    fn identity(trusted: &[&str]) -> ClientIdentity {
+        ClientIdentity::from_config(&RateLimitConfig {
+            trusted_proxies: trusted.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_client_ip_trusts_forwarded_only_from_proxies() {
+        let ids = identity(&["10.0.0.0/8", "192.168.1.1"]);
+
+        // Untrusted peer: spoofed header is ignored
+        let req = request("203.0.113.9:5000", Some("1.2.3.4"));
+        assert_eq!(ids.client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+
+        // Trusted chain: right-most untrusted hop is the client
+        let req = request("10.1.2.3:5000", Some("1.2.3.4, 198.51.100.7, 192.168.1.1"));
+        assert_eq!(ids.client_ip(&req), Some("198.51.100.7".parse().unwrap()));
+
+        assert!(ClientIdentity::from_config(&RateLimitConfig {
+            trusted_proxies: vec!["not-an-ip".into()],
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_tenant_partition_falls_back_to_ip() {
+        let ids = identity(&[]);
+        let tenant = Uuid::new_v4();
+
+        let mut req = request("203.0.113.9:5000", None);
+        assert_eq!(
+            ids.partition_key(RateLimitPartition::Tenant, &req),
+            "ip:203.0.113.9"
+        );
+
+        req.extensions_mut()
+            .insert(SecurityCtx::for_tenant(tenant, Uuid::new_v4()));
+        assert_eq!(
+            ids.partition_key(RateLimitPartition::Tenant, &req),
+            format!("tenant:{tenant}")
+        );
+        assert_eq!(ids.partition_key(RateLimitPartition::Route, &req), "");
+    }
+
+    #[test]
+    fn test_partitions_have_separate_buckets_and_stay_bounded() {
+        let buckets = PartitionBuckets::new(2, Duration::from_secs(600));
+        assert!(buckets.allow("a", 1, 1).allowed);
+        assert!(!buckets.allow("a", 1, 1).allowed);
+        // A noisy client does not consume another client's budget
+        assert!(buckets.allow("b", 1, 1).allowed);
+
+        // Map is full: the least recently seen client is evicted
+        assert!(buckets.allow("c", 1, 1).allowed);
+        let table = buckets.inner.lock();
+        assert_eq!(table.buckets.len(), 2);
+        assert!(!table.buckets.contains_key("a"));
+    }
+
+    #[test]
+    fn test_quota_reports_remaining_and_reset() {
+        let mut bucket = TokenBucket::new(1, 3);
+        let q = bucket.acquire();
+        assert!(q.allowed);
+        assert_eq!((q.limit, q.remaining), (3, 2));
+        assert_eq!(q.reset_secs, 1);
+
+        bucket.acquire();
+        bucket.acquire();
+        let q = bucket.acquire();
+        assert!(!q.allowed);
+        assert_eq!(q.remaining, 0);
+        assert_eq!(q.retry_after_secs, 1);
+        assert_eq!(q.reset_secs, 3);
+
+        let mut headers = HeaderMap::new();
+        q.apply(&mut headers);
+        assert_eq!(headers[RATELIMIT_LIMIT], "3");
+        assert_eq!(headers[RATELIMIT_REMAINING], "0");
+        assert_eq!(headers[RATELIMIT_RESET], "3");
     }
 }
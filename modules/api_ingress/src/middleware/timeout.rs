@@ -0,0 +1,161 @@
+//! Per-operation request timeouts
+//!
+//! Each operation uses `OperationSpec::timeout` or the `defaults.request_timeout_ms` fallback.
+//! Operations answering with `text/event-stream` or `application/octet-stream` are streaming
+//! and exempt: their handlers may legitimately hold the request open.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::Method;
+
+use modkit::api::OperationSpec;
+
+use crate::errors::ErrorCode;
+
+/// Response content types that mark an operation as streaming.
+const STREAMING_CONTENT_TYPES: &[&str] = &["text/event-stream", "application/octet-stream"];
+
+/// Whether the operation answers with a stream rather than a bounded body.
+pub fn is_streaming(spec: &OperationSpec) -> bool {
+    spec.responses
+        .iter()
+        .any(|r| STREAMING_CONTENT_TYPES.contains(&r.content_type))
+}
+
+/// Map from (method, path) to the operation's timeout; `None` means exempt.
+pub type TimeoutMap = Arc<HashMap<(Method, String), Option<Duration>>>;
+
+/// Build the timeout map from operation specs
+pub fn build_timeout_map(specs: &[OperationSpec], default: Duration) -> TimeoutMap {
+    let map = specs
+        .iter()
+        .map(|spec| {
+            let timeout = (!is_streaming(spec)).then(|| spec.timeout.unwrap_or(default));
+            ((spec.method.clone(), spec.path.clone()), timeout)
+        })
+        .collect();
+    Arc::new(map)
+}
+
+/// Timeout middleware
+///
+/// Routes without an operation spec (docs, probes) use `default`. On expiry the handler future
+/// is dropped and a problem+json 504 is returned.
+pub async fn timeout_middleware(
+    map: TimeoutMap,
+    default: Duration,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    // Use MatchedPath extension (set by Axum router) for accurate route matching
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let limit = match map.get(&(method.clone(), path.clone())) {
+        Some(Some(limit)) => *limit,
+        Some(None) => return next.run(req).await,
+        None => default,
+    };
+
+    match tokio::time::timeout(limit, next.run(req)).await {
+        Ok(resp) => resp,
+        Err(_) => {
+            tracing::warn!(
+                method = %method,
+                path = %path,
+                timeout_ms = limit.as_millis() as u64,
+                "Request timed out"
+            );
+            ErrorCode::api_ingress_errors_request_timeout_v1()
+                .to_problem(format!(
+                    "Request did not complete within {} ms",
+                    limit.as_millis()
+                ))
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::{middleware::from_fn, routing::get, Router};
+    use modkit::api::{OpenApiRegistryImpl, OperationBuilder};
+    use tower::ServiceExt;
+
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        "done"
+    }
+
+    fn app() -> Router {
+        let registry = OpenApiRegistryImpl::new();
+        let router = OperationBuilder::get("/fast")
+            .operation_id("test.fast")
+            .public()
+            .timeout(Duration::from_millis(20))
+            .handler(slow)
+            .json_response(StatusCode::OK, "ok")
+            .register(Router::new(), &registry);
+        let router = OperationBuilder::get("/slow")
+            .operation_id("test.slow")
+            .public()
+            .timeout(Duration::from_secs(5))
+            .handler(slow)
+            .json_response(StatusCode::OK, "ok")
+            .register(router, &registry);
+
+        let specs: Vec<_> = registry
+            .operation_specs
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        let map = build_timeout_map(&specs, Duration::from_secs(30));
+        router
+            .route("/unregistered", get(slow))
+            .layer(from_fn(move |req: Request, next: Next| {
+                timeout_middleware(map.clone(), Duration::from_millis(20), req, next)
+            }))
+    }
+
+    async fn call(uri: &str) -> Response {
+        app()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_operation_timeout_returns_problem() {
+        let resp = call("/fast").await;
+        assert_eq!(resp.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(
+            resp.headers()[http::header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            problem["code"],
+            "gts.hx.core.errors.err.v1~hx.api_ingress.errors.request_timeout.v1"
+        );
+
+        assert_eq!(call("/slow").await.status(), StatusCode::OK);
+        assert_eq!(
+            call("/unregistered").await.status(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+    }
+}
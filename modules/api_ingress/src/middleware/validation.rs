@@ -0,0 +1,305 @@
+//! JSON Schema validation of request bodies
+//!
+//! Operations declaring a JSON body with `json_request::<T>()` are checked against `T`'s
+//! component schema before the handler runs. Each schema is compiled once, inside a document
+//! carrying all registered components so `$ref`s between them resolve. Bodies of other content
+//! types and operations without a registered schema pass through untouched.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{MatchedPath, Request};
+use axum::http::{header, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use jsonschema::error::ValidationErrorKind;
+use modkit::api::operation_builder::RequestBodySchema;
+use modkit::api::{OperationSpec, ValidationViolation};
+use serde_json::{json, Value};
+use utoipa::openapi::{schema::Schema, RefOr};
+
+use crate::config::RequestValidationConfig;
+use crate::errors::ErrorCode;
+
+struct BodySchema {
+    name: String,
+    validator: jsonschema::Validator,
+    required: bool,
+}
+
+/// Compiled request body schemas keyed by (method, path).
+pub struct RequestValidators {
+    schemas: HashMap<(Method, String), BodySchema>,
+    max_errors: usize,
+}
+
+impl RequestValidators {
+    pub fn from_specs(
+        specs: &[OperationSpec],
+        components: &HashMap<String, RefOr<Schema>>,
+        cfg: &RequestValidationConfig,
+    ) -> Self {
+        let components = match serde_json::to_value(components) {
+            Ok(components) => components,
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    "Failed to serialize component schemas; request bodies not validated"
+                );
+                Value::Null
+            }
+        };
+
+        let mut schemas = HashMap::new();
+        for spec in specs {
+            let Some(body) = &spec.request_body else {
+                continue;
+            };
+            let RequestBodySchema::Ref { schema_name } = &body.schema else {
+                continue;
+            };
+            if !is_json(body.content_type) || components.get(schema_name).is_none() {
+                continue;
+            }
+            let root = json!({
+                "$ref": format!("#/components/schemas/{schema_name}"),
+                "components": { "schemas": components },
+            });
+            match jsonschema::options()
+                .with_draft(jsonschema::Draft::Draft202012)
+                .should_validate_formats(true)
+                .build(&root)
+            {
+                Ok(validator) => {
+                    schemas.insert(
+                        (spec.method.clone(), spec.path.clone()),
+                        BodySchema {
+                            name: schema_name.clone(),
+                            validator,
+                            required: body.required,
+                        },
+                    );
+                }
+                Err(e) => tracing::warn!(
+                    method = %spec.method,
+                    path = %spec.path,
+                    schema = %schema_name,
+                    error = %e,
+                    "Request schema does not compile; body not validated"
+                ),
+            }
+        }
+
+        Self {
+            schemas,
+            max_errors: cfg.max_errors.max(1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+}
+
+/// `application/json` and `+json` media types.
+fn is_json(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    essence.eq_ignore_ascii_case("application/json") || essence.ends_with("+json")
+}
+
+pub async fn request_validation_middleware(
+    validators: Arc<RequestValidators>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(schema) = req.extensions().get::<MatchedPath>().and_then(|p| {
+        validators
+            .schemas
+            .get(&(req.method().clone(), p.as_str().to_string()))
+    }) else {
+        return next.run(req).await;
+    };
+    let json_body = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(is_json);
+    if !json_body {
+        // Missing or other content types are the handler's (or MIME validation's) call
+        return next.run(req).await;
+    }
+
+    let (parts, body) = req.into_parts();
+    let body = match super::buffer_request_body(body).await {
+        Ok(body) => body,
+        Err(resp) => return resp,
+    };
+
+    let violations = if body.is_empty() {
+        if !schema.required {
+            return next.run(Request::from_parts(parts, Body::from(body))).await;
+        }
+        vec![ValidationViolation {
+            pointer: String::new(),
+            message: "request body is required".to_string(),
+            code: Some("required".to_string()),
+        }]
+    } else {
+        let value: Value = match serde_json::from_slice(&body) {
+            Ok(value) => value,
+            Err(e) => {
+                return ErrorCode::api_ingress_errors_malformed_json_body_v1()
+                    .to_problem(format!("Request body is not valid JSON: {e}"))
+                    .into_response();
+            }
+        };
+        schema
+            .validator
+            .iter_errors(&value)
+            .take(validators.max_errors)
+            .map(|e| violation(&e))
+            .collect()
+    };
+
+    if !violations.is_empty() {
+        return ErrorCode::api_ingress_errors_request_validation_failed_v1()
+            .to_problem(format!(
+                "Request body does not match the {} schema",
+                schema.name
+            ))
+            .with_errors(violations)
+            .into_response();
+    }
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+/// A violation points at the offending value; a missing property at where it should be.
+fn violation(err: &jsonschema::ValidationError<'_>) -> ValidationViolation {
+    let mut pointer = err.instance_path.to_string();
+    if let ValidationErrorKind::Required { property } = &err.kind {
+        if let Some(name) = property.as_str() {
+            pointer.push('/');
+            pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+        }
+    }
+    let schema_path = err.schema_path.to_string();
+    ValidationViolation {
+        pointer,
+        message: err.to_string(),
+        // The failing keyword: required, format, enum, minLength, pattern, ...
+        code: schema_path.rsplit('/').next().map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use axum::http::StatusCode;
+    use axum::middleware::from_fn;
+    use axum::Router;
+    use modkit::api::{OpenApiRegistryImpl, OperationBuilder};
+    use tower::ServiceExt;
+
+    #[derive(serde::Deserialize, utoipa::ToSchema)]
+    #[serde(rename_all = "lowercase")]
+    #[allow(dead_code)]
+    enum Role {
+        Admin,
+        Viewer,
+    }
+
+    #[derive(serde::Deserialize, utoipa::ToSchema)]
+    #[allow(dead_code)]
+    struct NewUser {
+        #[schema(min_length = 3, pattern = "^[a-z]+$")]
+        name: String,
+        role: Role,
+        #[schema(format = DateTime)]
+        starts_at: Option<String>,
+    }
+
+    fn app() -> Router {
+        let registry = OpenApiRegistryImpl::new();
+        let router = OperationBuilder::post("/users")
+            .operation_id("test.create_user")
+            .public()
+            .json_request::<NewUser>(&registry, "New user")
+            .handler(|| async { StatusCode::CREATED })
+            .json_response(StatusCode::CREATED, "created")
+            .register(Router::new(), &registry);
+
+        let specs: Vec<_> = registry
+            .operation_specs
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        let validators = Arc::new(RequestValidators::from_specs(
+            &specs,
+            &registry.components_registry.load(),
+            &RequestValidationConfig::default(),
+        ));
+        assert!(!validators.is_empty());
+        router.layer(from_fn(move |req: Request, next: Next| {
+            request_validation_middleware(validators.clone(), req, next)
+        }))
+    }
+
+    async fn post(body: &str) -> (StatusCode, Value) {
+        let resp = app()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/users")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn pointers(problem: &Value) -> Vec<&str> {
+        let mut pointers: Vec<&str> = problem["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["pointer"].as_str().unwrap())
+            .collect();
+        pointers.sort();
+        pointers
+    }
+
+    #[tokio::test]
+    async fn test_valid_body_reaches_handler() {
+        let (status, _) = post(r#"{"name":"alice","role":"admin"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_violations_point_at_fields() {
+        let (status, problem) =
+            post(r#"{"name":"Al","role":"root","starts_at":"yesterday"}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            problem["code"],
+            "gts.hx.core.errors.err.v1~hx.api_ingress.errors.request_validation_failed.v1"
+        );
+        // minLength and pattern on /name, enum on /role, format on /starts_at
+        assert_eq!(
+            pointers(&problem),
+            vec!["/name", "/name", "/role", "/starts_at"]
+        );
+
+        let (status, problem) = post(r#"{"role":"viewer"}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(pointers(&problem), vec!["/name"]);
+        assert_eq!(problem["errors"][0]["code"], "required");
+
+        let (status, _) = post("{not json").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,249 @@
+//! API versions and deprecation notices declared on operations
+//!
+//! Responses of deprecated operations carry `Deprecation: true`, plus `Sunset` (RFC 8594) and
+//! `Link: <...>; rel="successor-version"` when the spec names them.
+//!
+//! Every versioned operation is routed under its `/{version}` prefix. For operations versioned
+//! by media type, [`route_by_media_type`] rewrites a plain-path request whose `Accept` (or
+//! `Content-Type`) carries a `version` parameter to that prefix before the router sees it.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::{header, uri::PathAndQuery, HeaderName, HeaderValue, Method, Uri};
+use axum::middleware::{from_fn, Next};
+use axum::response::Response;
+use axum::Router;
+use modkit::api::{ApiVersionSpec, OperationSpec, VersionRouting};
+use tower::Layer;
+
+/// Map from (method, path) to the headers announcing the operation's deprecation.
+pub type DeprecationMap = Arc<HashMap<(Method, String), Vec<(HeaderName, HeaderValue)>>>;
+
+/// Build the deprecation header map from operation specs
+pub fn build_deprecation_map(specs: &[OperationSpec]) -> DeprecationMap {
+    let map = specs
+        .iter()
+        .filter_map(|spec| {
+            let deprecation = spec.deprecation.as_ref()?;
+            let mut headers = vec![(
+                HeaderName::from_static("deprecation"),
+                HeaderValue::from_static("true"),
+            )];
+            if let Some(sunset) = deprecation.sunset {
+                let date = sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+                headers.push((
+                    HeaderName::from_static("sunset"),
+                    HeaderValue::from_str(&date).expect("HTTP date is a valid header value"),
+                ));
+            }
+            if let Some(successor) = &deprecation.successor {
+                match HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\"")) {
+                    Ok(link) => headers.push((header::LINK, link)),
+                    Err(_) => tracing::warn!(
+                        path = %spec.path,
+                        successor = %successor,
+                        "Successor link is not a valid header value; not announced"
+                    ),
+                }
+            }
+            Some(((spec.method.clone(), spec.path.clone()), headers))
+        })
+        .collect();
+    Arc::new(map)
+}
+
+/// Adds the deprecation headers of the matched operation to its responses.
+pub async fn deprecation_middleware(map: DeprecationMap, req: Request, next: Next) -> Response {
+    let headers = req
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|p| map.get(&(req.method().clone(), p.as_str().to_string())))
+        .cloned();
+
+    let mut resp = next.run(req).await;
+    for (name, value) in headers.into_iter().flatten() {
+        resp.headers_mut().insert(name, value);
+    }
+    resp
+}
+
+/// Plain-path templates of the operations versioned by media type, per version.
+#[derive(Clone, Default)]
+pub struct MediaTypeVersions {
+    versions: Arc<Vec<(ApiVersionSpec, matchit::Router<()>)>>,
+}
+
+impl MediaTypeVersions {
+    pub fn from_specs(specs: &[OperationSpec]) -> Self {
+        let mut versions: Vec<(ApiVersionSpec, matchit::Router<()>)> = Vec::new();
+        for spec in specs {
+            let Some(version) = &spec.version else {
+                continue;
+            };
+            if version.routing != VersionRouting::MediaType {
+                continue;
+            }
+            let idx = match versions.iter().position(|(v, _)| v.name == version.name) {
+                Some(idx) => idx,
+                None => {
+                    versions.push((version.clone(), matchit::Router::new()));
+                    versions.len() - 1
+                }
+            };
+            // Other methods on the same path are already in the matcher
+            let _ = versions[idx].1.insert(spec.unversioned_path(), ());
+        }
+        Self {
+            versions: Arc::new(versions),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// Prefix the request path with the version its media type asks for, if an operation of
+    /// that version serves the path.
+    fn rewrite(&self, req: &mut Request) {
+        let Some(requested) = requested_version(req.headers()) else {
+            return;
+        };
+        let path = req.uri().path();
+        let Some((version, _)) = self
+            .versions
+            .iter()
+            .find(|(v, matcher)| v.matches_param(&requested) && matcher.at(path).is_ok())
+        else {
+            return;
+        };
+
+        let target = match req.uri().query() {
+            Some(q) => format!("/{}{}?{}", version.name, path, q),
+            None => format!("/{}{}", version.name, path),
+        };
+        let mut parts = req.uri().clone().into_parts();
+        parts.path_and_query = PathAndQuery::try_from(target).ok();
+        if let Ok(uri) = Uri::from_parts(parts) {
+            *req.uri_mut() = uri;
+        }
+    }
+}
+
+/// `version` parameter of the first `Accept` media range (or of `Content-Type`) that has one.
+fn requested_version(headers: &header::HeaderMap) -> Option<String> {
+    [header::ACCEPT, header::CONTENT_TYPE]
+        .iter()
+        .filter_map(|name| headers.get(name)?.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .flat_map(|media| media.split(';').skip(1))
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("version"))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+/// Wrap `router` so media-type version selection happens before routing.
+///
+/// Layers added with `Router::layer` run after a route was matched, so the rewrite has to wrap
+/// the whole router; an empty router forwarding everything to it keeps the `Router` type.
+pub fn route_by_media_type(router: Router, versions: MediaTypeVersions) -> Router {
+    let rewrite = from_fn(move |mut req: Request, next: Next| {
+        versions.rewrite(&mut req);
+        next.run(req)
+    });
+    Router::new().fallback_service(rewrite.layer(router))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use modkit::api::{OpenApiRegistryImpl, OperationBuilder};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let registry = OpenApiRegistryImpl::new();
+        let router = OperationBuilder::get("/users")
+            .operation_id("test.users_v1")
+            .version_by_media_type("v1")
+            .deprecated(Some(chrono::DateTime::UNIX_EPOCH))
+            .successor_version("/v2/users")
+            .public()
+            .handler(|| async { "v1" })
+            .json_response(StatusCode::OK, "ok")
+            .register(Router::new(), &registry);
+        let router = OperationBuilder::get("/users")
+            .operation_id("test.users_v2")
+            .version_by_media_type("v2")
+            .public()
+            .handler(|| async { "v2" })
+            .json_response(StatusCode::OK, "ok")
+            .register(router, &registry);
+
+        let specs: Vec<_> = registry
+            .operation_specs
+            .iter()
+            .map(|e| e.value().clone())
+            .collect();
+        let map = build_deprecation_map(&specs);
+        let router = router.layer(from_fn(move |req: Request, next: Next| {
+            deprecation_middleware(map.clone(), req, next)
+        }));
+        route_by_media_type(router, MediaTypeVersions::from_specs(&specs))
+    }
+
+    async fn call(uri: &str, accept: Option<&str>) -> (StatusCode, http::HeaderMap, String) {
+        let mut req = Request::builder().uri(uri);
+        if let Some(accept) = accept {
+            req = req.header(header::ACCEPT, accept);
+        }
+        let resp = app()
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let (parts, body) = resp.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (
+            parts.status,
+            parts.headers,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_media_type_parameter_selects_version() {
+        let (status, _, body) = call("/users", Some("application/json; version=2")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "v2");
+
+        let (_, _, body) = call(
+            "/users?x=1",
+            Some("text/plain, application/json;version=v1"),
+        )
+        .await;
+        assert_eq!(body, "v1");
+
+        // The prefixed path works without negotiation; the plain path needs it
+        assert_eq!(call("/v2/users", None).await.2, "v2");
+        assert_eq!(call("/users", None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(
+            call("/users", Some("application/json; version=3")).await.0,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_operation_announces_sunset() {
+        let (_, headers, _) = call("/v1/users", None).await;
+        assert_eq!(headers["deprecation"], "true");
+        assert_eq!(headers["sunset"], "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(
+            headers[header::LINK],
+            "</v2/users>; rel=\"successor-version\""
+        );
+
+        let (_, headers, _) = call("/v2/users", None).await;
+        assert!(headers.get("deprecation").is_none());
+    }
+}
@@ -0,0 +1,98 @@
+//! Connection serving with a hard stop
+//!
+//! `axum::serve` runs every connection on a detached task, so connections still busy when
+//! the drain deadline passes would outlive the server. Here the connection tasks belong to
+//! the server future: graceful shutdown waits for them, and dropping the future aborts them
+//! and closes their sockets.
+
+use std::net::SocketAddr;
+
+use axum::extract::ConnectInfo;
+use axum::serve::Listener;
+use axum::{Extension, Router};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+/// Serve `router` on `listener` until `stop_accepting` fires, then wait for the open
+/// connections to finish their requests. Dropping the future force-closes them.
+pub async fn serve<L>(mut listener: L, router: Router, stop_accepting: CancellationToken)
+where
+    L: Listener<Addr = SocketAddr>,
+{
+    let builder = Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+    let mut connections = JoinSet::new();
+
+    loop {
+        let (io, peer) = tokio::select! {
+            conn = listener.accept() => conn,
+            _ = stop_accepting.cancelled() => break,
+        };
+        // Reap finished connections so the set does not grow with every accepted one
+        while connections.try_join_next().is_some() {}
+
+        let svc = router.clone().layer(Extension(ConnectInfo(peer)));
+        let conn = builder
+            .serve_connection_with_upgrades(TokioIo::new(io), TowerToHyperService::new(svc))
+            .into_owned();
+        let conn = graceful.watch(conn);
+        connections.spawn(async move {
+            if let Err(e) = conn.await {
+                tracing::debug!(error = %e, peer = %peer, "Connection closed with an error");
+            }
+        });
+    }
+
+    drop(listener);
+    // Idle keep-alive connections close now, busy ones after their current request
+    graceful.shutdown().await;
+    while connections.join_next().await.is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    async fn start(stop: &CancellationToken) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+        let router = Router::new().route("/fast", get(|| async { "ok" }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve(listener, router, stop.clone()));
+        (addr, server)
+    }
+
+    async fn send(addr: SocketAddr, path: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nhost: test\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_stop_does_not_wait_for_idle_keep_alive_connections() {
+        let stop = CancellationToken::new();
+        let (addr, server) = start(&stop).await;
+
+        let mut stream = send(addr, "/fast").await;
+        let mut buf = [0u8; 256];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf[..n]).starts_with("HTTP/1.1 200"));
+
+        // The keep-alive connection is idle, so stopping does not wait for it
+        stop.cancel();
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("server did not stop")
+            .unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use sea_orm_migration::prelude::*;
+
+use super::migrations::ApiIngressIdempotencyKeys as Keys;
+
+/// How often a purge may run.
+const PURGE_INTERVAL_SECS: i64 = 60;
+
+/// Response captured for replay.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// One holder's claim on a key. A later claim of the same key only succeeds once this one's
+/// lock has expired, so it always carries a later deadline; completing or releasing with a
+/// stale token therefore never touches the new holder's entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimToken {
+    key: String,
+    fingerprint: String,
+    lock_until: i64,
+}
+
+/// Result of trying to take an idempotency key.
+#[derive(Debug)]
+pub enum Claim {
+    /// The key was free; the caller runs the request and then completes or releases it
+    Acquired(ClaimToken),
+    /// Another request holds the key; `response` is `None` while it is still running
+    Held {
+        fingerprint: String,
+        response: Option<StoredResponse>,
+    },
+}
+
+/// `api_ingress_idempotency_keys` table access. Keys are hashed together with the caller's
+/// identity before they get here, so one caller can never see another caller's responses.
+/// The table is system-owned and not tenant-scoped.
+pub struct IdempotencyStore {
+    conn: DatabaseConnection,
+    last_purge: AtomicI64,
+}
+
+impl IdempotencyStore {
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self {
+            conn,
+            last_purge: AtomicI64::new(0),
+        }
+    }
+
+    /// Take `key` for a new request until `lock_until` (unix seconds), or report who holds it.
+    /// An expired entry, finished or abandoned, no longer holds the key.
+    pub async fn claim(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        now: i64,
+        lock_until: i64,
+    ) -> anyhow::Result<Claim> {
+        let backend = self.conn.get_database_backend();
+
+        let expired = Query::delete()
+            .from_table(Keys::Table)
+            .and_where(Expr::col(Keys::Key).eq(key))
+            .and_where(Expr::col(Keys::ExpiresAt).lte(now))
+            .to_owned();
+        self.conn.execute(backend.build(&expired)).await?;
+
+        let insert = Query::insert()
+            .into_table(Keys::Table)
+            .columns([Keys::Key, Keys::Fingerprint, Keys::ExpiresAt])
+            .values_panic([key.into(), fingerprint.into(), lock_until.into()])
+            .on_conflict(OnConflict::column(Keys::Key).do_nothing().to_owned())
+            .to_owned();
+        if self
+            .conn
+            .execute(backend.build(&insert))
+            .await?
+            .rows_affected()
+            == 1
+        {
+            return Ok(Claim::Acquired(ClaimToken {
+                key: key.to_string(),
+                fingerprint: fingerprint.to_string(),
+                lock_until,
+            }));
+        }
+
+        let select = Query::select()
+            .columns([Keys::Fingerprint, Keys::Status, Keys::Headers, Keys::Body])
+            .from(Keys::Table)
+            .and_where(Expr::col(Keys::Key).eq(key))
+            .to_owned();
+        let Some(row) = self.conn.query_one(backend.build(&select)).await? else {
+            // Released between the insert and the select: the holder is just finishing
+            return Ok(Claim::Held {
+                fingerprint: fingerprint.to_string(),
+                response: None,
+            });
+        };
+        let fingerprint: String = row.try_get("", &Keys::Fingerprint.to_string())?;
+        let status: Option<i16> = row.try_get("", &Keys::Status.to_string())?;
+        let response = match status {
+            Some(status) => {
+                let headers: Option<String> = row.try_get("", &Keys::Headers.to_string())?;
+                let body: Option<Vec<u8>> = row.try_get("", &Keys::Body.to_string())?;
+                Some(StoredResponse {
+                    status: status.try_into()?,
+                    headers: match headers {
+                        Some(h) => serde_json::from_str(&h)?,
+                        None => Vec::new(),
+                    },
+                    body: body.unwrap_or_default(),
+                })
+            }
+            None => None,
+        };
+        Ok(Claim::Held {
+            fingerprint,
+            response,
+        })
+    }
+
+    /// Store the response of the request holding `claim`; it is replayed until `expires_at`.
+    /// Does nothing if the claim was lost to another request after its lock expired.
+    pub async fn complete(
+        &self,
+        claim: &ClaimToken,
+        response: &StoredResponse,
+        expires_at: i64,
+    ) -> anyhow::Result<()> {
+        let status: i16 = response.status.try_into()?;
+        let stmt = Query::update()
+            .table(Keys::Table)
+            .values([
+                (Keys::Status, status.into()),
+                (
+                    Keys::Headers,
+                    serde_json::to_string(&response.headers)?.into(),
+                ),
+                (Keys::Body, response.body.clone().into()),
+                (Keys::ExpiresAt, expires_at.into()),
+            ])
+            .cond_where(held_by(claim))
+            .to_owned();
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+        Ok(())
+    }
+
+    /// Free the key held by `claim` without storing a response, so a retry runs the request
+    /// again. Does nothing if the claim was lost to another request after its lock expired.
+    pub async fn release(&self, claim: &ClaimToken) -> anyhow::Result<()> {
+        let stmt = Query::delete()
+            .from_table(Keys::Table)
+            .cond_where(held_by(claim))
+            .to_owned();
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+        Ok(())
+    }
+
+    /// Claim the next purge: true at most once per `PURGE_INTERVAL_SECS` across callers.
+    pub fn purge_due(&self, now: i64) -> bool {
+        let last = self.last_purge.load(Ordering::Relaxed);
+        now - last >= PURGE_INTERVAL_SECS
+            && self
+                .last_purge
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+    }
+
+    /// Delete expired keys; callers throttle with [`Self::purge_due`].
+    pub async fn purge_expired(&self, now: i64) -> anyhow::Result<()> {
+        let stmt = Query::delete()
+            .from_table(Keys::Table)
+            .and_where(Expr::col(Keys::ExpiresAt).lte(now))
+            .to_owned();
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+        Ok(())
+    }
+}
+
+/// Matches the key's entry only while it is still the running request of `claim`.
+fn held_by(claim: &ClaimToken) -> Condition {
+    Condition::all()
+        .add(Expr::col(Keys::Key).eq(claim.key.as_str()))
+        .add(Expr::col(Keys::Fingerprint).eq(claim.fingerprint.as_str()))
+        .add(Expr::col(Keys::ExpiresAt).eq(claim.lock_until))
+        .add(Expr::col(Keys::Status).is_null())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use modkit_db::{ConnectOpts, DbHandle};
+    use tempfile::TempDir;
+
+    const NOW: i64 = 1_000;
+
+    async fn setup(dir: &TempDir) -> IdempotencyStore {
+        let dsn = format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("idempotency.db").display()
+        );
+        let db = DbHandle::connect(&dsn, ConnectOpts::default())
+            .await
+            .unwrap();
+        let conn = db.sea_secure().conn().clone();
+        super::super::migrations::Migrator::up(&conn, None)
+            .await
+            .unwrap();
+        IdempotencyStore::new(conn)
+    }
+
+    fn created() -> StoredResponse {
+        StoredResponse {
+            status: 201,
+            headers: vec![("location".to_string(), "/users/1".to_string())],
+            body: b"{\"id\":1}".to_vec(),
+        }
+    }
+
+    async fn acquire(store: &IdempotencyStore, fingerprint: &str, now: i64) -> ClaimToken {
+        match store.claim("k1", fingerprint, now, now + 60).await.unwrap() {
+            Claim::Acquired(token) => token,
+            other => panic!("expected the key to be free, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_running_key_is_held_and_completed_response_is_replayed() {
+        let dir = TempDir::new().unwrap();
+        let store = setup(&dir).await;
+        let token = acquire(&store, "fp-a", NOW).await;
+
+        // In flight: the middleware answers 409 for the same payload, 422 for another one
+        match store.claim("k1", "fp-b", NOW, NOW + 60).await.unwrap() {
+            Claim::Held {
+                fingerprint,
+                response: None,
+            } => assert_eq!(fingerprint, "fp-a"),
+            other => panic!("expected a running holder, got {other:?}"),
+        }
+
+        store
+            .complete(&token, &created(), NOW + 3600)
+            .await
+            .unwrap();
+        match store.claim("k1", "fp-a", NOW + 1, NOW + 61).await.unwrap() {
+            Claim::Held {
+                fingerprint,
+                response: Some(stored),
+            } => {
+                assert_eq!(fingerprint, "fp-a");
+                assert_eq!(stored, created());
+            }
+            other => panic!("expected a stored response, got {other:?}"),
+        }
+
+        // Replayed until it expires, then the key is free again
+        acquire(&store, "fp-a", NOW + 3600).await;
+    }
+
+    #[tokio::test]
+    async fn test_released_key_can_be_claimed_again() {
+        let dir = TempDir::new().unwrap();
+        let store = setup(&dir).await;
+        let token = acquire(&store, "fp-a", NOW).await;
+        store.release(&token).await.unwrap();
+        acquire(&store, "fp-a", NOW).await;
+    }
+
+    #[tokio::test]
+    async fn test_expired_holder_cannot_touch_the_next_claim() {
+        let dir = TempDir::new().unwrap();
+        let store = setup(&dir).await;
+        let stale = acquire(&store, "fp-a", NOW).await;
+
+        // The first request overran its lock and a retry took the key over
+        let current = acquire(&store, "fp-a", NOW + 60).await;
+        assert_ne!(stale, current);
+
+        store
+            .complete(&stale, &created(), NOW + 3600)
+            .await
+            .unwrap();
+        store.release(&stale).await.unwrap();
+        assert!(matches!(
+            store
+                .claim("k1", "fp-a", NOW + 61, NOW + 121)
+                .await
+                .unwrap(),
+            Claim::Held { response: None, .. }
+        ));
+
+        store
+            .complete(&current, &created(), NOW + 3600)
+            .await
+            .unwrap();
+        assert!(matches!(
+            store
+                .claim("k1", "fp-a", NOW + 61, NOW + 121)
+                .await
+                .unwrap(),
+            Claim::Held {
+                response: Some(_),
+                ..
+            }
+        ));
+    }
+}
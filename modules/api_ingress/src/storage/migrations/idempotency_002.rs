@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiIngressIdempotencyKeys::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiIngressIdempotencyKeys::Key)
+                            .string_len(64)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiIngressIdempotencyKeys::Fingerprint)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApiIngressIdempotencyKeys::Status).small_integer())
+                    .col(ColumnDef::new(ApiIngressIdempotencyKeys::Headers).text())
+                    .col(ColumnDef::new(ApiIngressIdempotencyKeys::Body).blob())
+                    .col(
+                        ColumnDef::new(ApiIngressIdempotencyKeys::ExpiresAt)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_api_ingress_idempotency_keys_expires_at")
+                    .table(ApiIngressIdempotencyKeys::Table)
+                    .col(ApiIngressIdempotencyKeys::ExpiresAt)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ApiIngressIdempotencyKeys::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum ApiIngressIdempotencyKeys {
+    Table,
+    Key,
+    Fingerprint,
+    Status,
+    Headers,
+    Body,
+    ExpiresAt,
+}
@@ -0,0 +1,19 @@
+use sea_orm_migration::prelude::*;
+
+mod idempotency_002;
+mod initial_001;
+
+pub use idempotency_002::ApiIngressIdempotencyKeys;
+pub use initial_001::ApiIngressRateLimits;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(initial_001::Migration),
+            Box::new(idempotency_002::Migration),
+        ]
+    }
+}
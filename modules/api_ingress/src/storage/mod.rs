@@ -0,0 +1,6 @@
+pub mod idempotency;
+pub mod migrations;
+pub mod rate_limits;
+
+pub use idempotency::{Claim, ClaimToken, IdempotencyStore, StoredResponse};
+pub use rate_limits::RateLimitStore;
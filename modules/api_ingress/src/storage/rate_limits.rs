@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use sea_orm_migration::prelude::*;
+
+use super::migrations::ApiIngressRateLimits;
+
+/// Windows older than this are deleted by [`RateLimitStore::purge_stale`].
+const RETENTION_SECS: i64 = 3_600;
+
+/// How often a purge may run.
+const PURGE_INTERVAL_SECS: i64 = 60;
+
+/// `api_ingress_rate_limits` table access: one fixed-window hit counter per bucket,
+/// shared by every replica using the same database. The table is system-owned and not
+/// tenant-scoped.
+pub struct RateLimitStore {
+    conn: DatabaseConnection,
+    last_purge: AtomicI64,
+}
+
+impl RateLimitStore {
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self {
+            conn,
+            last_purge: AtomicI64::new(0),
+        }
+    }
+
+    /// Count one hit for `bucket` in the window starting at `window_start` (unix seconds)
+    /// and return the window's total. A newer window resets the counter.
+    pub async fn hit(&self, bucket: &str, window_start: i64) -> anyhow::Result<i64> {
+        let stmt = Query::insert()
+            .into_table(ApiIngressRateLimits::Table)
+            .columns([
+                ApiIngressRateLimits::Bucket,
+                ApiIngressRateLimits::WindowStart,
+                ApiIngressRateLimits::Hits,
+            ])
+            .values_panic([bucket.into(), window_start.into(), 1i64.into()])
+            .on_conflict(
+                OnConflict::column(ApiIngressRateLimits::Bucket)
+                    .values([
+                        (
+                            ApiIngressRateLimits::Hits,
+                            Expr::case(
+                                Expr::col((
+                                    ApiIngressRateLimits::Table,
+                                    ApiIngressRateLimits::WindowStart,
+                                ))
+                                .eq(window_start),
+                                Expr::col((
+                                    ApiIngressRateLimits::Table,
+                                    ApiIngressRateLimits::Hits,
+                                ))
+                                .add(1),
+                            )
+                            .finally(1)
+                            .into(),
+                        ),
+                        (ApiIngressRateLimits::WindowStart, window_start.into()),
+                    ])
+                    .to_owned(),
+            )
+            .returning_col(ApiIngressRateLimits::Hits)
+            .to_owned();
+
+        let backend = self.conn.get_database_backend();
+        let row = self
+            .conn
+            .query_one(backend.build(&stmt))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("rate limit upsert returned no row"))?;
+        Ok(row.try_get("", &ApiIngressRateLimits::Hits.to_string())?)
+    }
+
+    /// Claim the next purge: true at most once per `PURGE_INTERVAL_SECS` across callers.
+    pub fn purge_due(&self, now: i64) -> bool {
+        let last = self.last_purge.load(Ordering::Relaxed);
+        now - last >= PURGE_INTERVAL_SECS
+            && self
+                .last_purge
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+    }
+
+    /// Delete long-expired windows; callers throttle with [`Self::purge_due`].
+    pub async fn purge_stale(&self, now: i64) -> anyhow::Result<()> {
+        let stmt = Query::delete()
+            .from_table(ApiIngressRateLimits::Table)
+            .and_where(Expr::col(ApiIngressRateLimits::WindowStart).lt(now - RETENTION_SECS))
+            .to_owned();
+        let backend = self.conn.get_database_backend();
+        self.conn.execute(backend.build(&stmt)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_is_claimed_once_per_interval() {
+        let store = RateLimitStore::new(DatabaseConnection::Disconnected);
+        assert!(store.purge_due(1_000));
+        assert!(!store.purge_due(1_000));
+        assert!(!store.purge_due(1_000 + PURGE_INTERVAL_SECS - 1));
+        assert!(store.purge_due(1_000 + PURGE_INTERVAL_SECS));
+    }
+}
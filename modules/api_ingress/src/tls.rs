@@ -0,0 +1,460 @@
+//! Native TLS termination with certificate hot reload and an optional HTTP → HTTPS redirect.
+//!
+//! The active rustls `ServerConfig` lives behind an `ArcSwap`. A poller watches the cert, key and
+//! client CA files and swaps in a fresh config when they change; new handshakes pick it up while
+//! established connections keep the config they were accepted with.
+
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use axum::extract::Request;
+use axum::http::{header, uri::Authority, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::Router;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::TlsConfig;
+
+/// Upper bound for a client to complete the TLS handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handshaken connections waiting for the HTTP server to pick them up.
+const ACCEPT_BACKLOG: usize = 128;
+
+fn provider() -> Arc<rustls::crypto::CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// Build a rustls server config from the files referenced by `cfg`, advertising h2 and http/1.1.
+pub fn load_server_config(cfg: &TlsConfig) -> Result<ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(&cfg.cert_path)
+        .with_context(|| format!("failed to read TLS certificate '{}'", cfg.cert_path))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("invalid PEM in TLS certificate '{}'", cfg.cert_path))?;
+    if certs.is_empty() {
+        anyhow::bail!("no certificate found in '{}'", cfg.cert_path);
+    }
+    let key = PrivateKeyDer::from_pem_file(&cfg.key_path)
+        .with_context(|| format!("failed to read TLS private key '{}'", cfg.key_path))?;
+
+    let builder = ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .context("unsupported TLS protocol versions")?;
+    let builder = match &cfg.client_ca_path {
+        Some(ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for ca in CertificateDer::pem_file_iter(ca_path)
+                .with_context(|| format!("failed to read client CA '{ca_path}'"))?
+            {
+                roots
+                    .add(ca.with_context(|| format!("invalid PEM in client CA '{ca_path}'"))?)
+                    .with_context(|| format!("invalid client CA certificate in '{ca_path}'"))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider())
+                .build()
+                .context("failed to build client certificate verifier")?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .context("TLS certificate and private key do not match")?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Current TLS config, swapped atomically on reload.
+#[derive(Clone)]
+pub struct ReloadableTls {
+    current: Arc<ArcSwap<ServerConfig>>,
+}
+
+impl ReloadableTls {
+    pub fn new(cfg: &TlsConfig) -> Result<Self> {
+        Ok(Self {
+            current: Arc::new(ArcSwap::from_pointee(load_server_config(cfg)?)),
+        })
+    }
+
+    fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.load_full())
+    }
+
+    /// Poll the TLS files every `reload_interval_ms` and swap in a new config when they change.
+    ///
+    /// A failed reload (e.g. the key was rotated but not the certificate yet) keeps the previous
+    /// config and is retried on the next change.
+    pub fn spawn_reloader(&self, cfg: TlsConfig, cancel: CancellationToken) -> JoinHandle<()> {
+        let current = self.current.clone();
+        tokio::spawn(async move {
+            let interval = Duration::from_millis(cfg.reload_interval_ms.max(100));
+            let mut seen = fingerprint(&cfg);
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => return,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+                let now = fingerprint(&cfg);
+                if now == seen {
+                    continue;
+                }
+                seen = now;
+                match load_server_config(&cfg) {
+                    Ok(config) => {
+                        current.store(Arc::new(config));
+                        tracing::info!(cert_path = %cfg.cert_path, "TLS certificates reloaded");
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %format!("{e:#}"), "TLS reload failed; keeping previous certificates");
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn tls_files(cfg: &TlsConfig) -> Vec<PathBuf> {
+    [
+        Some(&cfg.cert_path),
+        Some(&cfg.key_path),
+        cfg.client_ca_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(PathBuf::from)
+    .collect()
+}
+
+/// Modification time and size of each TLS file; `None` while a file is missing.
+fn fingerprint(cfg: &TlsConfig) -> Vec<Option<(SystemTime, u64)>> {
+    tls_files(cfg).iter().map(|p| file_stamp(p)).collect()
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// `axum::serve` listener that terminates TLS.
+///
+/// Handshakes run on their own tasks so a slow client cannot stall accepting others.
+pub struct TlsListener {
+    rx: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+    accept_task: JoinHandle<()>,
+}
+
+impl TlsListener {
+    pub fn new(tcp: TcpListener, tls: ReloadableTls) -> io::Result<Self> {
+        let local_addr = tcp.local_addr()?;
+        let (tx, rx) = mpsc::channel(ACCEPT_BACKLOG);
+        let accept_task = tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match tcp.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "TCP accept failed");
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        continue;
+                    }
+                };
+                let acceptor = tls.acceptor();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(tls_stream)) => {
+                            let _ = tx.send((tls_stream, peer)).await;
+                        }
+                        Ok(Err(e)) => tracing::debug!(%peer, error = %e, "TLS handshake failed"),
+                        Err(_) => tracing::debug!(%peer, "TLS handshake timed out"),
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            rx,
+            local_addr,
+            accept_task,
+        })
+    }
+}
+
+impl Drop for TlsListener {
+    fn drop(&mut self) {
+        // Release the port once the server stops accepting
+        self.accept_task.abort();
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.rx.recv().await {
+            Some(conn) => conn,
+            // The accept task only ends when aborted by our own Drop
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Router that answers every request with a permanent redirect to the HTTPS listener.
+pub fn redirect_router(https_port: u16) -> Router {
+    Router::new().fallback(move |req: Request| redirect_to_https(https_port, req))
+}
+
+async fn redirect_to_https(https_port: u16, req: Request) -> Response {
+    let Some(authority) = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.parse::<Authority>().ok())
+    else {
+        return (StatusCode::BAD_REQUEST, "missing or invalid Host header").into_response();
+    };
+    let host = authority.host();
+    let target_authority = if https_port == 443 {
+        host.to_string()
+    } else {
+        format!("{host}:{https_port}")
+    };
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    Redirect::permanent(&format!("https://{target_authority}{path}")).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::serve::Listener;
+    use rcgen::{BasicConstraints, CertificateParams, CertifiedKey, IsCa, KeyPair};
+    use rustls::pki_types::{PrivatePkcs8KeyDer, ServerName};
+    use rustls::ClientConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::client::TlsStream as ClientTlsStream;
+    use tokio_rustls::TlsConnector;
+    use tower::ServiceExt;
+
+    struct TestCa {
+        cert: rcgen::Certificate,
+        key: KeyPair,
+    }
+
+    impl TestCa {
+        fn new() -> Self {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let cert = params.self_signed(&key).unwrap();
+            Self { cert, key }
+        }
+
+        fn issue(&self, name: &str) -> CertifiedKey {
+            let key_pair = KeyPair::generate().unwrap();
+            let cert = CertificateParams::new(vec![name.to_string()])
+                .unwrap()
+                .signed_by(&key_pair, &self.cert, &self.key)
+                .unwrap();
+            CertifiedKey { cert, key_pair }
+        }
+    }
+
+    /// Write the server identity to `dir`, overwriting any previous one.
+    fn write_identity(dir: &Path, id: &CertifiedKey) -> TlsConfig {
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, id.cert.pem()).unwrap();
+        std::fs::write(&key_path, id.key_pair.serialize_pem()).unwrap();
+        TlsConfig {
+            cert_path: cert_path.display().to_string(),
+            key_path: key_path.display().to_string(),
+            reload_interval_ms: 100,
+            ..Default::default()
+        }
+    }
+
+    /// Serve `hello` to every connection that completes the handshake.
+    async fn serve(tls: ReloadableTls) -> SocketAddr {
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut listener = TlsListener::new(tcp, tls).unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await;
+                let _ = stream.write_all(b"hello").await;
+                let _ = stream.shutdown().await;
+            }
+        });
+        addr
+    }
+
+    async fn connect(
+        addr: SocketAddr,
+        ca: &TestCa,
+        client: Option<&CertifiedKey>,
+    ) -> io::Result<ClientTlsStream<TcpStream>> {
+        let mut roots = RootCertStore::empty();
+        roots.add(ca.cert.der().clone()).unwrap();
+        let builder = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots);
+        let config = match client {
+            Some(id) => builder
+                .with_client_auth_cert(
+                    vec![id.cert.der().clone()],
+                    PrivatePkcs8KeyDer::from(id.key_pair.serialize_der()).into(),
+                )
+                .unwrap(),
+            None => builder.with_no_client_auth(),
+        };
+        let tcp = TcpStream::connect(addr).await?;
+        TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+    }
+
+    async fn greeting(
+        addr: SocketAddr,
+        ca: &TestCa,
+        client: Option<&CertifiedKey>,
+    ) -> io::Result<Vec<u8>> {
+        let mut stream = connect(addr, ca, client).await?;
+        let mut body = Vec::new();
+        stream.read_to_end(&mut body).await?;
+        Ok(body)
+    }
+
+    fn served_cert(stream: &ClientTlsStream<TcpStream>) -> CertificateDer<'static> {
+        stream.get_ref().1.peer_certificates().unwrap()[0].clone()
+    }
+
+    async fn redirect(https_port: u16, host: &str, uri: &str) -> Response {
+        redirect_router(https_port)
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header(header::HOST, host)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_redirect_keeps_path_and_query() {
+        let resp = redirect(8443, "example.com:8080", "/users?limit=5").await;
+        assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            resp.headers()[header::LOCATION],
+            "https://example.com:8443/users?limit=5"
+        );
+
+        let resp = redirect(443, "[::1]:80", "/").await;
+        assert_eq!(resp.headers()[header::LOCATION], "https://[::1]/");
+    }
+
+    #[test]
+    fn test_missing_files_are_reported() {
+        let cfg = TlsConfig {
+            cert_path: "/nonexistent/cert.pem".to_string(),
+            key_path: "/nonexistent/key.pem".to_string(),
+            ..Default::default()
+        };
+        let err = load_server_config(&cfg).unwrap_err();
+        assert!(format!("{err:#}").contains("/nonexistent/cert.pem"));
+        assert!(fingerprint(&cfg).iter().all(Option::is_none));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_through_listener() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = TestCa::new();
+        let id = ca.issue("localhost");
+        let cfg = write_identity(dir.path(), &id);
+        let addr = serve(ReloadableTls::new(&cfg).unwrap()).await;
+
+        let stream = connect(addr, &ca, None).await.unwrap();
+        assert_eq!(&served_cert(&stream), id.cert.der());
+        drop(stream);
+        assert_eq!(greeting(addr, &ca, None).await.unwrap(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_rewritten_certificate_is_served_after_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = TestCa::new();
+        let first = ca.issue("localhost");
+        let cfg = write_identity(dir.path(), &first);
+        let tls = ReloadableTls::new(&cfg).unwrap();
+        let cancel = CancellationToken::new();
+        let reloader = tls.spawn_reloader(cfg, cancel.clone());
+        let addr = serve(tls).await;
+
+        let stream = connect(addr, &ca, None).await.unwrap();
+        assert_eq!(&served_cert(&stream), first.cert.der());
+
+        let second = ca.issue("localhost");
+        write_identity(dir.path(), &second);
+        let mut served = served_cert(&stream);
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            served = served_cert(&connect(addr, &ca, None).await.unwrap());
+            if &served == second.cert.der() {
+                break;
+            }
+        }
+        assert_eq!(&served, second.cert.der());
+
+        cancel.cancel();
+        reloader.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate_is_required_with_client_ca() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = TestCa::new();
+        let mut cfg = write_identity(dir.path(), &ca.issue("localhost"));
+        let ca_path = dir.path().join("client-ca.pem");
+        std::fs::write(&ca_path, ca.cert.pem()).unwrap();
+        cfg.client_ca_path = Some(ca_path.display().to_string());
+        let addr = serve(ReloadableTls::new(&cfg).unwrap()).await;
+
+        // With TLS 1.3 the rejection may only surface on the first read
+        let anonymous = greeting(addr, &ca, None).await;
+        assert!(!matches!(anonymous, Ok(ref body) if body == b"hello"));
+
+        let stranger = TestCa::new().issue("client");
+        let untrusted = greeting(addr, &ca, Some(&stranger)).await;
+        assert!(!matches!(untrusted, Ok(ref body) if body == b"hello"));
+
+        let client = ca.issue("client");
+        assert_eq!(greeting(addr, &ca, Some(&client)).await.unwrap(), b"hello");
+    }
+}
@@ -63,3 +63,24 @@ pub async fn serve_docs() -> Html<&'static str> {
 </html>"#,
     )
 }
+
+/// AsyncAPI viewer for `/asyncapi.json`; always loaded from the CDN, the embedded assets only
+/// cover the OpenAPI docs.
+pub async fn serve_asyncapi_docs() -> Html<&'static str> {
+    Html(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8"/>
+  <title>Event Docs</title>
+  <script src="https://unpkg.com/@asyncapi/web-component@latest/lib/asyncapi-web-component.js" defer></script>
+</head>
+<body>
+  <asyncapi-component
+    schemaUrl="/asyncapi.json"
+    cssImportPath="https://unpkg.com/@asyncapi/react-component@latest/styles/default.min.css">
+  </asyncapi-component>
+</body>
+</html>"#,
+    )
+}
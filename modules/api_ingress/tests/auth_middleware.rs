@@ -457,3 +457,36 @@ async fn test_route_pattern_matching_with_path_params() {
         "Route with different path parameter value should also be accessible"
     );
 }
+
+#[tokio::test]
+async fn test_admin_api_without_auth_needs_a_loopback_listener() {
+    async fn finalize(admin: serde_json::Value) -> Result<Router> {
+        let api_ctx = create_api_ingress_ctx(json!({
+            "api_ingress": {
+                "config": {
+                    "bind_addr": "0.0.0.0:8080",
+                    "auth_disabled": true,
+                    "admin": admin,
+                }
+            }
+        }));
+        let api_ingress = api_ingress::ApiIngress::default();
+        api_ingress.init(&api_ctx).await?;
+        api_ingress.rest_finalize(&api_ctx, Router::new())
+    }
+
+    let err = finalize(json!({ "enabled": true })).await.unwrap_err();
+    assert!(err.to_string().contains("loopback"), "{err}");
+    assert!(
+        finalize(json!({ "enabled": true, "bind_addr": "0.0.0.0:8088" }))
+            .await
+            .is_err()
+    );
+
+    let _router = finalize(json!({ "enabled": true, "bind_addr": "127.0.0.1:8088" }))
+        .await
+        .expect("loopback admin listener");
+    let _router = finalize(json!({ "enabled": true, "bind_addr": "localhost:8088" }))
+        .await
+        .expect("localhost admin listener");
+}
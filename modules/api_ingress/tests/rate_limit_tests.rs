@@ -245,3 +245,50 @@ async fn test_rate_limit_metadata_stored() {
     let test_op = json.pointer("/paths/~1test/get");
     assert!(test_op.is_some(), "Test endpoint should be in OpenAPI");
 }
+
+#[tokio::test]
+async fn test_rate_limited_requests_are_counted_in_metrics() {
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    let config = serde_json::json!({
+        "bind_addr": "127.0.0.1:0",
+        "cors_enabled": false,
+        "auth_disabled": true
+    });
+
+    let api_ingress = api_ingress::ApiIngress::default();
+    let ctx = create_test_module_ctx_with_config(config);
+    api_ingress.init(&ctx).await.expect("Failed to init");
+
+    let router = api_ingress
+        .rest_prepare(&ctx, Router::new())
+        .expect("Failed to prepare router");
+    let router = RateLimitedModule
+        .register_rest(&ctx, router, &api_ingress)
+        .expect("Failed to register routes");
+    let router = api_ingress
+        .rest_finalize(&ctx, router)
+        .expect("Failed to finalize router");
+
+    let get = |uri: &str| {
+        http::Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let first = router.clone().oneshot(get("/limited")).await.unwrap();
+    assert_eq!(first.status(), http::StatusCode::OK);
+    let second = router.clone().oneshot(get("/limited")).await.unwrap();
+    assert_eq!(second.status(), http::StatusCode::TOO_MANY_REQUESTS);
+
+    let metrics = router.oneshot(get("/metrics")).await.unwrap();
+    let body = axum::body::to_bytes(metrics.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    assert!(
+        text.contains(r#"http_requests_total{method="GET",route="/limited",status="429"} 1"#),
+        "429 not counted:\n{text}"
+    );
+}
@@ -7,12 +7,12 @@ use async_trait::async_trait;
 use tonic::transport::Channel;
 
 use modkit::runtime::{Endpoint, ModuleName};
-use modkit::{DirectoryApi, ServiceInstanceInfo};
+use modkit::{DirectoryApi, LbStrategy, ServiceInstanceInfo};
 use modkit_transport_grpc::client::GrpcClientConfig;
 
 use crate::server::proto::directory::v1::{
-    directory_service_client::DirectoryServiceClient, ListInstancesRequest,
-    ResolveGrpcServiceRequest,
+    directory_service_client::DirectoryServiceClient, LbStrategy as ProtoLbStrategy,
+    ListInstancesRequest, ResolveGrpcServiceRequest,
 };
 
 /// gRPC client implementation of DirectoryApi
@@ -73,9 +73,28 @@ impl DirectoryGrpcClient {
 #[async_trait]
 impl DirectoryApi for DirectoryGrpcClient {
     async fn resolve_grpc_service(&self, service_name: &str) -> Result<Endpoint> {
+        self.resolve_grpc_service_with(service_name, &LbStrategy::RoundRobin)
+            .await
+    }
+
+    async fn resolve_grpc_service_with(
+        &self,
+        service_name: &str,
+        strategy: &LbStrategy,
+    ) -> Result<Endpoint> {
+        let (proto_strategy, strategy_arg) = match strategy {
+            LbStrategy::RoundRobin => (ProtoLbStrategy::RoundRobin, String::new()),
+            LbStrategy::LeastOutstanding => (ProtoLbStrategy::LeastOutstanding, String::new()),
+            LbStrategy::Weighted => (ProtoLbStrategy::Weighted, String::new()),
+            LbStrategy::VersionPinned(v) => (ProtoLbStrategy::VersionPinned, v.clone()),
+            LbStrategy::ConsistentHash(k) => (ProtoLbStrategy::ConsistentHash, k.clone()),
+        };
+
         let mut client = self.inner.clone();
         let request = tonic::Request::new(ResolveGrpcServiceRequest {
             service_name: service_name.to_string(),
+            strategy: proto_strategy as i32,
+            strategy_arg,
         });
 
         let response = client
@@ -3,7 +3,7 @@
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
-use modkit::DirectoryApi;
+use modkit::{DirectoryApi, LbStrategy};
 
 /// Generated protobuf types
 pub mod proto {
@@ -16,8 +16,8 @@ pub mod proto {
 
 use proto::directory::v1::{
     directory_service_server::{DirectoryService, DirectoryServiceServer},
-    InstanceInfo, ListInstancesRequest, ListInstancesResponse, ResolveGrpcServiceRequest,
-    ResolveGrpcServiceResponse,
+    InstanceInfo, LbStrategy as ProtoLbStrategy, ListInstancesRequest, ListInstancesResponse,
+    ResolveGrpcServiceRequest, ResolveGrpcServiceResponse,
 };
 
 // Export the service name constant for use by the module
@@ -41,11 +41,18 @@ impl DirectoryService for DirectoryServiceImpl {
         &self,
         request: Request<ResolveGrpcServiceRequest>,
     ) -> Result<Response<ResolveGrpcServiceResponse>, Status> {
-        let service_name = request.into_inner().service_name;
+        let req = request.into_inner();
+        let strategy = match req.strategy() {
+            ProtoLbStrategy::RoundRobin => LbStrategy::RoundRobin,
+            ProtoLbStrategy::LeastOutstanding => LbStrategy::LeastOutstanding,
+            ProtoLbStrategy::Weighted => LbStrategy::Weighted,
+            ProtoLbStrategy::VersionPinned => LbStrategy::VersionPinned(req.strategy_arg),
+            ProtoLbStrategy::ConsistentHash => LbStrategy::ConsistentHash(req.strategy_arg),
+        };
 
         let endpoint = self
             .api
-            .resolve_grpc_service(&service_name)
+            .resolve_grpc_service_with(&req.service_name, &strategy)
             .await
             .map_err(|e| Status::not_found(e.to_string()))?;
 
@@ -3,6 +3,10 @@ use crate::domain::service::FileParserService;
 use axum::{Extension, Router};
 use modkit::api::{OpenApiRegistry, OperationBuilder};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Uploads and remote downloads can take far longer than the ingress default to parse.
+const PARSE_TIMEOUT: Duration = Duration::from_secs(300);
 
 pub fn register_routes(
     mut router: Router,
@@ -51,6 +55,7 @@ pub fn register_routes(
     // POST /file-parser/upload - Upload and parse a file
     router = OperationBuilder::post("/file-parser/upload")
         .operation_id("file_parser.upload")
+        .timeout(PARSE_TIMEOUT)
         .summary("Upload and parse a file")
         .tag("File Parser")
         .require_auth("file_parser", "read")
@@ -80,6 +85,7 @@ pub fn register_routes(
     // POST /file-parser/parse-url - Parse a file from a URL
     router = OperationBuilder::post("/file-parser/parse-url")
         .operation_id("file_parser.parse_url")
+        .timeout(PARSE_TIMEOUT)
         .summary("Parse a file from a URL")
         .tag("File Parser")
         .require_auth("file_parser", "read")
@@ -117,6 +123,7 @@ pub fn register_routes(
     // POST /file-parser/upload/markdown - Upload and parse a file, streaming Markdown
     router = OperationBuilder::post("/file-parser/upload/markdown")
         .operation_id("file_parser.upload_markdown")
+        .timeout(PARSE_TIMEOUT)
         .summary("Upload and parse a file, streaming Markdown")
         .tag("File Parser")
         .require_auth("file_parser", "read")
@@ -130,6 +137,7 @@ pub fn register_routes(
     // POST /file-parser/parse-url/markdown - Parse a file from a URL and stream Markdown
     router = OperationBuilder::post("/file-parser/parse-url/markdown")
         .operation_id("file_parser.parse_url_markdown")
+        .timeout(PARSE_TIMEOUT)
         .summary("Parse a file from a URL and stream Markdown")
         .tag("File Parser")
         .require_auth("file_parser", "read")
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::contract::{JobInfo, JobRun};
+
+/// REST DTO for a scheduled job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobDto {
+    pub name: String,
+    /// Human-readable schedule, e.g. `cron(0 */5 * * * *)` or `every(10m)`
+    pub schedule: String,
+    pub singleton: bool,
+    pub paused: bool,
+    /// Whether a run is in progress on this node
+    pub running: bool,
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<JobRunDto>,
+}
+
+/// REST DTO for the job list
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobListDto {
+    pub jobs: Vec<JobDto>,
+}
+
+/// REST DTO for the last recorded run of a job
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobRunDto {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// `succeeded`, `failed` or `timed_out`
+    pub status: String,
+    pub error: Option<String>,
+}
+
+impl From<JobRun> for JobRunDto {
+    fn from(run: JobRun) -> Self {
+        Self {
+            started_at: run.started_at,
+            duration_ms: run.duration_ms,
+            status: run.status.as_str().to_string(),
+            error: run.error,
+        }
+    }
+}
+
+impl From<JobInfo> for JobDto {
+    fn from(info: JobInfo) -> Self {
+        Self {
+            name: info.name,
+            schedule: info.schedule,
+            singleton: info.singleton,
+            paused: info.paused,
+            running: info.running,
+            next_run: info.next_run,
+            last_run: info.last_run.map(Into::into),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use modkit::api::problem::Problem;
+
+use crate::contract::SchedulerError;
+use crate::errors::ErrorCode;
+
+/// Map scheduler errors to RFC9457 Problem using the catalog
+pub fn scheduler_error_to_problem(err: SchedulerError) -> Problem {
+    match err {
+        SchedulerError::NotFound(name) => {
+            ErrorCode::scheduler_job_not_found_v1().to_problem(format!("Job not found: {}", name))
+        }
+
+        SchedulerError::AlreadyRegistered(name) => ErrorCode::scheduler_job_already_registered_v1()
+            .to_problem(format!("Job already registered: {}", name)),
+
+        e @ SchedulerError::InvalidSchedule { .. } => {
+            ErrorCode::scheduler_job_invalid_schedule_v1().to_problem(e.to_string())
+        }
+
+        e @ SchedulerError::NotRunning => {
+            ErrorCode::scheduler_job_not_running_v1().to_problem(e.to_string())
+        }
+
+        e @ SchedulerError::Store(_) => {
+            // Log the store error but don't expose database details to the client
+            tracing::error!(error = %e, "Job store error occurred");
+            ErrorCode::scheduler_job_store_v1().to_problem("An internal job store error occurred")
+        }
+    }
+}
+
+/// Implement From<SchedulerError> for Problem so it works with ApiError
+impl From<SchedulerError> for Problem {
+    fn from(e: SchedulerError) -> Self {
+        scheduler_error_to_problem(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_errors_do_not_leak_details() {
+        let problem =
+            scheduler_error_to_problem(SchedulerError::Store("UNIQUE constraint failed".into()));
+        assert_eq!(problem.status, http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            problem.code,
+            "gts.hx.core.errors.err.v1~hx.scheduler.job.store.v1"
+        );
+        assert!(!problem.detail.contains("UNIQUE"));
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use axum::extract::{Extension, Path};
+use modkit::api::prelude::*;
+use modkit_auth::axum_ext::Authz;
+use tracing::{field::Empty, info};
+
+use crate::api::rest::dto::{JobDto, JobListDto};
+use crate::contract::SchedulerError;
+use crate::domain::service::Scheduler;
+
+type SchedulerResult<T> = ApiResult<T, SchedulerError>;
+type SchedulerApiError = ApiError<SchedulerError>;
+
+/// List all registered jobs
+#[tracing::instrument(skip(svc, _ctx), fields(request_id = Empty))]
+pub async fn list_jobs(
+    Authz(_ctx): Authz,
+    Extension(svc): Extension<Arc<Scheduler>>,
+) -> SchedulerResult<JsonBody<JobListDto>> {
+    let jobs = svc.list().await.into_iter().map(JobDto::from).collect();
+    Ok(Json(JobListDto { jobs }))
+}
+
+/// Get a single job
+#[tracing::instrument(skip(svc, _ctx), fields(job = %name, request_id = Empty))]
+pub async fn get_job(
+    Authz(_ctx): Authz,
+    Extension(svc): Extension<Arc<Scheduler>>,
+    Path(name): Path<String>,
+) -> SchedulerResult<JsonBody<JobDto>> {
+    let job = svc
+        .get(&name)
+        .await
+        .map_err(SchedulerApiError::from_domain)?;
+    Ok(Json(JobDto::from(job)))
+}
+
+/// Run a job now
+#[tracing::instrument(skip(svc, _ctx), fields(job = %name, request_id = Empty))]
+pub async fn trigger_job(
+    Authz(_ctx): Authz,
+    Extension(svc): Extension<Arc<Scheduler>>,
+    Path(name): Path<String>,
+) -> SchedulerResult<(StatusCode, JsonBody<JobDto>)> {
+    info!("Triggering job via REST");
+    svc.trigger(&name).map_err(SchedulerApiError::from_domain)?;
+    let job = svc
+        .get(&name)
+        .await
+        .map_err(SchedulerApiError::from_domain)?;
+    Ok((StatusCode::ACCEPTED, Json(JobDto::from(job))))
+}
+
+/// Pause scheduled runs of a job
+#[tracing::instrument(skip(svc, _ctx), fields(job = %name, request_id = Empty))]
+pub async fn pause_job(
+    Authz(_ctx): Authz,
+    Extension(svc): Extension<Arc<Scheduler>>,
+    Path(name): Path<String>,
+) -> SchedulerResult<JsonBody<JobDto>> {
+    let job = svc
+        .set_paused(&name, true)
+        .await
+        .map_err(SchedulerApiError::from_domain)?;
+    Ok(Json(JobDto::from(job)))
+}
+
+/// Resume scheduled runs of a job
+#[tracing::instrument(skip(svc, _ctx), fields(job = %name, request_id = Empty))]
+pub async fn resume_job(
+    Authz(_ctx): Authz,
+    Extension(svc): Extension<Arc<Scheduler>>,
+    Path(name): Path<String>,
+) -> SchedulerResult<JsonBody<JobDto>> {
+    let job = svc
+        .set_paused(&name, false)
+        .await
+        .map_err(SchedulerApiError::from_domain)?;
+    Ok(Json(JobDto::from(job)))
+}
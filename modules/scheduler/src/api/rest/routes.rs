@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use axum::{Extension, Router};
+use modkit::api::{OpenApiRegistry, OperationBuilder};
+
+use crate::api::rest::{dto, handlers};
+use crate::domain::service::Scheduler;
+
+pub fn register_routes(
+    mut router: Router,
+    openapi: &dyn OpenApiRegistry,
+    scheduler: Arc<Scheduler>,
+) -> anyhow::Result<Router> {
+    // GET /scheduler/jobs - List registered jobs
+    router = OperationBuilder::get("/scheduler/jobs")
+        .operation_id("scheduler.list_jobs")
+        .require_auth("scheduler", "read")
+        .summary("List scheduled jobs")
+        .description("List all registered jobs with their schedule, pause state and last run")
+        .tag("scheduler")
+        .handler(handlers::list_jobs)
+        .json_response_with_schema::<dto::JobListDto>(openapi, http::StatusCode::OK, "Jobs")
+        .error_401(openapi)
+        .error_403(openapi)
+        .error_500(openapi)
+        .register(router, openapi);
+
+    // GET /scheduler/jobs/{name} - Get a job
+    router = OperationBuilder::get("/scheduler/jobs/{name}")
+        .operation_id("scheduler.get_job")
+        .require_auth("scheduler", "read")
+        .summary("Get a scheduled job")
+        .tag("scheduler")
+        .path_param("name", "Job name")
+        .handler(handlers::get_job)
+        .json_response_with_schema::<dto::JobDto>(openapi, http::StatusCode::OK, "Job found")
+        .error_401(openapi)
+        .error_403(openapi)
+        .error_404(openapi)
+        .error_500(openapi)
+        .register(router, openapi);
+
+    // POST /scheduler/jobs/{name}/trigger - Run a job now
+    router = OperationBuilder::post("/scheduler/jobs/{name}/trigger")
+        .operation_id("scheduler.trigger_job")
+        .require_auth("scheduler", "admin")
+        .summary("Run a job now")
+        .description("Start a run immediately, regardless of schedule and pause state")
+        .tag("scheduler")
+        .path_param("name", "Job name")
+        .handler(handlers::trigger_job)
+        .json_response_with_schema::<dto::JobDto>(
+            openapi,
+            http::StatusCode::ACCEPTED,
+            "Run requested",
+        )
+        .error_401(openapi)
+        .error_403(openapi)
+        .error_404(openapi)
+        .error_500(openapi)
+        .register(router, openapi);
+
+    // POST /scheduler/jobs/{name}/pause - Pause scheduled runs
+    router = OperationBuilder::post("/scheduler/jobs/{name}/pause")
+        .operation_id("scheduler.pause_job")
+        .require_auth("scheduler", "admin")
+        .summary("Pause a job")
+        .tag("scheduler")
+        .path_param("name", "Job name")
+        .handler(handlers::pause_job)
+        .json_response_with_schema::<dto::JobDto>(openapi, http::StatusCode::OK, "Job paused")
+        .error_401(openapi)
+        .error_403(openapi)
+        .error_404(openapi)
+        .error_500(openapi)
+        .register(router, openapi);
+
+    // POST /scheduler/jobs/{name}/resume - Resume scheduled runs
+    router = OperationBuilder::post("/scheduler/jobs/{name}/resume")
+        .operation_id("scheduler.resume_job")
+        .require_auth("scheduler", "admin")
+        .summary("Resume a job")
+        .tag("scheduler")
+        .path_param("name", "Job name")
+        .handler(handlers::resume_job)
+        .json_response_with_schema::<dto::JobDto>(openapi, http::StatusCode::OK, "Job resumed")
+        .error_401(openapi)
+        .error_403(openapi)
+        .error_404(openapi)
+        .error_500(openapi)
+        .register(router, openapi);
+
+    router = router.layer(Extension(scheduler));
+
+    Ok(router)
+}
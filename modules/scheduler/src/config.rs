@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the scheduler module
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SchedulerConfig {
+    /// How long `stop` waits for each running job to observe cancellation.
+    #[serde(default = "default_stop_timeout", with = "humantime_serde")]
+    pub stop_timeout: Duration,
+    /// Tolerated clock difference between nodes when deduplicating singleton runs.
+    #[serde(default = "default_clock_skew", with = "humantime_serde")]
+    pub clock_skew: Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            stop_timeout: default_stop_timeout(),
+            clock_skew: default_clock_skew(),
+        }
+    }
+}
+
+fn default_stop_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_clock_skew() -> Duration {
+    Duration::from_secs(5)
+}
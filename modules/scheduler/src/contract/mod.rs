@@ -0,0 +1,203 @@
+//! Public contract of the scheduler module.
+//!
+//! Other modules obtain [`SchedulerApi`] from the ClientHub during `init` and register
+//! their jobs there; the scheduler (a system module) is initialized before them.
+//!
+//! ```ignore
+//! let scheduler = ctx.client_hub().get::<dyn SchedulerApi>()?;
+//! scheduler.register(
+//!     JobSpec::every("users_info.cleanup", Duration::from_secs(600)).singleton(),
+//!     job_fn(move |job| {
+//!         let svc = svc.clone();
+//!         async move { svc.cleanup(job.cancel).await }
+//!     }),
+//! )?;
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+/// When a job fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// Cron expression: 5 fields (minute resolution) or 6/7 fields (with seconds/year).
+    Cron(String),
+    /// Fixed interval, aligned to the Unix epoch so that all nodes fire at the same instants.
+    Interval(Duration),
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Schedule::Cron(expr) => write!(f, "cron({expr})"),
+            Schedule::Interval(d) => write!(f, "every({})", humantime::format_duration(*d)),
+        }
+    }
+}
+
+/// Declaration of a job.
+#[derive(Debug, Clone)]
+pub struct JobSpec {
+    /// Unique job name; prefix with the owning module, e.g. `users_info.cleanup`.
+    pub name: String,
+    pub schedule: Schedule,
+    /// Run on at most one node cluster-wide (requires a database for the scheduler).
+    pub singleton: bool,
+    /// Cancel the run if it takes longer than this.
+    pub timeout: Option<Duration>,
+}
+
+impl JobSpec {
+    pub fn cron(name: impl Into<String>, expr: impl Into<String>) -> Self {
+        Self::new(name, Schedule::Cron(expr.into()))
+    }
+
+    pub fn every(name: impl Into<String>, interval: Duration) -> Self {
+        Self::new(name, Schedule::Interval(interval))
+    }
+
+    pub fn new(name: impl Into<String>, schedule: Schedule) -> Self {
+        Self {
+            name: name.into(),
+            schedule,
+            singleton: false,
+            timeout: None,
+        }
+    }
+
+    pub fn singleton(mut self) -> Self {
+        self.singleton = true;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Per-run context handed to a job.
+#[derive(Debug, Clone)]
+pub struct JobContext {
+    pub job: String,
+    /// Fires on shutdown or when the run exceeds its timeout.
+    pub cancel: CancellationToken,
+    /// `true` when started through [`SchedulerApi::trigger`].
+    pub manual: bool,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+/// Job body.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn run(&self, ctx: JobContext) -> anyhow::Result<()>;
+}
+
+/// Adapter turning an async closure into a [`JobHandler`].
+pub struct FnJob<F>(F);
+
+#[async_trait]
+impl<F, Fut> JobHandler for FnJob<F>
+where
+    F: Fn(JobContext) -> Fut + Send + Sync,
+    Fut: Future<Output = anyhow::Result<()>> + Send,
+{
+    async fn run(&self, ctx: JobContext) -> anyhow::Result<()> {
+        (self.0)(ctx).await
+    }
+}
+
+/// Wrap an async closure as a job handler.
+pub fn job_fn<F, Fut>(f: F) -> Arc<dyn JobHandler>
+where
+    F: Fn(JobContext) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    Arc::new(FnJob(f))
+}
+
+/// Outcome of a single run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+    TimedOut,
+}
+
+impl RunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::Failed => "failed",
+            RunStatus::TimedOut => "timed_out",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "succeeded" => Some(RunStatus::Succeeded),
+            "failed" => Some(RunStatus::Failed),
+            "timed_out" => Some(RunStatus::TimedOut),
+            _ => None,
+        }
+    }
+}
+
+/// Last recorded run of a job (possibly on another node).
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRun {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub status: RunStatus,
+    pub error: Option<String>,
+}
+
+/// Snapshot of a registered job.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub name: String,
+    pub schedule: String,
+    pub singleton: bool,
+    pub paused: bool,
+    pub running: bool,
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<JobRun>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchedulerError {
+    #[error("job '{0}' not found")]
+    NotFound(String),
+    #[error("job '{0}' is already registered")]
+    AlreadyRegistered(String),
+    #[error("invalid schedule for job '{name}': {message}")]
+    InvalidSchedule { name: String, message: String },
+    #[error("scheduler is not running")]
+    NotRunning,
+    #[error("job store error: {0}")]
+    Store(String),
+}
+
+/// Scheduler client exposed through the ClientHub.
+#[async_trait]
+pub trait SchedulerApi: Send + Sync {
+    /// Register a job. Intended to be called from `Module::init`.
+    fn register(&self, spec: JobSpec, handler: Arc<dyn JobHandler>) -> Result<(), SchedulerError>;
+
+    async fn list(&self) -> Vec<JobInfo>;
+
+    async fn get(&self, name: &str) -> Result<JobInfo, SchedulerError>;
+
+    /// Run the job now, regardless of its schedule and pause state.
+    async fn trigger(&self, name: &str) -> Result<(), SchedulerError>;
+
+    /// Pause or resume scheduled runs (cluster-wide when the scheduler has a database).
+    async fn set_paused(&self, name: &str, paused: bool) -> Result<JobInfo, SchedulerError>;
+}
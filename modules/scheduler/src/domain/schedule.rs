@@ -0,0 +1,107 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::contract::{Schedule, SchedulerError};
+
+/// Validated schedule ready to compute fire times.
+#[derive(Debug, Clone)]
+pub enum ParsedSchedule {
+    Cron(Box<cron::Schedule>),
+    Interval(Duration),
+}
+
+impl ParsedSchedule {
+    pub fn parse(name: &str, schedule: &Schedule) -> Result<Self, SchedulerError> {
+        let invalid = |message: String| SchedulerError::InvalidSchedule {
+            name: name.to_string(),
+            message,
+        };
+        match schedule {
+            Schedule::Cron(expr) => {
+                // The `cron` crate wants seconds; accept classic 5-field expressions too.
+                let expr = if expr.split_whitespace().count() == 5 {
+                    format!("0 {expr}")
+                } else {
+                    expr.clone()
+                };
+                cron::Schedule::from_str(&expr)
+                    .map(|s| ParsedSchedule::Cron(Box::new(s)))
+                    .map_err(|e| invalid(e.to_string()))
+            }
+            Schedule::Interval(d) if d.is_zero() => Err(invalid("interval must be > 0".into())),
+            Schedule::Interval(d) => Ok(ParsedSchedule::Interval(*d)),
+        }
+    }
+
+    /// First fire time strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            ParsedSchedule::Cron(s) => s.after(&after).next(),
+            ParsedSchedule::Interval(d) => {
+                // Align to multiples of the interval since the Unix epoch so that every node
+                // computes the same fire times.
+                let step = i64::try_from(d.as_millis()).unwrap_or(i64::MAX).max(1);
+                let next = after
+                    .timestamp_millis()
+                    .div_euclid(step)
+                    .checked_add(1)?
+                    .checked_mul(step)?;
+                Utc.timestamp_millis_opt(next).single()
+            }
+        }
+    }
+
+    /// Distance between the fire time `at` and the following one.
+    pub fn period_at(&self, at: DateTime<Utc>) -> Duration {
+        match self {
+            ParsedSchedule::Interval(d) => *d,
+            ParsedSchedule::Cron(_) => self
+                .next_after(at)
+                .and_then(|next| (next - at).to_std().ok())
+                .unwrap_or(Duration::MAX),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn five_field_cron_is_accepted() {
+        let s = ParsedSchedule::parse("j", &Schedule::Cron("*/5 * * * *".into())).unwrap();
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 10, 2, 30).unwrap();
+        assert_eq!(
+            s.next_after(at).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 10, 5, 0).unwrap()
+        );
+        assert_eq!(
+            s.period_at(s.next_after(at).unwrap()),
+            Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        assert!(ParsedSchedule::parse("j", &Schedule::Cron("nope".into())).is_err());
+        assert!(ParsedSchedule::parse("j", &Schedule::Interval(Duration::ZERO)).is_err());
+    }
+
+    #[test]
+    fn interval_schedule_is_epoch_aligned() {
+        let s = ParsedSchedule::parse("j", &Schedule::Interval(Duration::from_secs(60))).unwrap();
+        let at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 20).unwrap();
+        assert_eq!(
+            s.next_after(at).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 1, 0).unwrap()
+        );
+        // A fire time maps to the following slot, not to itself.
+        let slot = Utc.with_ymd_and_hms(2025, 1, 1, 0, 1, 0).unwrap();
+        assert_eq!(
+            s.next_after(slot).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 2, 0).unwrap()
+        );
+    }
+}
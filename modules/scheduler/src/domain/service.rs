@@ -0,0 +1,558 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use modkit::lifecycle::Lifecycle;
+use modkit_db::{DbHandle, LockConfig};
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::config::SchedulerConfig;
+use crate::contract::{
+    JobContext, JobHandler, JobInfo, JobRun, JobSpec, RunStatus, SchedulerError,
+};
+use crate::domain::schedule::ParsedSchedule;
+use crate::infra::storage::JobStore;
+
+/// Advisory-lock namespace for singleton jobs.
+const LOCK_NAMESPACE: &str = "scheduler";
+
+struct Job {
+    spec: JobSpec,
+    schedule: ParsedSchedule,
+    handler: Arc<dyn JobHandler>,
+    paused: AtomicBool,
+    running: AtomicBool,
+    trigger: Notify,
+    next_run: Mutex<Option<DateTime<Utc>>>,
+    last_run: Mutex<Option<JobRun>>,
+    lifecycle: Lifecycle,
+}
+
+/// Job registry and runner. Each job runs in its own [`Lifecycle`].
+pub struct Scheduler {
+    cfg: SchedulerConfig,
+    db: Option<Arc<DbHandle>>,
+    store: Option<JobStore>,
+    jobs: RwLock<BTreeMap<String, Arc<Job>>>,
+    root: Mutex<Option<CancellationToken>>,
+}
+
+impl Scheduler {
+    /// Without a database, job state is kept in memory only and singleton jobs run on
+    /// every node.
+    pub fn new(cfg: SchedulerConfig, db: Option<Arc<DbHandle>>) -> Self {
+        let store = db
+            .as_ref()
+            .map(|db| JobStore::new(db.sea_secure().conn().clone()));
+        Self {
+            cfg,
+            db,
+            store,
+            jobs: RwLock::new(BTreeMap::new()),
+            root: Mutex::new(None),
+        }
+    }
+
+    pub fn register(
+        self: &Arc<Self>,
+        spec: JobSpec,
+        handler: Arc<dyn JobHandler>,
+    ) -> Result<(), SchedulerError> {
+        let schedule = ParsedSchedule::parse(&spec.name, &spec.schedule)?;
+        if spec.singleton && self.db.is_none() {
+            warn!(job = %spec.name, "Scheduler has no database; singleton job will run on every node");
+        }
+
+        let job = Arc::new(Job {
+            schedule,
+            handler,
+            paused: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            trigger: Notify::new(),
+            next_run: Mutex::new(None),
+            last_run: Mutex::new(None),
+            lifecycle: Lifecycle::new_named("scheduler_job"),
+            spec,
+        });
+
+        {
+            let mut jobs = self.jobs.write();
+            if jobs.contains_key(&job.spec.name) {
+                return Err(SchedulerError::AlreadyRegistered(job.spec.name.clone()));
+            }
+            jobs.insert(job.spec.name.clone(), job.clone());
+        }
+        info!(job = %job.spec.name, schedule = %job.spec.schedule, singleton = job.spec.singleton, "Job registered");
+
+        // Late registrations start right away.
+        if let Some(root) = self.root.lock().clone() {
+            self.spawn(job, &root);
+        }
+        Ok(())
+    }
+
+    pub fn start(self: &Arc<Self>, cancel: CancellationToken) {
+        // Own a child so `stop` cancels the jobs without cancelling the caller's token.
+        let cancel = cancel.child_token();
+        *self.root.lock() = Some(cancel.clone());
+        let jobs: Vec<_> = self.jobs.read().values().cloned().collect();
+        info!(jobs = jobs.len(), "Scheduler started");
+        for job in jobs {
+            self.spawn(job, &cancel);
+        }
+    }
+
+    pub async fn stop(&self) {
+        if let Some(root) = self.root.lock().take() {
+            root.cancel();
+        }
+        let jobs: Vec<_> = self.jobs.read().values().cloned().collect();
+        for job in jobs {
+            if let Err(e) = job.lifecycle.stop(self.cfg.stop_timeout).await {
+                warn!(job = %job.spec.name, error = %e, "Job did not stop cleanly");
+            }
+        }
+        info!("Scheduler stopped");
+    }
+
+    pub async fn list(&self) -> Vec<JobInfo> {
+        let jobs: Vec<_> = self.jobs.read().values().cloned().collect();
+        let mut out = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            out.push(self.info_of(&job).await);
+        }
+        out
+    }
+
+    pub async fn get(&self, name: &str) -> Result<JobInfo, SchedulerError> {
+        let job = self.job(name)?;
+        Ok(self.info_of(&job).await)
+    }
+
+    pub fn trigger(&self, name: &str) -> Result<(), SchedulerError> {
+        let job = self.job(name)?;
+        if self.root.lock().is_none() {
+            return Err(SchedulerError::NotRunning);
+        }
+        info!(job = %name, "Job triggered manually");
+        job.trigger.notify_one();
+        Ok(())
+    }
+
+    pub async fn set_paused(&self, name: &str, paused: bool) -> Result<JobInfo, SchedulerError> {
+        let job = self.job(name)?;
+        if let Some(store) = &self.store {
+            // The row may not exist yet if the runner has not persisted the job.
+            store.ensure(name, &job.spec.schedule.to_string()).await?;
+            store.set_paused(name, paused).await?;
+        }
+        job.paused.store(paused, Ordering::Release);
+        info!(job = %name, paused, "Job pause state changed");
+        Ok(self.info_of(&job).await)
+    }
+
+    fn job(&self, name: &str) -> Result<Arc<Job>, SchedulerError> {
+        self.jobs
+            .read()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| SchedulerError::NotFound(name.to_string()))
+    }
+
+    /// Job snapshot; persisted state (other nodes' runs, cluster-wide pause) wins when present.
+    async fn info_of(&self, job: &Job) -> JobInfo {
+        self.refresh_from_store(job).await;
+        JobInfo {
+            name: job.spec.name.clone(),
+            schedule: job.spec.schedule.to_string(),
+            singleton: job.spec.singleton,
+            paused: job.paused.load(Ordering::Acquire),
+            running: job.running.load(Ordering::Acquire),
+            next_run: *job.next_run.lock(),
+            last_run: job.last_run.lock().clone(),
+        }
+    }
+
+    async fn refresh_from_store(&self, job: &Job) {
+        let Some(store) = &self.store else { return };
+        match store.load(&job.spec.name).await {
+            Ok(Some(state)) => {
+                job.paused.store(state.paused, Ordering::Release);
+                if state.last_run.is_some() {
+                    *job.last_run.lock() = state.last_run;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!(job = %job.spec.name, error = %e, "Failed to load job state"),
+        }
+    }
+
+    fn spawn(self: &Arc<Self>, job: Arc<Job>, root: &CancellationToken) {
+        let this = self.clone();
+        let runner = job.clone();
+        let res = job
+            .lifecycle
+            .start_with_token(root.child_token(), move |cancel| async move {
+                this.run_job(runner, cancel).await;
+                Ok(())
+            });
+        if let Err(e) = res {
+            warn!(job = %job.spec.name, error = %e, "Job runner already started");
+        }
+    }
+
+    async fn run_job(&self, job: Arc<Job>, cancel: CancellationToken) {
+        if let Some(store) = &self.store {
+            let schedule = job.spec.schedule.to_string();
+            if let Err(e) = store.ensure(&job.spec.name, &schedule).await {
+                warn!(job = %job.spec.name, error = %e, "Failed to persist job");
+            }
+        }
+        self.refresh_from_store(&job).await;
+
+        let mut after = Utc::now();
+        loop {
+            let next = job.schedule.next_after(after);
+            *job.next_run.lock() = next;
+
+            let manual = match next {
+                Some(at) => {
+                    let wait = (at - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+                    tokio::select! {
+                        _ = cancel.cancelled() => break,
+                        _ = tokio::time::sleep(wait) => false,
+                        _ = job.trigger.notified() => true,
+                    }
+                }
+                None => tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = job.trigger.notified() => true,
+                },
+            };
+
+            let scheduled_at = match (manual, next) {
+                (false, Some(at)) => at,
+                _ => Utc::now(),
+            };
+
+            if !manual {
+                self.refresh_from_store(&job).await;
+                if job.paused.load(Ordering::Acquire) {
+                    debug!(job = %job.spec.name, "Job paused; skipping run");
+                    after = scheduled_at;
+                    continue;
+                }
+            }
+
+            self.execute(&job, scheduled_at, manual, &cancel).await;
+
+            if !manual {
+                // Skip slots missed while the job was running.
+                after = scheduled_at.max(Utc::now());
+            }
+        }
+        *job.next_run.lock() = None;
+    }
+
+    async fn execute(
+        &self,
+        job: &Job,
+        scheduled_at: DateTime<Utc>,
+        manual: bool,
+        cancel: &CancellationToken,
+    ) {
+        let name = job.spec.name.as_str();
+
+        let guard = match (&self.db, job.spec.singleton) {
+            (Some(db), true) => {
+                let cfg = LockConfig {
+                    max_wait: None,
+                    max_attempts: Some(1),
+                    ..Default::default()
+                };
+                match db
+                    .try_lock(LOCK_NAMESPACE, &format!("job:{name}"), cfg)
+                    .await
+                {
+                    Ok(Some(guard)) => Some(guard),
+                    Ok(None) => {
+                        debug!(job = %name, "Singleton job is running on another node");
+                        return;
+                    }
+                    Err(e) => {
+                        warn!(job = %name, error = %e, "Failed to acquire singleton job lock");
+                        return;
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        if guard.is_some() && !manual && self.ran_elsewhere(job, scheduled_at).await {
+            debug!(job = %name, %scheduled_at, "Slot already served by another node");
+            if let Some(guard) = guard {
+                guard.release().await;
+            }
+            return;
+        }
+
+        job.running.store(true, Ordering::Release);
+        let token = cancel.child_token();
+        let ctx = JobContext {
+            job: name.to_string(),
+            cancel: token.clone(),
+            manual,
+            scheduled_at,
+        };
+
+        let started_at = Utc::now();
+        let t0 = Instant::now();
+        let (status, error) = match job.spec.timeout {
+            Some(limit) => match tokio::time::timeout(limit, job.handler.run(ctx)).await {
+                Ok(res) => outcome(res),
+                Err(_) => (
+                    RunStatus::TimedOut,
+                    Some(format!(
+                        "timed out after {}",
+                        humantime::format_duration(limit)
+                    )),
+                ),
+            },
+            None => outcome(job.handler.run(ctx).await),
+        };
+        token.cancel();
+        job.running.store(false, Ordering::Release);
+
+        let run = JobRun {
+            started_at,
+            duration_ms: u64::try_from(t0.elapsed().as_millis()).unwrap_or(u64::MAX),
+            status,
+            error,
+        };
+        match run.status {
+            RunStatus::Succeeded => {
+                info!(job = %name, manual, duration_ms = run.duration_ms, "Job finished")
+            }
+            _ => warn!(
+                job = %name,
+                manual,
+                duration_ms = run.duration_ms,
+                status = run.status.as_str(),
+                error = run.error.as_deref().unwrap_or(""),
+                "Job failed"
+            ),
+        }
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.record_run(name, &run).await {
+                warn!(job = %name, error = %e, "Failed to record job run");
+            }
+        }
+        *job.last_run.lock() = Some(run);
+
+        if let Some(guard) = guard {
+            guard.release().await;
+        }
+    }
+
+    /// Whether another node already started the run for this slot.
+    async fn ran_elsewhere(&self, job: &Job, scheduled_at: DateTime<Utc>) -> bool {
+        let Some(store) = &self.store else {
+            return false;
+        };
+        let Ok(Some(state)) = store.load(&job.spec.name).await else {
+            return false;
+        };
+        let Some(last) = state.last_run else {
+            return false;
+        };
+        let tolerance = self
+            .cfg
+            .clock_skew
+            .min(job.schedule.period_at(scheduled_at) / 2);
+        let tolerance = chrono::Duration::from_std(tolerance).unwrap_or_default();
+        last.started_at >= scheduled_at - tolerance
+    }
+}
+
+fn outcome(res: anyhow::Result<()>) -> (RunStatus, Option<String>) {
+    match res {
+        Ok(()) => (RunStatus::Succeeded, None),
+        Err(e) => (RunStatus::Failed, Some(format!("{e:#}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::job_fn;
+    use std::sync::atomic::AtomicUsize;
+
+    fn counting_job(counter: Arc<AtomicUsize>) -> Arc<dyn JobHandler> {
+        job_fn(move |_ctx| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn manual_trigger_runs_paused_job_and_records_last_run() {
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default(), None));
+        let counter = Arc::new(AtomicUsize::new(0));
+        scheduler
+            .register(
+                JobSpec::every("test.hourly", Duration::from_secs(3600)),
+                counting_job(counter.clone()),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            scheduler.trigger("test.hourly"),
+            Err(SchedulerError::NotRunning)
+        ));
+
+        let cancel = CancellationToken::new();
+        scheduler.start(cancel.clone());
+        scheduler.set_paused("test.hourly", true).await.unwrap();
+        scheduler.trigger("test.hourly").unwrap();
+
+        for _ in 0..100 {
+            if counter.load(Ordering::SeqCst) == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        let info = scheduler.get("test.hourly").await.unwrap();
+        assert!(info.paused);
+        assert!(info.next_run.is_some());
+        assert_eq!(info.last_run.unwrap().status, RunStatus::Succeeded);
+
+        scheduler.stop().await;
+    }
+
+    #[tokio::test]
+    async fn duplicate_and_invalid_jobs_are_rejected() {
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default(), None));
+        let counter = Arc::new(AtomicUsize::new(0));
+        let spec = JobSpec::cron("test.cron", "*/5 * * * *");
+        scheduler
+            .register(spec.clone(), counting_job(counter.clone()))
+            .unwrap();
+        assert!(matches!(
+            scheduler.register(spec, counting_job(counter.clone())),
+            Err(SchedulerError::AlreadyRegistered(_))
+        ));
+        assert!(matches!(
+            scheduler.register(JobSpec::cron("test.bad", "bogus"), counting_job(counter)),
+            Err(SchedulerError::InvalidSchedule { .. })
+        ));
+        assert!(matches!(
+            scheduler.get("missing").await,
+            Err(SchedulerError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn timed_out_runs_are_reported() {
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default(), None));
+        scheduler
+            .register(
+                JobSpec::every("test.slow", Duration::from_secs(3600))
+                    .timeout(Duration::from_millis(20)),
+                job_fn(|ctx| async move {
+                    ctx.cancel.cancelled().await;
+                    Ok(())
+                }),
+            )
+            .unwrap();
+        scheduler.start(CancellationToken::new());
+        scheduler.trigger("test.slow").unwrap();
+
+        let mut status = None;
+        for _ in 0..100 {
+            status = scheduler
+                .get("test.slow")
+                .await
+                .unwrap()
+                .last_run
+                .map(|r| r.status);
+            if status.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(status, Some(RunStatus::TimedOut));
+        scheduler.stop().await;
+    }
+
+    #[tokio::test]
+    async fn stop_does_not_cancel_the_callers_token() {
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default(), None));
+        let counter = Arc::new(AtomicUsize::new(0));
+        scheduler
+            .register(
+                JobSpec::every("test.hourly", Duration::from_secs(3600)),
+                counting_job(counter),
+            )
+            .unwrap();
+
+        let cancel = CancellationToken::new();
+        scheduler.start(cancel.clone());
+        scheduler.stop().await;
+        assert!(!cancel.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn pause_before_first_run_survives_job_persistence() {
+        use modkit_db::{ConnectOpts, DbHandle};
+        use sea_orm_migration::MigratorTrait;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dsn = format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("scheduler.db").display()
+        );
+        let db = Arc::new(
+            DbHandle::connect(&dsn, ConnectOpts::default())
+                .await
+                .unwrap(),
+        );
+        crate::infra::storage::migrations::Migrator::up(db.sea_secure().conn(), None)
+            .await
+            .unwrap();
+
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default(), Some(db)));
+        let counter = Arc::new(AtomicUsize::new(0));
+        scheduler
+            .register(
+                JobSpec::every("test.hourly", Duration::from_secs(3600)),
+                counting_job(counter),
+            )
+            .unwrap();
+        scheduler.set_paused("test.hourly", true).await.unwrap();
+
+        scheduler.start(CancellationToken::new());
+        let mut info = scheduler.get("test.hourly").await.unwrap();
+        for _ in 0..100 {
+            if info.next_run.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            info = scheduler.get("test.hourly").await.unwrap();
+        }
+        assert!(info.next_run.is_some());
+        assert!(info.paused);
+
+        scheduler.stop().await;
+    }
+}
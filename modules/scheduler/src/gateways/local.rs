@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::contract::{JobHandler, JobInfo, JobSpec, SchedulerApi, SchedulerError};
+use crate::domain::service::Scheduler;
+
+/// In-process implementation of [`SchedulerApi`] that delegates to the scheduler service.
+pub struct SchedulerLocalClient {
+    scheduler: Arc<Scheduler>,
+}
+
+impl SchedulerLocalClient {
+    pub fn new(scheduler: Arc<Scheduler>) -> Self {
+        Self { scheduler }
+    }
+}
+
+#[async_trait]
+impl SchedulerApi for SchedulerLocalClient {
+    fn register(&self, spec: JobSpec, handler: Arc<dyn JobHandler>) -> Result<(), SchedulerError> {
+        self.scheduler.register(spec, handler)
+    }
+
+    async fn list(&self) -> Vec<JobInfo> {
+        self.scheduler.list().await
+    }
+
+    async fn get(&self, name: &str) -> Result<JobInfo, SchedulerError> {
+        self.scheduler.get(name).await
+    }
+
+    async fn trigger(&self, name: &str) -> Result<(), SchedulerError> {
+        self.scheduler.trigger(name)
+    }
+
+    async fn set_paused(&self, name: &str, paused: bool) -> Result<JobInfo, SchedulerError> {
+        self.scheduler.set_paused(name, paused).await
+    }
+}
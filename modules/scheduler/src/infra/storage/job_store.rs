@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, QueryResult, StatementBuilder};
+use sea_orm_migration::prelude::*;
+
+use super::migrations::SchedulerJobs;
+use crate::contract::{JobRun, RunStatus, SchedulerError};
+
+/// Persisted state of a job, shared by all nodes using the same database.
+#[derive(Debug, Clone, Default)]
+pub struct StoredJobState {
+    pub paused: bool,
+    pub last_run: Option<JobRun>,
+    pub run_count: u64,
+    pub failure_count: u64,
+}
+
+/// `scheduler_jobs` table access. The table is system-owned and not tenant-scoped.
+pub struct JobStore {
+    conn: DatabaseConnection,
+}
+
+impl JobStore {
+    pub fn new(conn: DatabaseConnection) -> Self {
+        Self { conn }
+    }
+
+    /// Insert the job row if missing and refresh its schedule text.
+    pub async fn ensure(&self, name: &str, schedule: &str) -> Result<(), SchedulerError> {
+        let stmt = Query::insert()
+            .into_table(SchedulerJobs::Table)
+            .columns([SchedulerJobs::Name, SchedulerJobs::Schedule])
+            .values_panic([name.into(), schedule.into()])
+            .on_conflict(
+                OnConflict::column(SchedulerJobs::Name)
+                    .update_column(SchedulerJobs::Schedule)
+                    .to_owned(),
+            )
+            .to_owned();
+        self.exec(&stmt).await
+    }
+
+    pub async fn load(&self, name: &str) -> Result<Option<StoredJobState>, SchedulerError> {
+        let stmt = Query::select()
+            .columns([
+                SchedulerJobs::Paused,
+                SchedulerJobs::LastStartedAt,
+                SchedulerJobs::LastDurationMs,
+                SchedulerJobs::LastStatus,
+                SchedulerJobs::LastError,
+                SchedulerJobs::RunCount,
+                SchedulerJobs::FailureCount,
+            ])
+            .from(SchedulerJobs::Table)
+            .and_where(Expr::col(SchedulerJobs::Name).eq(name))
+            .to_owned();
+        let backend = self.conn.get_database_backend();
+        let row = self
+            .conn
+            .query_one(backend.build(&stmt))
+            .await
+            .map_err(store_err)?;
+        row.as_ref().map(decode).transpose()
+    }
+
+    pub async fn set_paused(&self, name: &str, paused: bool) -> Result<(), SchedulerError> {
+        let stmt = Query::update()
+            .table(SchedulerJobs::Table)
+            .value(SchedulerJobs::Paused, paused)
+            .and_where(Expr::col(SchedulerJobs::Name).eq(name))
+            .to_owned();
+        self.exec(&stmt).await
+    }
+
+    pub async fn record_run(&self, name: &str, run: &JobRun) -> Result<(), SchedulerError> {
+        let failed = i64::from(run.status != RunStatus::Succeeded);
+        let duration_ms: i64 = run.duration_ms.try_into().unwrap_or(i64::MAX);
+        let stmt = Query::update()
+            .table(SchedulerJobs::Table)
+            .value(SchedulerJobs::LastStartedAt, run.started_at)
+            .value(SchedulerJobs::LastDurationMs, duration_ms)
+            .value(SchedulerJobs::LastStatus, run.status.as_str())
+            .value(SchedulerJobs::LastError, run.error.clone())
+            .value(
+                SchedulerJobs::RunCount,
+                Expr::col(SchedulerJobs::RunCount).add(1),
+            )
+            .value(
+                SchedulerJobs::FailureCount,
+                Expr::col(SchedulerJobs::FailureCount).add(failed),
+            )
+            .and_where(Expr::col(SchedulerJobs::Name).eq(name))
+            .to_owned();
+        self.exec(&stmt).await
+    }
+
+    async fn exec<S: StatementBuilder>(&self, stmt: &S) -> Result<(), SchedulerError> {
+        let backend = self.conn.get_database_backend();
+        self.conn
+            .execute(backend.build(stmt))
+            .await
+            .map(|_| ())
+            .map_err(store_err)
+    }
+}
+
+fn decode(row: &QueryResult) -> Result<StoredJobState, SchedulerError> {
+    let col = |c: SchedulerJobs| c.to_string();
+    let started_at: Option<DateTime<Utc>> = row
+        .try_get("", &col(SchedulerJobs::LastStartedAt))
+        .map_err(store_err)?;
+    let status: Option<String> = row
+        .try_get("", &col(SchedulerJobs::LastStatus))
+        .map_err(store_err)?;
+    let duration: Option<i64> = row
+        .try_get("", &col(SchedulerJobs::LastDurationMs))
+        .map_err(store_err)?;
+
+    let last_run = match (started_at, status.as_deref().and_then(RunStatus::parse)) {
+        (Some(started_at), Some(status)) => Some(JobRun {
+            started_at,
+            duration_ms: duration.and_then(|d| d.try_into().ok()).unwrap_or(0),
+            status,
+            error: row
+                .try_get("", &col(SchedulerJobs::LastError))
+                .map_err(store_err)?,
+        }),
+        _ => None,
+    };
+
+    let count = |c: SchedulerJobs| -> Result<u64, SchedulerError> {
+        let v: i64 = row.try_get("", &col(c)).map_err(store_err)?;
+        Ok(v.try_into().unwrap_or(0))
+    };
+
+    Ok(StoredJobState {
+        paused: row
+            .try_get("", &col(SchedulerJobs::Paused))
+            .map_err(store_err)?,
+        last_run,
+        run_count: count(SchedulerJobs::RunCount)?,
+        failure_count: count(SchedulerJobs::FailureCount)?,
+    })
+}
+
+fn store_err(e: DbErr) -> SchedulerError {
+    SchedulerError::Store(e.to_string())
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SchedulerJobs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SchedulerJobs::Name)
+                            .string_len(255)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SchedulerJobs::Schedule).string().not_null())
+                    .col(
+                        ColumnDef::new(SchedulerJobs::Paused)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(SchedulerJobs::LastStartedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(SchedulerJobs::LastDurationMs).big_integer())
+                    .col(ColumnDef::new(SchedulerJobs::LastStatus).string_len(16))
+                    .col(ColumnDef::new(SchedulerJobs::LastError).text())
+                    .col(
+                        ColumnDef::new(SchedulerJobs::RunCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SchedulerJobs::FailureCount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SchedulerJobs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum SchedulerJobs {
+    Table,
+    Name,
+    Schedule,
+    Paused,
+    LastStartedAt,
+    LastDurationMs,
+    LastStatus,
+    LastError,
+    RunCount,
+    FailureCount,
+}
@@ -0,0 +1,4 @@
+pub mod job_store;
+pub mod migrations;
+
+pub use job_store::{JobStore, StoredJobState};
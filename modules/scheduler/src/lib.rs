@@ -0,0 +1,26 @@
+// === PUBLIC CONTRACT ===
+// Other modules register jobs through the SchedulerApi client.
+pub mod contract;
+pub use contract::{job_fn, JobContext, JobHandler, JobSpec, Schedule, SchedulerApi};
+
+// Generated error catalog from gts/errors.json
+pub mod errors;
+
+// === MODULE DEFINITION ===
+// ModKit needs access to the module struct for instantiation
+pub mod module;
+pub use module::SchedulerModule;
+
+// === INTERNAL MODULES ===
+// WARNING: These modules are internal implementation details!
+// They are exposed only for comprehensive testing and should NOT be used by external consumers.
+#[doc(hidden)]
+pub mod api;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod domain;
+#[doc(hidden)]
+pub mod gateways;
+#[doc(hidden)]
+pub mod infra;
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use modkit::api::OpenApiRegistry;
+use modkit::{DbModule, Module, ModuleCtx, RestfulModule, StatefulModule};
+use sea_orm_migration::MigratorTrait;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::api::rest::routes;
+use crate::config::SchedulerConfig;
+use crate::contract::SchedulerApi;
+use crate::domain::service::Scheduler;
+use crate::gateways::local::SchedulerLocalClient;
+
+/// Cluster-aware job scheduler. Runs as a system module so that regular modules can
+/// register jobs from their `init`.
+#[modkit::module(
+    name = "scheduler",
+    capabilities = [db, rest, stateful, system],
+    client = crate::contract::SchedulerApi
+)]
+pub struct SchedulerModule {
+    scheduler: arc_swap::ArcSwapOption<Scheduler>,
+}
+
+impl Default for SchedulerModule {
+    fn default() -> Self {
+        Self {
+            scheduler: arc_swap::ArcSwapOption::from(None),
+        }
+    }
+}
+
+impl Clone for SchedulerModule {
+    fn clone(&self) -> Self {
+        Self {
+            scheduler: arc_swap::ArcSwapOption::new(
+                self.scheduler.load().as_ref().map(|s| s.clone()),
+            ),
+        }
+    }
+}
+
+impl SchedulerModule {
+    fn scheduler(&self) -> anyhow::Result<Arc<Scheduler>> {
+        self.scheduler
+            .load()
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Scheduler not initialized"))
+    }
+}
+
+#[async_trait]
+impl Module for SchedulerModule {
+    async fn init(&self, ctx: &ModuleCtx) -> anyhow::Result<()> {
+        info!("Initializing scheduler module");
+
+        let cfg: SchedulerConfig = ctx.config()?;
+        debug!(
+            "Loaded scheduler config: stop_timeout={:?}, clock_skew={:?}",
+            cfg.stop_timeout, cfg.clock_skew
+        );
+
+        // Without a database, job state stays in memory and singleton jobs are not coordinated.
+        let db = ctx.db_optional();
+        if db.is_none() {
+            info!("Scheduler has no database; job state is kept in memory");
+        }
+
+        let scheduler = Arc::new(Scheduler::new(cfg, db));
+        self.scheduler.store(Some(scheduler.clone()));
+
+        let api: Arc<dyn SchedulerApi> = Arc::new(SchedulerLocalClient::new(scheduler));
+        expose_scheduler_client(ctx, &api)?;
+        info!("Scheduler API exposed to ClientHub");
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait]
+impl DbModule for SchedulerModule {
+    async fn migrate(&self, db: &modkit_db::DbHandle) -> anyhow::Result<()> {
+        info!("Running scheduler database migrations");
+        // scheduler_jobs is a system table, not tenant-scoped
+        let sec = db.sea_secure();
+        crate::infra::storage::migrations::Migrator::up(sec.conn(), None).await?;
+        info!("Scheduler database migrations completed successfully");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StatefulModule for SchedulerModule {
+    async fn start(&self, cancel: CancellationToken) -> anyhow::Result<()> {
+        self.scheduler()?.start(cancel);
+        Ok(())
+    }
+
+    async fn stop(&self, _cancel: CancellationToken) -> anyhow::Result<()> {
+        self.scheduler()?.stop().await;
+        Ok(())
+    }
+}
+
+impl RestfulModule for SchedulerModule {
+    fn register_rest(
+        &self,
+        _ctx: &ModuleCtx,
+        router: axum::Router,
+        openapi: &dyn OpenApiRegistry,
+    ) -> anyhow::Result<axum::Router> {
+        info!("Registering scheduler REST routes");
+        let router = routes::register_routes(router, openapi, self.scheduler()?)?;
+        info!("Scheduler REST routes registered successfully");
+        Ok(router)
+    }
+}